@@ -27,9 +27,23 @@ fn main() {
                 .help("File to dump")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("lang")
+                .help("Language of the file")
+                .short("l")
+                .long("lang")
+                .possible_values(&["c", "c23", "c++"])
+                .default_value("c++")
+                .takes_value(true),
+        )
         .get_matches();
 
     let file = matches.value_of("file").unwrap().to_string();
+    let lang = match matches.value_of("lang").unwrap() {
+        "c" => Language::C,
+        "c23" => Language::C23,
+        _ => Language::CPP,
+    };
 
     let source = source::get_source_mutex();
     let if_cache = Arc::new(IfCache::default());
@@ -39,12 +53,13 @@ fn main() {
         includes: vec![],
         current_dir: PathBuf::from("."),
         file: PathBuf::from(""),
-        lang: Language::CPP,
+        lang: lang.clone(),
     };
 
     let lexer = Lexer::<DefaultContext>::new_from_file(&file, source, if_cache, opt);
 
-    let context = Context::default();
+    let mut context = Context::default();
+    context.set_language(lang);
     let mut parser = UnitParser { lexer, context };
 
     match parser.parse() {
@@ -162,6 +162,10 @@ impl PreprocContext for StatsContext {
         self.default.save_switch(file, pos, next);
     }
 
+    fn strict_conditionals(&self) -> bool {
+        self.default.strict_conditionals()
+    }
+
     fn new_with_if_cache(if_cache: Arc<IfCache>) -> Self {
         Self {
             default: DefaultContext::new_with_if_cache(if_cache),
@@ -32,7 +32,9 @@ pub enum Macro {
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Language {
+    /// C11 or C17
     C,
+    C23,
     CPP,
 }
 
@@ -323,6 +325,15 @@ impl<'a> Args<'a> {
                         self.opt.includes.push(path.to_string());
                     }
                 }
+                b's' => {
+                    if let Some(std) = part.strip_prefix("-std=") {
+                        if self.opt.lang == Language::C
+                            && matches!(std, "c2x" | "c23" | "gnu2x" | "gnu23")
+                        {
+                            self.opt.lang = Language::C23;
+                        }
+                    }
+                }
                 b'U' => {
                     let undef = if part.len() > 2 {
                         &part[2..]
@@ -457,6 +468,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_args_std() {
+        let opt = Args::get_options(b"/usr/bin/clang-9 -std=gnu99 -c foo.c");
+        assert_eq!(opt.lang, Language::C);
+
+        let opt = Args::get_options(b"/usr/bin/clang-9 -std=c2x -c foo.c");
+        assert_eq!(opt.lang, Language::C23);
+
+        let opt = Args::get_options(b"/usr/bin/clang++-9 -std=c++17 -c foo.cpp");
+        assert_eq!(opt.lang, Language::CPP);
+    }
+
     #[test]
     fn test_args_include() {
         let cl = b"/usr/bin/clang-9 -std=gnu99 -o /foo/bar-oof/rab -include A -include B -i blah-blah -include C";
@@ -4,6 +4,8 @@
 // copied, modified, or distributed except according to those terms.
 
 use crate::args::Macro;
+use crate::lexer::preprocessor::context::DefaultContext;
+use crate::lexer::Lexer;
 
 macro_rules! defined {
     ( $lhs: expr, $rhs: expr) => {{
@@ -377,3 +379,147 @@ pub fn get_sys_paths() -> Vec<String> {
         "/usr/include".to_string(),
     ]
 }
+
+/// A macro to define in a context
+#[derive(Clone, Debug, PartialEq)]
+pub enum Predefined<'a> {
+    /// #define name value
+    Object(&'a str, &'a str),
+    /// #define name(params...) body
+    Function(&'a str, &'a [&'a str], &'a str),
+}
+
+/// Get a context where the macros are already defined (so usable in #if)
+pub fn get_context_with(macros: &[Predefined]) -> DefaultContext {
+    let mut buf = Vec::with_capacity(64 * macros.len());
+    for mac in macros {
+        buf.extend_from_slice(b"#define ");
+        match mac {
+            Predefined::Object(name, value) => {
+                buf.extend_from_slice(name.as_bytes());
+                buf.push(b' ');
+                buf.extend_from_slice(value.as_bytes());
+            }
+            Predefined::Function(name, params, body) => {
+                buf.extend_from_slice(name.as_bytes());
+                buf.push(b'(');
+                buf.extend_from_slice(params.join(", ").as_bytes());
+                buf.extend_from_slice(b") ");
+                buf.extend_from_slice(body.as_bytes());
+            }
+        }
+        buf.push(b'\n');
+    }
+
+    let mut lexer = Lexer::<DefaultContext>::new(&buf);
+    lexer.consume_all();
+    lexer.into_context()
+}
+
+/// The main macros predefined by gcc 11 for linux on x86_64
+pub fn gcc_x86_64() -> Vec<Predefined<'static>> {
+    vec![
+        Predefined::Object("__GNUC__", "11"),
+        Predefined::Object("__GNUC_MINOR__", "2"),
+        Predefined::Object("__GNUC_PATCHLEVEL__", "0"),
+        Predefined::Object("__CHAR_BIT__", "8"),
+        Predefined::Object("__SIZEOF_INT__", "4"),
+        Predefined::Object("__SIZEOF_LONG__", "8"),
+        Predefined::Object("__SIZEOF_POINTER__", "8"),
+        Predefined::Object("__ORDER_LITTLE_ENDIAN__", "1234"),
+        Predefined::Object("__BYTE_ORDER__", "__ORDER_LITTLE_ENDIAN__"),
+        Predefined::Object("_LP64", "1"),
+        Predefined::Object("__LP64__", "1"),
+        Predefined::Object("__ELF__", "1"),
+        Predefined::Object("__x86_64", "1"),
+        Predefined::Object("__x86_64__", "1"),
+        Predefined::Object("__amd64", "1"),
+        Predefined::Object("__amd64__", "1"),
+        Predefined::Object("__linux", "1"),
+        Predefined::Object("__linux__", "1"),
+        Predefined::Object("__gnu_linux__", "1"),
+        Predefined::Object("__unix", "1"),
+        Predefined::Object("__unix__", "1"),
+    ]
+}
+
+/// The main macros predefined by msvc for windows on x64
+pub fn msvc_x64() -> Vec<Predefined<'static>> {
+    vec![
+        Predefined::Object("_MSC_VER", "1929"),
+        Predefined::Object("_MSC_FULL_VER", "192930133"),
+        Predefined::Object("_WIN32", "1"),
+        Predefined::Object("_WIN64", "1"),
+        Predefined::Object("_M_X64", "100"),
+        Predefined::Object("_M_AMD64", "100"),
+        Predefined::Object("_INTEGRAL_MAX_BITS", "64"),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lexer::preprocessor::context::PreprocContext;
+    use crate::lexer::source::FileId;
+    use crate::lexer::{TLexer, Token};
+    use pretty_assertions::assert_eq;
+
+    fn get_ids(src: &[u8], context: DefaultContext) -> Vec<String> {
+        let mut lexer = Lexer::new_with_context(src, FileId(0), context);
+        let mut ids = Vec::new();
+        loop {
+            match lexer.next_useful() {
+                Token::Identifier(id) => ids.push(id),
+                Token::Eof => break,
+                _ => {}
+            }
+        }
+        ids
+    }
+
+    #[test]
+    fn test_context_with() {
+        let context = get_context_with(&[
+            Predefined::Object("__GNUC__", "11"),
+            Predefined::Function("MAX", &["a", "b"], "((a) > (b) ? (a) : (b))"),
+        ]);
+
+        assert_eq!(context.render_macro("__GNUC__").unwrap(), "__GNUC__ 11");
+        assert_eq!(
+            get_ids(
+                b"#if __GNUC__ >= 10 && MAX(__GNUC__, 3) == 11\nyes\n#else\nno\n#endif\n",
+                context
+            ),
+            vec!["yes"]
+        );
+    }
+
+    #[test]
+    fn test_presets() {
+        let src = b"#if defined(__x86_64__) && __GNUC__ >= 10\ngcc\n#elif defined(_WIN32)\nmsvc\n#endif\n";
+
+        assert_eq!(get_ids(src, get_context_with(&gcc_x86_64())), vec!["gcc"]);
+        assert_eq!(get_ids(src, get_context_with(&msvc_x64())), vec!["msvc"]);
+    }
+
+    #[test]
+    fn test_presets_has_operators() {
+        let src = concat!(
+            "#ifdef __has_include\n",
+            "has_include\n",
+            "#endif\n",
+            "#if __has_attribute(nodiscard)\n",
+            "has_nodiscard\n",
+            "#endif\n",
+            "#ifndef __has_builtin\n",
+            "no_has_builtin\n",
+            "#endif\n",
+        );
+
+        assert_eq!(
+            get_ids(src.as_bytes(), get_context_with(&gcc_x86_64())),
+            vec!["has_include", "has_nodiscard", "no_has_builtin"]
+        );
+    }
+}
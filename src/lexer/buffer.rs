@@ -10,6 +10,8 @@ use super::source::FileId;
 pub(crate) struct OutBuf {
     pub(crate) buf: Vec<u8>,
     pub(crate) last: Option<String>,
+    pub(crate) paste_avoidance: bool,
+    pub(crate) in_condition: bool,
 }
 
 impl Default for OutBuf {
@@ -17,6 +19,8 @@ impl Default for OutBuf {
         Self {
             buf: Vec::with_capacity(512),
             last: None,
+            paste_avoidance: true,
+            in_condition: false,
         }
     }
 }
@@ -28,6 +32,15 @@ impl OutBuf {
             self.buf.extend_from_slice(last.as_bytes());
         }
     }
+
+    /// Add a space around an expansion to avoid to paste it with its neighbours
+    /// (disabled when building a header name where only the original whites matter)
+    #[inline(always)]
+    pub(crate) fn pad(&mut self) {
+        if self.paste_avoidance && matches!(self.buf.last(), Some(c) if *c != b' ') {
+            self.buf.push(b' ');
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -47,6 +60,23 @@ impl Default for Position {
     }
 }
 
+impl Position {
+    /// The start of a buffer: a leading UTF-8 BOM is skipped but still counted
+    /// in the byte offsets, so columns are relative to the first character after it
+    fn start(buf: &[u8]) -> Self {
+        let pos = if buf.starts_with(b"\xEF\xBB\xBF") {
+            3
+        } else {
+            0
+        };
+        Self {
+            pos,
+            line: 1,
+            lpos: pos,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct BufferData {
     buf: Vec<u8>,
@@ -59,13 +89,17 @@ pub struct BufferData {
 impl BufferData {
     pub fn new(buf: Vec<u8>, source_id: FileId, path_index: PathIndex) -> Self {
         Self {
+            position: Position::start(&buf),
             buf,
-            position: Position::default(),
             source_id,
             path_index,
             fake_source_id: None,
         }
     }
+
+    pub(crate) fn get_source_id(&self) -> FileId {
+        self.source_id
+    }
 }
 
 #[derive(Clone, Debug, Default, Hash, PartialEq)]
@@ -83,6 +117,7 @@ pub(crate) struct Buffer<'a> {
     position: Position,
     saved_position: Position,
     saved_buf: &'a [u8],
+    tab_width: u32,
 }
 
 impl<'a> Buffer<'a> {
@@ -92,13 +127,14 @@ impl<'a> Buffer<'a> {
             preproc: OutBuf::default(),
             current: &[],
             len: buf.len(),
-            position: Position::default(),
+            position: Position::start(&buf),
             saved_position: Position::default(),
             saved_buf: &[],
+            tab_width: 1,
         };
         ret.stack.push(BufferData {
+            position: ret.position.clone(),
             buf,
-            position: Position::default(),
             source_id,
             fake_source_id: None,
             path_index,
@@ -140,7 +176,7 @@ impl<'a> Buffer<'a> {
         self.stack.push(buf);
         let last = self.stack.last().unwrap();
         self.current = unsafe { &*std::mem::transmute::<&[u8], *const [u8]>(&last.buf) };
-        self.position = Position::default();
+        self.position = last.position.clone();
         self.len = self.current.len()
     }
 
@@ -153,27 +189,49 @@ impl<'a> Buffer<'a> {
             return true;
         }
 
-        if self.stack.pop().is_none() {
-            // the stack is empty
+        // the main buffer is never removed in order to be able to restore a checkpoint
+        if self.stack.len() <= 1 {
             return false;
         }
+        self.stack.pop();
 
         while let Some(data) = self.stack.last() {
+            self.current = unsafe { &*std::mem::transmute::<&[u8], *const [u8]>(&data.buf) };
+            self.len = self.current.len();
+            self.position = data.position.clone();
             if data.position.pos < data.buf.len() {
-                self.current = unsafe { &*std::mem::transmute::<&[u8], *const [u8]>(&data.buf) };
-                self.len = self.current.len();
-                self.position = data.position.clone();
                 return true;
             }
+            if self.stack.len() == 1 {
+                return false;
+            }
             self.stack.pop();
         }
 
-        !self.stack.is_empty()
+        false
+    }
+
+    /// Check if we're in the main buffer: neither in an included file nor in a macro expansion
+    pub(crate) fn in_main_buffer(&self) -> bool {
+        self.stack.len() == 1 && !self.preproc_use()
+    }
+
+    /// Go back to a position in the main buffer: the included files and the expansions are dropped
+    pub(crate) fn restore_main(&mut self, pos: Position) {
+        self.preproc.buf.clear();
+        self.preproc.last = None;
+        self.stack.truncate(1);
+
+        let main: *const [u8] = self.stack[0].buf.as_slice();
+        self.current = unsafe { &*main };
+        self.len = self.current.len();
+        self.position = pos;
     }
 
+    /// Must be called once the '\n' has been consumed
     pub(crate) fn add_new_line(&mut self) {
         self.position.line += 1;
-        self.position.lpos = self.position.pos + 1;
+        self.position.lpos = self.position.pos;
     }
 
     pub(crate) fn get_line(&self) -> u32 {
@@ -190,6 +248,11 @@ impl<'a> Buffer<'a> {
             .map(|last| last.fake_source_id.unwrap_or(last.source_id))
     }
 
+    /// Get the id of the current file, ignoring the one set by #line
+    pub(crate) fn get_file_id(&self) -> Option<FileId> {
+        self.stack.last().map(|last| last.source_id)
+    }
+
     pub(crate) fn get_path_index(&self) -> Option<PathIndex> {
         self.stack.last().map(|last| last.path_index)
     }
@@ -199,8 +262,26 @@ impl<'a> Buffer<'a> {
         last.fake_source_id = Some(id);
     }
 
+    pub(crate) fn set_tab_width(&mut self, width: u32) {
+        self.tab_width = width.max(1);
+    }
+
     pub(crate) fn get_column(&self) -> u32 {
-        ((self.position.pos + 1) - self.position.lpos) as u32
+        let Position { pos, lpos, .. } = self.position;
+        if self.tab_width == 1 || lpos > pos {
+            return ((pos + 1) - lpos) as u32;
+        }
+
+        // a tab moves to the next tab stop
+        let width = self.tab_width;
+        let column = self.current[lpos..pos].iter().fold(0, |col, c| {
+            if *c == b'\t' {
+                (col / width + 1) * width
+            } else {
+                col + 1
+            }
+        });
+        column + 1
     }
 
     pub(crate) fn reset(&mut self) {
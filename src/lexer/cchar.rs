@@ -3,7 +3,8 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use super::lexer::{Lexer, Token};
+use super::errors::LexerError;
+use super::lexer::{Lexer, TLexer, Token};
 use super::preprocessor::context::PreprocContext;
 use super::string::StringType;
 
@@ -208,10 +209,13 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
         }
     }
 
+    /// Get the value of a character literal and the number of c-chars it contains:
+    /// a multi-char literal 'ab' has the value ('a' << 8) | 'b'
     #[inline(always)]
-    pub(crate) fn get_c_char_u32(&mut self) -> u32 {
+    pub(crate) fn get_c_char_u32(&mut self) -> (u32, usize) {
         // TODO: LiteralSupport.cpp
         let mut val: u32 = 0;
+        let mut n = 0;
         loop {
             if self.buf.has_char() {
                 let c = self.buf.next_char();
@@ -219,6 +223,7 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                     self.buf.inc();
                     if let Some(e) = self.get_escape() {
                         val = (val << Self::get_shift(e)) | e;
+                        n += 1;
                     }
                 } else if c == b'\'' {
                     self.buf.inc();
@@ -226,6 +231,10 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                 } else {
                     self.buf.inc();
                     val = (val << 8) | u32::from(c);
+                    // don't count the continuation bytes of an utf-8 char
+                    if c & 0xC0 != 0x80 {
+                        n += 1;
+                    }
                 }
             } else {
                 break;
@@ -233,7 +242,7 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
         }
 
         // TODO: try to improve that stuff and check that's valid
-        if val <= 0xFF {
+        let val = if val <= 0xFF {
             val
         } else {
             let bytes = val.to_be_bytes();
@@ -255,12 +264,38 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
             } else {
                 val
             }
+        };
+
+        (val, n)
+    }
+
+    /// Check that the char fits in its type: the value is truncated when it doesn't
+    fn check_char_range(&mut self, typ: &StringType, c: u32, n: usize) -> u32 {
+        let (max, mask) = match typ {
+            StringType::None => return c,
+            StringType::U8 => (0x7F, 0xFF),
+            StringType::U => (0xFFFF, 0xFFFF),
+            _ => (0x10_FFFF, u32::MAX),
+        };
+
+        // the literals in a macro expansion have already been checked where they're written
+        if !self.buf.preproc_use() {
+            if n > 1 {
+                self.errors
+                    .push(LexerError::CharTooLong { sp: self.span() });
+            } else if c > max || (0xD800..=0xDFFF).contains(&c) {
+                self.errors
+                    .push(LexerError::CharNotEncodable { sp: self.span() });
+            }
         }
+
+        c & mask
     }
 
     #[inline(always)]
     pub(super) fn get_char(&mut self, typ: StringType) -> Token {
-        let c = self.get_c_char_u32();
+        let (c, n) = self.get_c_char_u32();
+        let c = self.check_char_range(&typ, c, n);
         if let Some(suf) = self.get_suffix() {
             let c = Box::new((c, suf));
             match typ {
@@ -326,6 +361,28 @@ mod tests {
         assert_eq!(p.next_token(), Token::LiteralUUChar(u32::from('ڢ')));
     }
 
+    #[test]
+    fn test_multi_and_wide_char() {
+        let mut p = Lexer::<DefaultContext>::new(b"'ab' L'\\u00e9' U'\\U0001F600' u8'a'");
+        assert_eq!(p.next_token(), Token::LiteralChar(0x6162));
+        assert_eq!(p.next_token(), Token::LiteralLChar(0xe9));
+        assert_eq!(p.next_token(), Token::LiteralUUChar(0x1F600));
+        assert_eq!(p.next_token(), Token::LiteralU8Char(u32::from('a')));
+        assert!(p.errors.is_empty());
+
+        let mut p =
+            Lexer::<DefaultContext>::new("u'ab' u'\\U0001F600' u8'\u{e9}' U'\\uD800'".as_bytes());
+        assert_eq!(p.next_token(), Token::LiteralUChar(0x6162));
+        assert_eq!(p.next_token(), Token::LiteralUChar(0xF600));
+        assert_eq!(p.next_token(), Token::LiteralU8Char(0xe9));
+        assert_eq!(p.next_token(), Token::LiteralUUChar(0xD800));
+        assert!(matches!(p.errors[0], LexerError::CharTooLong { .. }));
+        assert!(p.errors[1..]
+            .iter()
+            .all(|e| matches!(e, LexerError::CharNotEncodable { .. })));
+        assert_eq!(p.errors.len(), 4);
+    }
+
     #[test]
     fn test_char_suffix() {
         let mut p = Lexer::<DefaultContext>::new(b"'a'_f u'b'_g U'c'_h u8'd'_i L'e'_j");
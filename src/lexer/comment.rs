@@ -3,10 +3,56 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use super::lexer::{Lexer, Token};
+use super::lexer::{Lexer, Location, Token};
 use super::preprocessor::context::PreprocContext;
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CommentKind {
+    /// // ...
+    Line,
+    /// /* ... */
+    Block,
+    /// /// ... or //! ...
+    DocLine,
+    /// /** ... */ or /*! ... */
+    DocBlock,
+}
+
+/// A comment emitted when the comments are retained:
+/// start and end are the bounds of the whole comment (delimiters included)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Comment {
+    pub kind: CommentKind,
+    pub start: Location,
+    pub end: Location,
+}
+
 impl<'a, PC: PreprocContext> Lexer<'a, PC> {
+    fn comment_token(&mut self, comment: &[u8], block: bool) -> Token {
+        if !self.retain_comments {
+            return Token::Comment;
+        }
+
+        let doc = if block {
+            (comment.starts_with(b"*") && !comment.iter().all(|c| *c == b'*'))
+                || comment.starts_with(b"!")
+        } else {
+            (comment.starts_with(b"/") && !comment.starts_with(b"//")) || comment.starts_with(b"!")
+        };
+        let kind = match (block, doc) {
+            (false, false) => CommentKind::Line,
+            (true, false) => CommentKind::Block,
+            (false, true) => CommentKind::DocLine,
+            (true, true) => CommentKind::DocBlock,
+        };
+
+        Token::RetainedComment(Box::new(Comment {
+            kind,
+            start: self.start,
+            end: self.location(),
+        }))
+    }
+
     pub(crate) fn get_multiline_comment(&mut self) -> Token {
         self.buf.inc();
         let spos = self.buf.pos();
@@ -20,10 +66,12 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                         let comment = self.buf.slice_m_n(spos, 1);
                         self.buf.inc();
                         self.comment = Some(comment);
-                        return Token::Comment;
+                        return self.comment_token(comment, true);
                     }
                 } else if c == b'\n' {
+                    self.buf.inc();
                     self.buf.add_new_line();
+                    continue;
                 }
                 self.buf.inc();
             } else {
@@ -33,7 +81,7 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
 
         let comment = self.buf.slice(spos);
         self.comment = Some(comment);
-        Token::Comment
+        self.comment_token(comment, true)
     }
 
     pub(crate) fn get_single_comment(&mut self) -> Token {
@@ -44,19 +92,17 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                 let c = self.buf.next_char();
                 self.buf.inc();
                 if c == b'\\' {
-                    self.buf.inc();
-                    if self.buf.has_char() {
-                        let c = self.buf.next_char();
-                        if c == b'\n' {
-                            self.buf.add_new_line();
-                        }
+                    if self.buf.has_char() && self.buf.next_char() == b'\n' {
+                        // continuation line
+                        self.buf.inc();
+                        self.buf.add_new_line();
                     }
                 } else if c == b'\n' {
                     //self.buf.add_new_line();
                     let comment = self.buf.slice_m_n(spos, 1);
                     self.buf.dec();
                     self.comment = Some(comment);
-                    return Token::Comment;
+                    return self.comment_token(comment, false);
                 }
             } else {
                 break;
@@ -65,7 +111,7 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
 
         let comment = self.buf.slice(spos);
         self.comment = Some(comment);
-        Token::Comment
+        self.comment_token(comment, false)
     }
 
     #[inline(always)]
@@ -81,7 +127,9 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                         break;
                     }
                 } else if c == b'\n' {
+                    self.buf.inc();
                     self.buf.add_new_line();
+                    continue;
                 }
                 self.buf.inc();
             } else {
@@ -100,11 +148,11 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                     break;
                 } else if c == b'\\' {
                     self.buf.inc();
-                    if self.buf.has_char() {
-                        let c = self.buf.next_char();
-                        if c == b'\n' {
-                            self.buf.add_new_line();
-                        }
+                    if self.buf.has_char() && self.buf.next_char() == b'\n' {
+                        // continuation line
+                        self.buf.inc();
+                        self.buf.add_new_line();
+                        continue;
                     }
                 }
                 self.buf.inc();
@@ -142,4 +190,35 @@ mod tests {
         assert_eq!(p.next_token(), Token::Comment);
         assert_eq!(p.get_comment().unwrap(), b"/ ");
     }
+
+    #[test]
+    fn test_retained_comments() {
+        let mut p = Lexer::<DefaultContext>::new(b"// a\n/* b */ /// c\nx /** d */ //! e\n/**/");
+        p.set_retain_comments(true);
+
+        let comments: Vec<_> = std::iter::repeat_with(|| p.next_token())
+            .take_while(|t| *t != Token::Eof)
+            .filter_map(|t| match t {
+                Token::RetainedComment(c) => Some((c.kind, c.start.pos, c.end.pos)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            comments,
+            vec![
+                (CommentKind::Line, 0, 4),
+                (CommentKind::Block, 5, 12),
+                (CommentKind::DocLine, 13, 18),
+                (CommentKind::DocBlock, 21, 29),
+                (CommentKind::DocLine, 30, 35),
+                (CommentKind::Block, 36, 40),
+            ]
+        );
+
+        // default mode
+        let mut p = Lexer::<DefaultContext>::new(b"/** d */");
+        assert_eq!(p.next_token(), Token::Comment);
+        assert_eq!(p.get_comment().unwrap(), b"* d ");
+    }
 }
@@ -10,6 +10,15 @@ pub enum LexerError {
     ErrorDirective { sp: Span, msg: String },
     EndifWithoutPreceedingIf { sp: Span },
     FileIncludeError { sp: Span, file: String },
+    MacroRedefinition { sp: Span, name: String },
+    ExtraTokens { sp: Span, directive: String },
+    InvalidLiteralSuffix { sp: Span, suffix: String },
+    DefinedFromExpansion { sp: Span },
+    CharTooLong { sp: Span },
+    CharNotEncodable { sp: Span },
+    UnterminatedConditional { sp: Span },
+    DiagnosticPopWithoutPush { sp: Span },
+    UnterminatedHeaderName { sp: Span, term: char },
 }
 
 impl LexerError {
@@ -23,6 +32,34 @@ impl LexerError {
             FileIncludeError { sp, file } => {
                 (*sp, format!("can't open file {} for inclusion", file))
             }
+            MacroRedefinition { sp, name } => (*sp, format!("macro {} redefined", name)),
+            ExtraTokens { sp, directive } => (
+                *sp,
+                format!("extra tokens at end of #{} directive", directive),
+            ),
+            InvalidLiteralSuffix { sp, suffix } => (
+                *sp,
+                format!("invalid suffix \"{}\" on numeric literal", suffix),
+            ),
+            DefinedFromExpansion { sp } => (
+                *sp,
+                "this use of \"defined\" may not be portable".to_owned(),
+            ),
+            CharTooLong { sp } => (*sp, "character constant too long for its type".to_owned()),
+            CharNotEncodable { sp } => (
+                *sp,
+                "character not encodable in a single code unit".to_owned(),
+            ),
+            UnterminatedConditional { sp } => {
+                (*sp, "unterminated conditional directive".to_owned())
+            }
+            DiagnosticPopWithoutPush { sp } => (
+                *sp,
+                "#pragma GCC diagnostic pop could not pop, no matching push".to_owned(),
+            ),
+            UnterminatedHeaderName { sp, term } => {
+                (*sp, format!("missing terminating {} character", term))
+            }
         };
         StringlyError { message, sp }
     }
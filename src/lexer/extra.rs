@@ -36,6 +36,10 @@ impl SavedLexer {
         self.toks.push(tok);
     }
 
+    pub fn append(&mut self, other: SavedLexer) {
+        self.toks.extend(other.toks);
+    }
+
     pub fn is_consumed(&self) -> bool {
         self.pos >= self.toks.len()
     }
@@ -5,17 +5,20 @@
 
 use hashbrown::HashMap;
 use lazy_static::lazy_static;
+use std::borrow::Cow;
 use std::fs::{self, File};
-use std::io::Read;
-use std::path::PathBuf;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use super::buffer::{Buffer, BufferData, Position};
+use super::comment::Comment;
 use super::errors::LexerError;
 use super::extra::SavedLexer;
 use super::preprocessor::cache::IfCache;
-use super::preprocessor::context::PreprocContext;
+use super::preprocessor::context::{IfState, PreprocContext};
 use super::preprocessor::include::PathIndex;
+use super::preprocessor::macros::Macro;
 use super::source::{FileId, SourceMutex};
 use super::string::StringType;
 use crate::args;
@@ -81,7 +84,7 @@ pub(super) const CHARS: [Kind; 256] = [
 ];
 
 macro_rules! mk_maps {
-    ( $name: ident, $conv_name: ident, $test_name: ident, $( $tok_val: expr, $tok_name: path ), *) => {
+    ( $name: ident, $conv_name: ident, $spell_name: ident, $test_name: ident, $( $tok_val: expr, $tok_name: path ), *) => {
         lazy_static! {
             static ref $name: HashMap<&'static str, Token> = {
                 use Token::*;
@@ -100,6 +103,14 @@ macro_rules! mk_maps {
             }
         }
 
+        pub(crate) fn $spell_name(tok: &Token) -> Option<&'static str> {
+            use Token::*;
+            match tok {
+                $($tok_name => Some($tok_val),)*
+                    _ => Option::None,
+            }
+        }
+
         #[allow(dead_code)]
         pub fn $test_name() -> Vec<(&'static str, Token)> {
             use Token::*;
@@ -112,7 +123,8 @@ macro_rules! mk_maps {
 }
 
 mk_maps! {
-    PREPROC_KEYWORDS, preproc_kw_to_str, test_preproc_kw,
+    PREPROC_KEYWORDS, preproc_kw_to_str, preproc_kw_spelling, test_preproc_kw,
+    "assert", PreprocAssert,
     "define", PreprocDefine,
     "elif", PreprocElif,
     "else", PreprocElse,
@@ -125,6 +137,7 @@ mk_maps! {
     "include_next", PreprocIncludeNext,
     "line", PreprocLine,
     "pragma", PreprocPragma,
+    "unassert", PreprocUnassert,
     "undef", PreprocUndef
 }
 
@@ -134,13 +147,18 @@ mk_maps! {
 // a, b, c, d, e, f, g, i, l, m, n, o, p, r, s, t, u, v, w, x and _
 // So if there is some change then need to fix next_token too
 mk_maps! {
-    CPP_KEYWORDS, cpp_kw_to_str, test_cpp_kw,
+    CPP_KEYWORDS, cpp_kw_to_str, cpp_kw_spelling, test_cpp_kw,
     "alignas", Alignas,
+    "_Alignas", CAlignas,
     "alignof", Alignof,
     "and", AndKw,
     "and_eq", AndEq,
     "asm", Asm,
+    "__asm", MSAsm,
+    "__asm__", GnuAsm,
+    "__attribute__", Attribute,
     "auto", Auto,
+    "__auto_type", GnuAutoType,
     "bitand", BitAnd,
     "bitor", BitOr,
     "bool", Bool,
@@ -200,8 +218,10 @@ mk_maps! {
     "namespace", Namespace,
     "new", New,
     "noexcept", Noexcept,
+    "_Noreturn", CNoreturn,
     "not", NotKw,
     "not_eq", NotEq,
+    "__null", GnuNull,
     "nullptr", Nullptr,
     "operator", Operator,
     "or", OrKw,
@@ -231,6 +251,7 @@ mk_maps! {
     "template", Template,
     "this", This,
     "__thiscall", Thiscall,
+    "__thread", GnuThread,
     "thread_local", ThreadLocal,
     "throw", Throw,
     "true", True,
@@ -248,6 +269,7 @@ mk_maps! {
     "virtual", Virtual,
     "void", Void,
     "volatile", Volatile,
+    "__volatile__", GnuVolatile,
     "wchar_t", WcharT,
     "while", While,
     "xor", XorKw,
@@ -262,6 +284,7 @@ pub enum Token {
     Eof,
     Eol,
     Comment,
+    RetainedComment(Box<Comment>),
     Not,
     NotEqual,
     Dollar,
@@ -308,6 +331,8 @@ pub enum Token {
     LiteralLongLong(u64),
     LiteralULong(u64),
     LiteralULongLong(u64),
+    LiteralSize(u64),
+    LiteralUSize(u64),
     LiteralIntUD(Box<(u64, String)>),
     LiteralString(String),
     LiteralLString(String),
@@ -329,6 +354,7 @@ pub enum Token {
     LiteralURStringUD(Box<(String, String)>),
     LiteralUURStringUD(Box<(String, String)>),
     LiteralU8RStringUD(Box<(String, String)>),
+    HeaderName(String),
     ColonColon,
     Colon,
     SemiColon,
@@ -360,11 +386,16 @@ pub enum Token {
     RightBrace,
     Tilde,
     Alignas,
+    CAlignas,
     Alignof,
     AndKw,
     AndEq,
     Asm,
+    MSAsm,
+    GnuAsm,
+    Attribute,
     Auto,
+    GnuAutoType,
     BitAnd,
     BitOr,
     Bool,
@@ -424,8 +455,10 @@ pub enum Token {
     Namespace,
     New,
     Noexcept,
+    CNoreturn,
     NotKw,
     NotEq,
+    GnuNull,
     Nullptr,
     Operator,
     OrKw,
@@ -453,6 +486,7 @@ pub enum Token {
     Template,
     This,
     Thiscall,
+    GnuThread,
     ThreadLocal,
     Throw,
     True,
@@ -467,6 +501,7 @@ pub enum Token {
     Virtual,
     Void,
     Volatile,
+    GnuVolatile,
     WcharT,
     While,
     XorKw,
@@ -484,6 +519,8 @@ pub enum Token {
     PreprocLine,
     PreprocPragma,
     PreprocUndef,
+    PreprocAssert,
+    PreprocUnassert,
     MSRestrict,
     MSUptr,
     MSSptr,
@@ -491,7 +528,7 @@ pub enum Token {
     MSUnaligned,
 }
 
-#[derive(Clone, Debug, Copy, Default)]
+#[derive(Clone, Debug, Copy, Default, PartialEq)]
 pub struct Location {
     pub pos: usize,
     pub line: u32,
@@ -560,12 +597,28 @@ pub trait TLexer {
     fn span(&self) -> Span;
 }
 
+/// The state needed to restart the lexing from a point in the main file.
+/// From the preprocessor context, only the if states and the value of __COUNTER__
+/// are saved: the macros (un)defined, the regions and the #pragma once files
+/// found after the checkpoint are kept when it's restored.
+#[derive(Clone, Debug)]
+pub struct LexerCheckpoint {
+    position: Position,
+    if_stack: Vec<IfState>,
+    counter: Option<u64>,
+    errors: usize,
+}
+
 pub struct Lexer<'a, PC: PreprocContext> {
     pub(crate) buf: Buffer<'a>,
     pub(crate) context: PC,
     pub(crate) comment: Option<&'a [u8]>,
+    pub(crate) pragma: Option<&'a [u8]>,
+    pub(crate) retain_comments: bool,
     pub(crate) start: Location,
     pub(crate) errors: Vec<LexerError>,
+    pub(crate) header_name: bool,
+    pub(crate) lang: args::Language,
 }
 
 impl<'a, PC: PreprocContext> TLexer for Lexer<'a, PC> {
@@ -574,7 +627,7 @@ impl<'a, PC: PreprocContext> TLexer for Lexer<'a, PC> {
             let tok = self.next_token();
             //eprintln!("{:?} -- {:?} -- {:?}", tok, self.span(), self.context.get_path(self.buf.get_source_id().unwrap()));
             match tok {
-                Token::Comment | Token::Eol => {}
+                Token::Comment | Token::RetainedComment(_) | Token::Eol => {}
                 _ => {
                     return tok;
                 }
@@ -646,8 +699,12 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
             buf: Buffer::new(buf.to_vec(), FileId(0), PathIndex(0)),
             context: PC::default(),
             comment: None,
+            pragma: None,
+            retain_comments: false,
             start: Location::dummy(),
             errors: Vec::new(),
+            header_name: false,
+            lang: args::Language::CPP,
         }
     }
 
@@ -656,11 +713,35 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
             buf: Buffer::new(buf.to_vec(), source_id, PathIndex(0)),
             context,
             comment: None,
+            pragma: None,
+            retain_comments: false,
             start: Location::dummy(),
             errors: Vec::new(),
+            header_name: false,
+            lang: args::Language::CPP,
         }
     }
 
+    /// Read the file and lex it: the lexer owns the bytes and the file is
+    /// registered in the source map of the context to get its id
+    pub fn from_path<P: AsRef<Path>>(path: P, mut context: PC) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let data = fs::read(&path)?;
+        let source_id = context.get_id(&path);
+
+        Ok(Self {
+            buf: Buffer::new(data, source_id, PathIndex(0)),
+            context,
+            comment: None,
+            pragma: None,
+            retain_comments: false,
+            start: Location::dummy(),
+            errors: Vec::new(),
+            header_name: false,
+            lang: args::Language::CPP,
+        })
+    }
+
     pub fn new_from_file(
         file: &str,
         source: SourceMutex,
@@ -729,19 +810,37 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
             buf: buffer,
             context,
             comment: None,
+            pragma: None,
+            retain_comments: false,
             start: Location::dummy(),
             errors: Vec::new(),
+            header_name: false,
+            lang: opt.lang,
         }
     }
 
+    /// Set the language: some keywords only exist in C or in C++ (e.g. _Noreturn or nullptr)
+    pub fn set_language(&mut self, lang: args::Language) {
+        self.lang = lang;
+    }
+
     pub fn get_comment(&self) -> &Option<&'a [u8]> {
         &self.comment
     }
 
+    /// When set, the comments are emitted as RetainedComment with their kind and bounds
+    pub fn set_retain_comments(&mut self, retain: bool) {
+        self.retain_comments = retain;
+    }
+
     pub fn get_context(&self) -> &PC {
         &self.context
     }
 
+    pub fn into_context(self) -> PC {
+        self.context
+    }
+
     pub fn get_errors(&self) -> &[LexerError] {
         &self.errors
     }
@@ -760,6 +859,38 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
         self.buf.get_line()
     }
 
+    /// Save the current state: None if we're in an included file or in a macro expansion
+    pub fn checkpoint(&self) -> Option<LexerCheckpoint> {
+        if !self.buf.in_main_buffer() {
+            return None;
+        }
+
+        let counter = match self.context.get("__COUNTER__") {
+            Some(Macro::Counter(counter)) => Some(counter.get()),
+            _ => None,
+        };
+
+        Some(LexerCheckpoint {
+            position: self.buf.raw_pos(),
+            if_stack: self.context.get_if_stack(),
+            counter,
+            errors: self.errors.len(),
+        })
+    }
+
+    /// Restart the lexing from a checkpoint
+    pub fn restore(&mut self, checkpoint: &LexerCheckpoint) {
+        self.buf.restore_main(checkpoint.position.clone());
+        self.context.set_if_stack(checkpoint.if_stack.clone());
+        if let (Some(value), Some(Macro::Counter(counter))) =
+            (checkpoint.counter, self.context.get("__COUNTER__"))
+        {
+            counter.set(value);
+        }
+        self.errors.truncate(checkpoint.errors);
+        self.comment = None;
+    }
+
     pub fn get_file(&self) -> PathBuf {
         self.context.get_path(self.buf.get_source_id().unwrap())
     }
@@ -768,6 +899,16 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
         self.buf.get_column()
     }
 
+    /// Set the number of columns for a tab (default is 1): use 8 to get the same columns as gcc
+    pub fn set_tab_width(&mut self, width: u32) {
+        self.buf.set_tab_width(width);
+    }
+
+    /// Check if the last token comes from a macro expansion
+    pub fn in_macro_expansion(&self) -> bool {
+        self.buf.preproc_use()
+    }
+
     pub fn debug(&self, msg: &str) {
         eprintln!(
             "DEBUG ({}): line {} in file ({:?}) {:?}",
@@ -779,8 +920,8 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
     }
 
     pub(crate) fn get_identifier(&mut self) -> Option<Token> {
-        let id = self.get_identifier_str();
-        if !self.buf.preproc_use() && self.macro_eval(id) {
+        let id = self.get_spliced_identifier_str();
+        if !self.buf.preproc_use() && self.macro_eval(&id) {
             self.buf.switch_to_preproc();
             None
         } else {
@@ -806,6 +947,32 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
         unsafe { std::str::from_utf8_unchecked(&self.buf.slice(spos)) }
     }
 
+    #[inline(always)]
+    fn is_splice_in_identifier(&self) -> bool {
+        self.buf.has_char_n(2)
+            && self.buf.next_char() == b'\\'
+            && self.buf.next_char_n(1) == b'\n'
+            && *unsafe { CHARS.get_unchecked(self.buf.next_char_n(2) as usize) } != Kind::NON
+    }
+
+    /// An identifier can be split by line continuations: fo\<newline>o is foo
+    pub(crate) fn get_spliced_identifier_str(&mut self) -> Cow<'a, str> {
+        let id = self.get_identifier_str();
+        if !self.is_splice_in_identifier() {
+            return Cow::Borrowed(id);
+        }
+
+        let mut id = id.to_string();
+        while self.is_splice_in_identifier() {
+            self.buf.inc_n(2);
+            self.buf.add_new_line();
+            self.buf.inc();
+            id.push_str(self.get_identifier_str());
+        }
+
+        Cow::Owned(id)
+    }
+
     pub(crate) fn get_preproc_keyword(&mut self, pos: Position) -> Token {
         let spos = self.buf.pos();
         loop {
@@ -858,32 +1025,50 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
     }
 
     pub(crate) fn get_identifier_or_keyword(&mut self) -> Option<Token> {
-        let spos = self.buf.pos() - 1;
-        loop {
-            if self.buf.has_char() {
-                let c = self.buf.next_char();
-                let kind = unsafe { CHARS.get_unchecked(c as usize) };
-                if *kind == Kind::NON {
-                    break;
-                }
-
-                self.buf.inc();
-            } else {
-                break;
-            }
-        }
-
-        let id = unsafe { std::str::from_utf8_unchecked(&self.buf.slice(spos)) };
-        if !self.buf.preproc_use() && self.macro_eval(id) {
+        let id = self.get_spliced_identifier_str();
+        if !self.buf.preproc_use() && self.macro_eval(&id) {
             self.buf.switch_to_preproc();
             None
-        } else if let Some(keyword) = CPP_KEYWORDS.get(id) {
+        } else if let Some(keyword) = CPP_KEYWORDS.get(&*id).filter(|k| self.is_keyword(k)) {
+            if *keyword == Token::Import {
+                // import <header>: the header name is lexed as in an #include
+                self.header_name = self.is_header_name_next();
+            }
             Some(keyword.clone())
         } else {
             Some(Token::Identifier(id.to_string()))
         }
     }
 
+    /// The C and C++ keywords are in the same map: check that the keyword exists in the language
+    fn is_keyword(&self, keyword: &Token) -> bool {
+        match keyword {
+            Token::Nullptr => self.lang != args::Language::C,
+            Token::CAlignas | Token::CNoreturn => self.lang != args::Language::CPP,
+            _ => true,
+        }
+    }
+
+    fn is_header_name_next(&self) -> bool {
+        let mut n = 0;
+        while self.buf.has_char_n(n) {
+            match self.buf.next_char_n(n) {
+                b' ' | b'\t' => n += 1,
+                c => return c == b'<',
+            }
+        }
+        false
+    }
+
+    pub(crate) fn get_header_name(&mut self) -> Token {
+        skip_whites!(self);
+        let spos = self.buf.pos();
+        let name = self
+            .get_include_content(b'>')
+            .unwrap_or_else(|| self.buf.slice(spos));
+        Token::HeaderName(String::from_utf8_lossy(name).to_string())
+    }
+
     pub(crate) fn get_exclamation(&mut self) -> Token {
         if self.buf.has_char() {
             let c = self.buf.next_char();
@@ -1068,8 +1253,8 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
             let c = self.buf.next_char();
             if c == b'\n' {
                 // continuation line
-                self.buf.add_new_line();
                 self.buf.inc();
+                self.buf.add_new_line();
                 return None;
             }
         }
@@ -1108,8 +1293,10 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
 
     pub fn next_token(&mut self) -> Token {
         loop {
+            // check_char can switch to another buffer so the start must be set after
+            let has_char = self.buf.check_char();
             self.start = self.location();
-            if self.buf.check_char() {
+            if has_char {
                 let c = self.buf.next_char();
                 self.buf.inc();
                 match c {
@@ -1178,6 +1365,10 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                         return Token::SemiColon;
                     }
                     b'<' => {
+                        if self.header_name {
+                            self.header_name = false;
+                            return self.get_header_name();
+                        }
                         return self.get_lower();
                     }
                     b'=' => {
@@ -1280,7 +1471,10 @@ mod tests {
 
     use super::*;
     use crate::lexer::preprocessor::context::DefaultContext;
+    use crate::lexer::preprocessor::include::IncludeLocator;
+    use crate::lexer::source::get_source_mutex;
     use pretty_assertions::assert_eq;
+    use tempdir::TempDir;
 
     #[test]
     fn test_keywords() {
@@ -1376,11 +1570,169 @@ mod tests {
         assert_eq!(p.next_token(), Token::LowerEqualGreater);
     }
 
+    #[test]
+    fn test_tab_width() {
+        for (width, columns) in &[(1, [2, 6, 7]), (8, [9, 17, 18])] {
+            let mut p = Lexer::<DefaultContext>::new(b"\tint\ta;");
+            p.set_tab_width(*width);
+
+            for (tok, column) in [
+                Token::Int,
+                Token::Identifier("a".to_string()),
+                Token::SemiColon,
+            ]
+            .iter()
+            .zip(columns.iter())
+            {
+                assert_eq!(&p.next_token(), tok);
+                assert_eq!(p.span().start.column, *column);
+            }
+        }
+    }
+
+    #[test]
+    fn test_continuation_column() {
+        let mut p = Lexer::<DefaultContext>::new(b"a \\\n  b \\\nc");
+        for (tok, line, column) in [("a", 1, 1), ("b", 2, 3), ("c", 3, 1)] {
+            assert_eq!(p.next_token(), Token::Identifier(tok.to_string()));
+            let sp = p.span();
+            assert_eq!((sp.start.line, sp.start.column), (line, column));
+        }
+    }
+
+    #[test]
+    fn test_utf8_bom() {
+        let src = "#define A x\nint A;";
+        let mut with_bom = b"\xEF\xBB\xBF".to_vec();
+        with_bom.extend_from_slice(src.as_bytes());
+
+        let mut p = Lexer::<DefaultContext>::new(src.as_bytes());
+        let mut q = Lexer::<DefaultContext>::new(&with_bom);
+        loop {
+            let tok = p.next_useful();
+            assert_eq!(q.next_useful(), tok);
+
+            let (sp, sq) = (p.span(), q.span());
+            assert_eq!(sp.start.line, sq.start.line);
+            assert_eq!(sp.start.column, sq.start.column);
+            if !q.in_macro_expansion() {
+                assert_eq!(sp.start.pos + 3, sq.start.pos);
+            }
+
+            if tok == Token::Eof {
+                break;
+            }
+        }
+        assert!(q.get_context().get("A").is_some());
+    }
+
+    #[test]
+    fn test_in_macro_expansion() {
+        let mut p = Lexer::<DefaultContext>::new(b"#define A x y\nb A c");
+        assert_eq!(p.next_useful(), Token::PreprocDefine);
+        assert_eq!(p.next_useful(), Token::Identifier("b".to_string()));
+        assert!(!p.in_macro_expansion());
+        assert_eq!(p.next_useful(), Token::Identifier("x".to_string()));
+        assert!(p.in_macro_expansion());
+        assert_eq!(p.next_useful(), Token::Identifier("y".to_string()));
+        assert!(p.in_macro_expansion());
+        assert_eq!(p.next_useful(), Token::Identifier("c".to_string()));
+        assert!(!p.in_macro_expansion());
+    }
+
     #[test]
     fn test_for_cpp_kw() {
         for (s, tok) in test_cpp_kw().drain(..) {
             let mut p = Lexer::<DefaultContext>::new(s.as_bytes());
+            if matches!(tok, Token::CAlignas | Token::CNoreturn) {
+                p.set_language(args::Language::C);
+            }
             assert_eq!(p.next_token(), tok);
         }
     }
+
+    #[test]
+    fn test_language_kw() {
+        let lex = |s: &str, lang: args::Language| {
+            let mut p = Lexer::<DefaultContext>::new(s.as_bytes());
+            p.set_language(lang);
+            p.next_token()
+        };
+
+        assert_eq!(
+            lex("nullptr", args::Language::C),
+            Token::Identifier("nullptr".to_string())
+        );
+        assert_eq!(lex("nullptr", args::Language::C23), Token::Nullptr);
+        assert_eq!(lex("nullptr", args::Language::CPP), Token::Nullptr);
+
+        assert_eq!(lex("_Noreturn", args::Language::C), Token::CNoreturn);
+        assert_eq!(lex("_Alignas", args::Language::C23), Token::CAlignas);
+        assert_eq!(
+            lex("_Noreturn", args::Language::CPP),
+            Token::Identifier("_Noreturn".to_string())
+        );
+        assert_eq!(
+            lex("_Alignas", args::Language::CPP),
+            Token::Identifier("_Alignas".to_string())
+        );
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        let mut p = Lexer::<DefaultContext>::new(
+            concat!(
+                "#define A 1\n",
+                "int a = __COUNTER__;\n",
+                "#if A\n",
+                "int b = __COUNTER__;\n",
+                "#else\n",
+                "int x;\n",
+                "#endif\n",
+                "int c = __COUNTER__ + A;\n",
+            )
+            .as_bytes(),
+        );
+
+        while p.next_token() != Token::Identifier("b".to_string()) {}
+        let checkpoint = p.checkpoint().unwrap();
+
+        let tail = |p: &mut Lexer<DefaultContext>| {
+            std::iter::repeat_with(|| p.next_token())
+                .take_while(|t| *t != Token::Eof)
+                .collect::<Vec<_>>()
+        };
+
+        let first = tail(&mut p);
+        assert!(first.contains(&Token::LiteralInt(2)));
+        assert!(!first.contains(&Token::Identifier("x".to_string())));
+
+        p.restore(&checkpoint);
+        assert_eq!(tail(&mut p), first);
+    }
+
+    #[test]
+    fn test_from_path() {
+        let tmp = TempDir::new("test").unwrap();
+        let file = tmp.path().join("foo.cpp");
+        std::fs::write(&file, "#define A 1\nint x = A;\n").unwrap();
+
+        let mut context = DefaultContext::default();
+        context.set_source(get_source_mutex());
+        let mut p = Lexer::from_path(&file, context).unwrap();
+
+        assert_eq!(p.next_useful(), Token::PreprocDefine);
+        assert_eq!(p.next_useful(), Token::Int);
+        let id = p.span().file.unwrap();
+        assert_ne!(id, FileId(0));
+        assert_eq!(p.context.get_path(id), file);
+
+        assert_eq!(p.next_useful(), Token::Identifier("x".to_string()));
+        assert_eq!(p.next_useful(), Token::Equal);
+        assert_eq!(p.next_useful(), Token::LiteralInt(1));
+        assert_eq!(p.next_useful(), Token::SemiColon);
+        assert_eq!(p.next_useful(), Token::Eof);
+
+        assert!(Lexer::from_path(tmp.path().join("bar.cpp"), DefaultContext::default()).is_err());
+    }
 }
@@ -14,6 +14,8 @@ pub mod source;
 
 mod cchar;
 mod comment;
+pub use self::comment::{Comment, CommentKind};
 mod number;
+mod spelling;
 mod string;
 mod tools;
@@ -7,7 +7,8 @@ use bitflags::bitflags;
 use hashbrown::HashMap;
 use lazy_static::lazy_static;
 
-use super::lexer::{self, Lexer, Token};
+use super::errors::LexerError;
+use super::lexer::{self, Lexer, TLexer, Token};
 use super::preprocessor::context::PreprocContext;
 
 const POW_P_10: [f64; 309] = [
@@ -164,6 +165,8 @@ enum IntType {
     UL,
     LL,
     ULL,
+    Z,
+    UZ,
     UserDefined(String),
 }
 
@@ -192,6 +195,17 @@ lazy_static! {
         map.insert("Ull", IntType::ULL);
         map.insert("uLL", IntType::ULL);
         map.insert("ULL", IntType::ULL);
+        // C++23
+        map.insert("z", IntType::Z);
+        map.insert("Z", IntType::Z);
+        map.insert("uz", IntType::UZ);
+        map.insert("uZ", IntType::UZ);
+        map.insert("Uz", IntType::UZ);
+        map.insert("UZ", IntType::UZ);
+        map.insert("zu", IntType::UZ);
+        map.insert("zU", IntType::UZ);
+        map.insert("Zu", IntType::UZ);
+        map.insert("ZU", IntType::UZ);
         map
     };
 }
@@ -214,6 +228,27 @@ lazy_static! {
     };
 }
 
+/// Check if a suffix which isn't a builtin one can be a user-defined one:
+/// the ones without a leading underscore are reserved for the standard library
+fn is_valid_ud_suffix(suf: &str, float: bool) -> bool {
+    if suf.starts_with('_') {
+        return true;
+    }
+
+    // chrono and complex literals
+    match suf {
+        "h" | "min" | "s" | "ms" | "us" | "ns" | "d" | "y" | "i" | "il" | "if" => true,
+        // C++23 extended floating-point types
+        "f16" | "f32" | "f64" | "f128" | "bf16" | "F16" | "F32" | "F64" | "F128" | "BF16" => float,
+        // MSVC sized integers
+        "i8" | "i16" | "i32" | "i64" | "ui8" | "ui16" | "ui32" | "ui64" | "I8" | "I16" | "I32"
+        | "I64" | "UI8" | "UI16" | "UI32" | "UI64" => !float,
+        // GNU __float128 and __float80
+        "q" | "Q" | "w" | "W" => float,
+        _ => false,
+    }
+}
+
 #[inline(always)]
 pub(crate) fn get_decimal(dec: u64, exp: i64) -> f64 {
     if exp == 0 {
@@ -508,6 +543,8 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                 IntType::UL => Token::LiteralULong(num),
                 IntType::LL => Token::LiteralLongLong(num),
                 IntType::ULL => Token::LiteralULongLong(num),
+                IntType::Z => Token::LiteralSize(num),
+                IntType::UZ => Token::LiteralUSize(num),
                 IntType::UserDefined(suf) => Token::LiteralIntUD(Box::new((num, suf))),
             }
         } else {
@@ -526,14 +563,23 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                 let id = self.get_identifier_str();
                 if let Some(suf) = INT_SUFFIXES.get(id) {
                     return Some(suf.clone());
-                } else {
+                } else if is_valid_ud_suffix(id, false) {
                     return Some(IntType::UserDefined(id.to_string()));
+                } else {
+                    self.invalid_suffix(id);
                 }
             }
         }
         None
     }
 
+    fn invalid_suffix(&mut self, suf: &str) {
+        self.errors.push(LexerError::InvalidLiteralSuffix {
+            sp: self.span(),
+            suffix: suf.to_string(),
+        });
+    }
+
     #[inline(always)]
     pub(crate) fn get_typed_float(&mut self, num: f64) -> Token {
         if self.buf.has_char() {
@@ -553,8 +599,11 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
             let id = self.get_identifier_str();
             if let Some(suf) = FLOAT_SUFFIXES.get(id) {
                 Some(suf.clone())
-            } else {
+            } else if is_valid_ud_suffix(id, true) {
                 Some(FloatType::UserDefined(id.to_string()))
+            } else {
+                self.invalid_suffix(id);
+                None
             }
         } else {
             None
@@ -564,7 +613,6 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
     #[inline(always)]
     pub(crate) fn get_typed_float_suf(&mut self, suf: u8, num: f64) -> Token {
         if let Some(suf) = self.get_float_type(suf) {
-            self.buf.inc();
             match suf {
                 FloatType::F => Token::LiteralFloat(num),
                 FloatType::L => Token::LiteralLongDouble(num),
@@ -602,6 +650,8 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                     self.buf.inc();
                     let (dec, exp) = self.get_number_after_dot(0);
                     return self.get_typed_float(get_decimal(dec, exp));
+                } else {
+                    return self.get_typed_int(0);
                 }
             } else {
                 let num = self.get_int(num);
@@ -837,6 +887,11 @@ mod tests {
         assert_eq!(p.next_token(), Token::LiteralDouble(0.));
         assert_eq!(p.next_token(), Token::LiteralDouble(0.));
 
+        let mut p = Lexer::<DefaultContext>::new(b"0u 0UL 0ll");
+        assert_eq!(p.next_token(), Token::LiteralUInt(0));
+        assert_eq!(p.next_token(), Token::LiteralULong(0));
+        assert_eq!(p.next_token(), Token::LiteralLongLong(0));
+
         let mut p = Lexer::<DefaultContext>::new(b"123 123u 123U 123llu 123LLu 123llU 123LLU 123ull 123Ull 123ULL 123lu 123ul 123uL 123L");
         assert_eq!(p.next_token(), Token::LiteralInt(123));
         assert_eq!(p.next_token(), Token::LiteralUInt(123));
@@ -899,4 +954,64 @@ mod tests {
         let mut p = Lexer::<DefaultContext>::new(b"0.123");
         assert_eq!(p.next_token(), Token::LiteralDouble(0.123));
     }
+
+    #[test]
+    fn test_number_suffix() {
+        let mut p = Lexer::<DefaultContext>::new(b"123ull 1.0f; 1z 2uz 10ms 1.5_km");
+        assert_eq!(p.next_token(), Token::LiteralULongLong(123));
+        assert_eq!(p.next_token(), Token::LiteralFloat(1.0));
+        assert_eq!(p.next_token(), Token::SemiColon);
+        assert_eq!(p.next_token(), Token::LiteralSize(1));
+        assert_eq!(p.next_token(), Token::LiteralUSize(2));
+        assert_eq!(
+            p.next_token(),
+            Token::LiteralIntUD(Box::new((10, "ms".to_string())))
+        );
+        assert_eq!(
+            p.next_token(),
+            Token::LiteralFloatUD(Box::new((1.5, "_km".to_string())))
+        );
+        assert!(p.errors.is_empty());
+    }
+
+    #[test]
+    fn test_number_vendor_suffix() {
+        let mut p = Lexer::<DefaultContext>::new(b"1i64 2ui32 1.0q 2.5W");
+        assert_eq!(
+            p.next_token(),
+            Token::LiteralIntUD(Box::new((1, "i64".to_string())))
+        );
+        assert_eq!(
+            p.next_token(),
+            Token::LiteralIntUD(Box::new((2, "ui32".to_string())))
+        );
+        assert_eq!(
+            p.next_token(),
+            Token::LiteralFloatUD(Box::new((1.0, "q".to_string())))
+        );
+        assert_eq!(
+            p.next_token(),
+            Token::LiteralFloatUD(Box::new((2.5, "W".to_string())))
+        );
+        assert!(p.errors.is_empty());
+    }
+
+    #[test]
+    fn test_number_invalid_suffix() {
+        let mut p = Lexer::<DefaultContext>::new(b"123lll 1.0fl 123fu");
+        assert_eq!(p.next_token(), Token::LiteralInt(123));
+        assert_eq!(p.next_token(), Token::LiteralDouble(1.0));
+        assert_eq!(p.next_token(), Token::LiteralInt(123));
+        assert_eq!(p.next_token(), Token::Eof);
+
+        let suffixes: Vec<_> = p
+            .errors
+            .iter()
+            .map(|e| match e {
+                LexerError::InvalidLiteralSuffix { suffix, .. } => suffix.as_str(),
+                _ => panic!("Unexpected error: {:?}", e),
+            })
+            .collect();
+        assert_eq!(suffixes, vec!["lll", "fl", "fu"]);
+    }
 }
@@ -4,7 +4,8 @@
 // copied, modified, or distributed except according to those terms.
 
 use super::context::PreprocContext;
-use crate::lexer::lexer::{Lexer, Token};
+use crate::lexer::errors::LexerError;
+use crate::lexer::lexer::{Lexer, TLexer, Token};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Operator {
@@ -137,11 +138,7 @@ impl Operator {
                 let b = stack.pop().unwrap();
                 let a = stack.pop().unwrap();
 
-                let cond = match a {
-                    Int::Unsigned(x) => x != 0,
-                    Int::Signed(x) => x != 0,
-                };
-                if cond {
+                if a.as_bool() {
                     stack.push(b);
                 } else {
                     stack.push(c);
@@ -171,6 +168,10 @@ pub(crate) enum Int {
     Signed(i64),
 }
 
+// The operations follow the intmax_t/uintmax_t semantics:
+// if one of the operands is unsigned then the other one is converted to unsigned.
+// Overflows wrap and a division by zero gives 0 since it can occur in a branch
+// which isn't evaluated (e.g. 0 ? 1 / 0 : 1).
 impl Int {
     #[inline(always)]
     fn as_bool(&self) -> bool {
@@ -180,27 +181,56 @@ impl Int {
         }
     }
 
+    #[inline(always)]
+    fn as_unsigned(&self) -> u64 {
+        match *self {
+            Int::Unsigned(x) => x,
+            Int::Signed(x) => x as u64,
+        }
+    }
+
     #[inline(always)]
     fn to_bool(&mut self) -> bool {
         let b = self.as_bool();
-        *self = Int::Unsigned(b as u64);
+        *self = Int::Signed(b as i64);
         b
     }
 
+    #[inline(always)]
+    fn arith<U, S>(&mut self, right: Int, u: U, s: S)
+    where
+        U: Fn(u64, u64) -> u64,
+        S: Fn(i64, i64) -> i64,
+    {
+        *self = match (*self, right) {
+            (Int::Signed(x), Int::Signed(y)) => Int::Signed(s(x, y)),
+            (x, y) => Int::Unsigned(u(x.as_unsigned(), y.as_unsigned())),
+        }
+    }
+
+    #[inline(always)]
+    fn cmp<U, S>(&mut self, right: Int, u: U, s: S)
+    where
+        U: Fn(u64, u64) -> bool,
+        S: Fn(i64, i64) -> bool,
+    {
+        *self = match (*self, right) {
+            (Int::Signed(x), Int::Signed(y)) => Int::Signed(s(x, y) as i64),
+            (x, y) => Int::Signed(u(x.as_unsigned(), y.as_unsigned()) as i64),
+        }
+    }
+
     #[inline(always)]
     fn minus(&mut self) {
         *self = match self {
-            Int::Unsigned(n) => Int::Signed(-(*n as i64)),
-            Int::Signed(n) => Int::Signed(-*n),
+            Int::Unsigned(n) => Int::Unsigned(n.wrapping_neg()),
+            Int::Signed(n) => Int::Signed(n.wrapping_neg()),
         }
     }
 
     #[inline(always)]
     fn not(&mut self) {
-        *self = match self {
-            Int::Unsigned(n) => Int::Unsigned((*n == 0) as u64),
-            Int::Signed(n) => Int::Unsigned((*n == 0) as u64),
-        }
+        *self = Int::Signed(!self.as_bool() as i64);
     }
 
     #[inline(always)]
@@ -213,260 +243,109 @@ impl Int {
 
     #[inline(always)]
     fn mul(&mut self, right: Int) {
-        *self = match self {
-            Int::Unsigned(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned(*x * y),
-                Int::Signed(y) => Int::Signed((*x as i64) * y),
-            },
-            Int::Signed(x) => match right {
-                Int::Unsigned(y) => Int::Signed(*x * (y as i64)),
-                Int::Signed(y) => Int::Signed(*x * y),
-            },
-        }
+        self.arith(right, u64::wrapping_mul, i64::wrapping_mul);
     }
 
     #[inline(always)]
     fn div(&mut self, right: Int) {
-        *self = match self {
-            Int::Unsigned(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned(*x / y),
-                Int::Signed(y) => Int::Signed((*x as i64) / y),
-            },
-            Int::Signed(x) => match right {
-                Int::Unsigned(y) => Int::Signed(*x / (y as i64)),
-                Int::Signed(y) => Int::Signed(*x / y),
-            },
-        }
+        self.arith(
+            right,
+            |x, y| x.checked_div(y).unwrap_or(0),
+            |x, y| if y == 0 { 0 } else { x.wrapping_div(y) },
+        );
     }
 
     #[inline(always)]
     fn modulo(&mut self, right: Int) {
-        *self = match self {
-            Int::Unsigned(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned(*x % y),
-                Int::Signed(y) => Int::Signed((*x as i64) % y),
-            },
-            Int::Signed(x) => match right {
-                Int::Unsigned(y) => Int::Signed(*x % (y as i64)),
-                Int::Signed(y) => Int::Signed(*x % y),
-            },
-        }
+        self.arith(
+            right,
+            |x, y| x.checked_rem(y).unwrap_or(0),
+            |x, y| if y == 0 { 0 } else { x.wrapping_rem(y) },
+        );
     }
 
     #[inline(always)]
     fn add(&mut self, right: Int) {
-        *self = match self {
-            Int::Unsigned(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned(*x + y),
-                Int::Signed(y) => Int::Signed((*x as i64) + y),
-            },
-            Int::Signed(x) => match right {
-                Int::Unsigned(y) => Int::Signed(*x + (y as i64)),
-                Int::Signed(y) => Int::Signed(*x + y),
-            },
-        }
+        self.arith(right, u64::wrapping_add, i64::wrapping_add);
     }
 
     #[inline(always)]
     fn sub(&mut self, right: Int) {
-        *self = match self {
-            Int::Unsigned(x) => match right {
-                Int::Unsigned(y) => {
-                    if *x >= y {
-                        Int::Unsigned(*x - y)
-                    } else {
-                        Int::Signed(-((y - *x) as i64))
-                    }
-                }
-                Int::Signed(y) => Int::Signed((*x as i64) - y),
-            },
-            Int::Signed(x) => match right {
-                Int::Unsigned(y) => Int::Signed(*x - (y as i64)),
-                Int::Signed(y) => Int::Signed(*x - y),
-            },
-        }
+        self.arith(right, u64::wrapping_sub, i64::wrapping_sub);
     }
 
     #[inline(always)]
     fn lshift(&mut self, right: Int) {
+        // the type of the result is the one of the left operand
+        let y = right.as_unsigned();
         *self = match self {
-            Int::Unsigned(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned(*x << y),
-                Int::Signed(y) => Int::Signed((*x as i64) << y),
-            },
-            Int::Signed(x) => match right {
-                Int::Unsigned(y) => Int::Signed(*x << (y as i64)),
-                Int::Signed(y) => Int::Signed(*x << y),
-            },
+            Int::Unsigned(x) => Int::Unsigned(if y >= 64 { 0 } else { *x << y }),
+            Int::Signed(x) => Int::Signed(if y >= 64 { 0 } else { *x << y }),
         }
     }
 
     #[inline(always)]
     fn rshift(&mut self, right: Int) {
+        let y = right.as_unsigned();
         *self = match self {
-            Int::Unsigned(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned(*x >> y),
-                Int::Signed(y) => Int::Signed((*x as i64) >> y),
-            },
-            Int::Signed(x) => match right {
-                Int::Unsigned(y) => Int::Signed(*x >> (y as i64)),
-                Int::Signed(y) => Int::Signed(*x >> y),
-            },
+            Int::Unsigned(x) => Int::Unsigned(if y >= 64 { 0 } else { *x >> y }),
+            Int::Signed(x) => Int::Signed(*x >> y.min(63)),
         }
     }
 
     #[inline(always)]
     fn lt(&mut self, right: Int) {
-        *self = match self {
-            Int::Unsigned(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned((*x < y) as u64),
-                Int::Signed(y) => Int::Unsigned(((*x as i64) < y) as u64),
-            },
-            Int::Signed(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned((*x < (y as i64)) as u64),
-                Int::Signed(y) => Int::Unsigned((*x < y) as u64),
-            },
-        }
+        self.cmp(right, |x, y| x < y, |x, y| x < y);
     }
 
     #[inline(always)]
     fn gt(&mut self, right: Int) {
-        *self = match self {
-            Int::Unsigned(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned((*x > y) as u64),
-                Int::Signed(y) => Int::Unsigned(((*x as i64) > y) as u64),
-            },
-            Int::Signed(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned((*x > (y as i64)) as u64),
-                Int::Signed(y) => Int::Unsigned((*x > y) as u64),
-            },
-        }
+        self.cmp(right, |x, y| x > y, |x, y| x > y);
     }
 
     #[inline(always)]
     fn leq(&mut self, right: Int) {
-        *self = match self {
-            Int::Unsigned(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned((*x <= y) as u64),
-                Int::Signed(y) => Int::Unsigned(((*x as i64) <= y) as u64),
-            },
-            Int::Signed(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned((*x <= (y as i64)) as u64),
-                Int::Signed(y) => Int::Unsigned((*x <= y) as u64),
-            },
-        }
+        self.cmp(right, |x, y| x <= y, |x, y| x <= y);
     }
 
     #[inline(always)]
     fn geq(&mut self, right: Int) {
-        *self = match self {
-            Int::Unsigned(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned((*x >= y) as u64),
-                Int::Signed(y) => Int::Unsigned(((*x as i64) >= y) as u64),
-            },
-            Int::Signed(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned((*x >= (y as i64)) as u64),
-                Int::Signed(y) => Int::Unsigned((*x >= y) as u64),
-            },
-        }
+        self.cmp(right, |x, y| x >= y, |x, y| x >= y);
     }
 
     #[inline(always)]
     fn eq(&mut self, right: Int) {
-        *self = match self {
-            Int::Unsigned(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned((*x == y) as u64),
-                Int::Signed(y) => Int::Unsigned(((*x as i64) == y) as u64),
-            },
-            Int::Signed(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned((*x == (y as i64)) as u64),
-                Int::Signed(y) => Int::Unsigned((*x == y) as u64),
-            },
-        }
+        self.cmp(right, |x, y| x == y, |x, y| x == y);
     }
 
     #[inline(always)]
     fn neq(&mut self, right: Int) {
-        *self = match self {
-            Int::Unsigned(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned((*x != y) as u64),
-                Int::Signed(y) => Int::Unsigned(((*x as i64) != y) as u64),
-            },
-            Int::Signed(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned((*x != (y as i64)) as u64),
-                Int::Signed(y) => Int::Unsigned((*x != y) as u64),
-            },
-        }
+        self.cmp(right, |x, y| x != y, |x, y| x != y);
     }
 
     #[inline(always)]
     fn bitand(&mut self, right: Int) {
-        *self = match self {
-            Int::Unsigned(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned(*x & y),
-                Int::Signed(y) => Int::Signed((*x as i64) & y),
-            },
-            Int::Signed(x) => match right {
-                Int::Unsigned(y) => Int::Signed(*x & (y as i64)),
-                Int::Signed(y) => Int::Signed(*x & y),
-            },
-        }
+        self.arith(right, |x, y| x & y, |x, y| x & y);
     }
 
     #[inline(always)]
     fn bitxor(&mut self, right: Int) {
-        *self = match self {
-            Int::Unsigned(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned(*x ^ y),
-                Int::Signed(y) => Int::Signed((*x as i64) ^ y),
-            },
-            Int::Signed(x) => match right {
-                Int::Unsigned(y) => Int::Signed(*x ^ (y as i64)),
-                Int::Signed(y) => Int::Signed(*x ^ y),
-            },
-        }
+        self.arith(right, |x, y| x ^ y, |x, y| x ^ y);
     }
 
     #[inline(always)]
     fn bitor(&mut self, right: Int) {
-        *self = match self {
-            Int::Unsigned(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned(*x | y),
-                Int::Signed(y) => Int::Signed((*x as i64) | y),
-            },
-            Int::Signed(x) => match right {
-                Int::Unsigned(y) => Int::Signed(*x | (y as i64)),
-                Int::Signed(y) => Int::Signed(*x | y),
-            },
-        }
+        self.arith(right, |x, y| x | y, |x, y| x | y);
     }
 
     #[inline(always)]
     fn and(&mut self, right: Int) {
-        *self = match self {
-            Int::Unsigned(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned(((*x != 0) && (y != 0)) as u64),
-                Int::Signed(y) => Int::Unsigned(((*x != 0) && (y != 0)) as u64),
-            },
-            Int::Signed(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned(((*x != 0) && (y != 0)) as u64),
-                Int::Signed(y) => Int::Unsigned(((*x != 0) && (y != 0)) as u64),
-            },
-        }
+        *self = Int::Signed((self.as_bool() && right.as_bool()) as i64);
     }
 
     #[inline(always)]
     fn or(&mut self, right: Int) {
-        *self = match self {
-            Int::Unsigned(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned(((*x != 0) || (y != 0)) as u64),
-                Int::Signed(y) => Int::Unsigned(((*x != 0) || (y != 0)) as u64),
-            },
-            Int::Signed(x) => match right {
-                Int::Unsigned(y) => Int::Unsigned(((*x != 0) || (y != 0)) as u64),
-                Int::Signed(y) => Int::Unsigned(((*x != 0) || (y != 0)) as u64),
-            },
-        }
+        *self = Int::Signed((self.as_bool() || right.as_bool()) as i64);
     }
 }
 
@@ -616,22 +495,49 @@ impl<'a, 'b, PC: PreprocContext> Condition<'a, 'b, PC> {
     #[inline(always)]
     fn handle_id(&mut self, id: &str) {
         if id == "defined" {
+            // the behavior is undefined when defined comes from a macro expansion:
+            // like GCC, it's evaluated anyway with a warning
+            if self.lexer.buf.preproc_use() {
+                self.lexer.errors.push(LexerError::DefinedFromExpansion {
+                    sp: self.lexer.span(),
+                });
+            }
             let x = self.lexer.get_defined(self.is_lazy_op());
-            self.operands.push(Int::Unsigned(x));
+            self.operands.push(Int::Signed(x as i64));
+        } else if id == "__has_cpp_attribute" || id == "__has_attribute" {
+            let x = self.lexer.get_has_cpp_attribute(self.is_lazy_op());
+            self.operands.push(Int::Signed(x as i64));
+        } else if id == "__has_include" || id == "__has_include_next" {
+            let x = self
+                .lexer
+                .get_has_include(id == "__has_include_next", self.is_lazy_op());
+            self.operands.push(Int::Signed(x as i64));
         } else {
-            self.operands.push(Int::Unsigned(0));
+            self.operands.push(Int::Signed(0));
         }
         self.last = LastKind::Operand;
     }
 
     #[inline(always)]
     fn push_zero(&mut self) {
-        self.operands.push(Int::Unsigned(0));
+        self.operands.push(Int::Signed(0));
         self.last = LastKind::Operand;
     }
 
     fn eval(&mut self) -> Int {
+        self.lexer.buf.get_preproc_buf().in_condition = true;
         loop {
+            // GNU assertion: #predicate(answer)
+            skip_whites!(self.lexer);
+            if self.lexer.buf.has_char() && self.lexer.buf.next_char() == b'#' {
+                self.lexer.buf.inc();
+                let (pred, answer) = self.lexer.get_assertion();
+                let x = self.lexer.context.is_asserted(pred, answer.as_deref());
+                self.operands.push(Int::Signed(x as i64));
+                self.last = LastKind::Operand;
+                continue;
+            }
+
             let tok = self.lexer.next_token();
             match tok {
                 Token::Plus => {
@@ -690,12 +596,23 @@ impl<'a, 'b, PC: PreprocContext> Condition<'a, 'b, PC> {
                 Token::And => {
                     self.push_operator(Operator::BitAnd);
                 }
-                Token::LiteralChar(x)
-                | Token::LiteralLChar(x)
-                | Token::LiteralUUChar(x)
-                | Token::LiteralUChar(x)
-                | Token::LiteralU8Char(x) => {
-                    self.operands.push(Int::Unsigned(x as u64));
+                Token::LiteralChar(x) => {
+                    // a character constant is an int and char is signed:
+                    // '\xff' is -1 and 'ab' is ('a' << 8) | 'b'
+                    let x = if x <= 0xFF {
+                        i64::from(x as u8 as i8)
+                    } else {
+                        i64::from(x as i32)
+                    };
+                    self.operands.push(Int::Signed(x));
+                    self.last = LastKind::Operand;
+                }
+                Token::LiteralLChar(x) => {
+                    self.operands.push(Int::Signed(i64::from(x as i32)));
+                    self.last = LastKind::Operand;
+                }
+                Token::LiteralUUChar(x) | Token::LiteralUChar(x) | Token::LiteralU8Char(x) => {
+                    self.operands.push(Int::Unsigned(u64::from(x)));
                     self.last = LastKind::Operand;
                 }
                 Token::Xor => {
@@ -731,11 +648,21 @@ impl<'a, 'b, PC: PreprocContext> Condition<'a, 'b, PC> {
                     self.flush_until_paren();
                 }
                 Token::LiteralInt(x)
-                | Token::LiteralUInt(x)
                 | Token::LiteralLong(x)
                 | Token::LiteralLongLong(x)
+                | Token::LiteralSize(x) => {
+                    // a literal too big for intmax_t is unsigned
+                    if x > i64::MAX as u64 {
+                        self.operands.push(Int::Unsigned(x));
+                    } else {
+                        self.operands.push(Int::Signed(x as i64));
+                    }
+                    self.last = LastKind::Operand;
+                }
+                Token::LiteralUInt(x)
                 | Token::LiteralULong(x)
-                | Token::LiteralULongLong(x) => {
+                | Token::LiteralULongLong(x)
+                | Token::LiteralUSize(x) => {
                     self.operands.push(Int::Unsigned(x));
                     self.last = LastKind::Operand;
                 }
@@ -800,14 +727,20 @@ impl<'a, 'b, PC: PreprocContext> Condition<'a, 'b, PC> {
                 Token::False => {
                     self.handle_id("false");
                 }
-                Token::Comment => {}
+                Token::Comment | Token::RetainedComment(_) => {}
                 Token::Question => {
                     self.push_operator(Operator::Question);
                 }
                 Token::Colon => {
+                    // a ? b ? c : d : e: the second colon completes b ? c : d
+                    if let Some(Operator::Colon) = self.operators.last() {
+                        self.operators.pop().unwrap().operate(&mut self.operands);
+                        self.operators.pop();
+                    }
                     self.push_operator(Operator::Colon);
                 }
                 Token::Eol | Token::Eof => {
+                    self.lexer.buf.get_preproc_buf().in_condition = false;
                     self.flush();
                     return self.operands.pop().unwrap();
                 }
@@ -842,7 +775,7 @@ mod tests {
         let mut cond = Condition::new(&mut lexer);
         let res = cond.eval();
 
-        assert_eq!(res, Int::Unsigned(1));
+        assert_eq!(res, Int::Signed(1));
     }
 
     #[test]
@@ -851,7 +784,7 @@ mod tests {
         let mut cond = Condition::new(&mut lexer);
         let res = cond.eval();
 
-        assert_eq!(res, Int::Unsigned(5));
+        assert_eq!(res, Int::Signed(5));
     }
 
     #[test]
@@ -860,7 +793,7 @@ mod tests {
         let mut cond = Condition::new(&mut lexer);
         let res = cond.eval();
 
-        assert_eq!(res, Int::Unsigned(6));
+        assert_eq!(res, Int::Signed(6));
     }
 
     #[test]
@@ -869,7 +802,7 @@ mod tests {
         let mut cond = Condition::new(&mut lexer);
         let res = cond.eval();
 
-        assert_eq!(res, Int::Unsigned(14));
+        assert_eq!(res, Int::Signed(14));
     }
 
     #[test]
@@ -878,7 +811,7 @@ mod tests {
         let mut cond = Condition::new(&mut lexer);
         let res = cond.eval();
 
-        assert_eq!(res, Int::Unsigned(1));
+        assert_eq!(res, Int::Signed(1));
     }
 
     #[test]
@@ -905,7 +838,7 @@ mod tests {
         let mut cond = Condition::new(&mut lexer);
         let res = cond.eval();
 
-        assert_eq!(res, Int::Unsigned(1));
+        assert_eq!(res, Int::Signed(1));
     }
 
     #[test]
@@ -914,7 +847,7 @@ mod tests {
         let mut cond = Condition::new(&mut lexer);
         let res = cond.eval();
 
-        assert_eq!(res, Int::Unsigned(1));
+        assert_eq!(res, Int::Signed(1));
     }
 
     #[test]
@@ -933,12 +866,12 @@ mod tests {
         let mut cond = Condition::new(&mut lexer);
         let res = cond.eval();
 
-        assert_eq!(res, Int::Unsigned(1));
+        assert_eq!(res, Int::Signed(1));
 
         let mut cond = Condition::new(&mut lexer);
         let res = cond.eval();
 
-        assert_eq!(res, Int::Unsigned(1));
+        assert_eq!(res, Int::Signed(1));
     }
 
     #[test]
@@ -947,25 +880,25 @@ mod tests {
         let mut cond = Condition::new(&mut lexer);
         let res = cond.eval();
 
-        assert_eq!(res, Int::Unsigned(2));
+        assert_eq!(res, Int::Signed(2));
 
         let mut lexer = Lexer::<DefaultContext>::new(b"0 ? 2 : 3");
         let mut cond = Condition::new(&mut lexer);
         let res = cond.eval();
 
-        assert_eq!(res, Int::Unsigned(3));
+        assert_eq!(res, Int::Signed(3));
 
         let mut lexer = Lexer::<DefaultContext>::new(b"0 * 1 ? 2 * 3 : 3 * 4");
         let mut cond = Condition::new(&mut lexer);
         let res = cond.eval();
 
-        assert_eq!(res, Int::Unsigned(12));
+        assert_eq!(res, Int::Signed(12));
 
         let mut lexer = Lexer::<DefaultContext>::new(b"1 + 1 ? 2 * 3 : 3 * 4");
         let mut cond = Condition::new(&mut lexer);
         let res = cond.eval();
 
-        assert_eq!(res, Int::Unsigned(6));
+        assert_eq!(res, Int::Signed(6));
     }
 
     #[test]
@@ -974,7 +907,7 @@ mod tests {
         let mut cond = Condition::new(&mut lexer);
         let res = cond.eval();
 
-        assert_eq!(res, Int::Unsigned(1 | 2 | 4 | 1024 | 8 | 16 | 32));
+        assert_eq!(res, Int::Signed(1 | 2 | 4 | 1024 | 8 | 16 | 32));
     }
 
     #[test]
@@ -983,7 +916,7 @@ mod tests {
         let mut cond = Condition::new(&mut lexer);
         let res = cond.eval();
 
-        assert_eq!(res, Int::Unsigned(1 ^ 2 ^ 4 ^ 1024 ^ 8 ^ 16 ^ 32));
+        assert_eq!(res, Int::Signed(1 ^ 2 ^ 4 ^ 1024 ^ 8 ^ 16 ^ 32));
     }
 
     #[test]
@@ -994,7 +927,7 @@ mod tests {
         let mut cond = Condition::new(&mut lexer);
         let res = cond.eval();
 
-        assert_eq!(res, Int::Unsigned(0));
+        assert_eq!(res, Int::Signed(0));
     }
 
     #[test]
@@ -1003,7 +936,7 @@ mod tests {
         let mut cond = Condition::new(&mut lexer);
         let res = cond.eval();
 
-        assert_eq!(res, Int::Unsigned(0));
+        assert_eq!(res, Int::Signed(0));
     }
 
     #[test]
@@ -1012,6 +945,172 @@ mod tests {
         let mut cond = Condition::new(&mut lexer);
         let res = cond.eval();
 
-        assert_eq!(res, Int::Unsigned(0));
+        assert_eq!(res, Int::Signed(0));
+    }
+
+    #[test]
+    fn test_condition_prec_version() {
+        let mut lexer = Lexer::<DefaultContext>::new(
+            concat!(
+                "#define MAJOR 1\n",
+                "#define MINOR 2\n",
+                "#define PATCH 3\n",
+                "MAJOR * 10000 + MINOR * 100 + PATCH >= 10203 && 1 << 2 + 1 == 8\n",
+            )
+            .as_bytes(),
+        );
+        lexer.consume_tokens(3);
+
+        let mut cond = Condition::new(&mut lexer);
+        let res = cond.eval();
+
+        assert_eq!(res, Int::Signed(1));
+    }
+
+    #[test]
+    fn test_question_nested() {
+        let mut lexer = Lexer::<DefaultContext>::new(b"0 ? 1 : 2 > 1 ? 3 : 4");
+        let mut cond = Condition::new(&mut lexer);
+        let res = cond.eval();
+
+        assert_eq!(res, Int::Signed(3));
+
+        let mut lexer = Lexer::<DefaultContext>::new(b"1 ? 0 ? 5 : 6 : 7");
+        let mut cond = Condition::new(&mut lexer);
+        let res = cond.eval();
+
+        assert_eq!(res, Int::Signed(6));
+    }
+
+    #[test]
+    fn test_div_by_zero_unevaluated() {
+        let mut lexer = Lexer::<DefaultContext>::new(b"1 ? 2 : 3 / 0");
+        let mut cond = Condition::new(&mut lexer);
+        let res = cond.eval();
+
+        assert_eq!(res, Int::Signed(2));
+
+        let mut lexer = Lexer::<DefaultContext>::new(b"0 && 1 % 0");
+        let mut cond = Condition::new(&mut lexer);
+        let res = cond.eval();
+
+        assert_eq!(res, Int::Signed(0));
+    }
+
+    #[test]
+    fn test_condition_unsigned() {
+        let mut lexer = Lexer::<DefaultContext>::new(b"-1 > 0u");
+        let mut cond = Condition::new(&mut lexer);
+        let res = cond.eval();
+
+        assert_eq!(res, Int::Signed(1));
+
+        let mut lexer = Lexer::<DefaultContext>::new(b"-1 > 0");
+        let mut cond = Condition::new(&mut lexer);
+        let res = cond.eval();
+
+        assert_eq!(res, Int::Signed(0));
+
+        let mut lexer = Lexer::<DefaultContext>::new(b"0u - 1");
+        let mut cond = Condition::new(&mut lexer);
+        let res = cond.eval();
+
+        assert_eq!(res, Int::Unsigned(u64::MAX));
+    }
+
+    #[test]
+    fn test_condition_shift_mod() {
+        let mut lexer = Lexer::<DefaultContext>::new(b"(-8 >> 1) + 17 % 5 * 2 - (~0 & 0xF)");
+        let mut cond = Condition::new(&mut lexer);
+        let res = cond.eval();
+
+        assert_eq!(res, Int::Signed(-4 + 4 - 15));
+    }
+
+    #[test]
+    fn test_condition_defined() {
+        let mut lexer = Lexer::<DefaultContext>::new(
+            concat!(
+                "#define X\n",
+                "#define Y X\n",
+                "defined(X) && defined Y && !defined(Z)\n",
+            )
+            .as_bytes(),
+        );
+        lexer.consume_tokens(2);
+
+        let mut cond = Condition::new(&mut lexer);
+        let res = cond.eval();
+
+        assert_eq!(res, Int::Signed(1));
+        assert!(lexer.errors.is_empty());
+    }
+
+    #[test]
+    fn test_condition_defined_from_expansion() {
+        let mut lexer = Lexer::<DefaultContext>::new(
+            concat!("#define X\n", "#define HAS_X defined(X)\n", "HAS_X && 1\n",).as_bytes(),
+        );
+        lexer.consume_tokens(2);
+
+        let mut cond = Condition::new(&mut lexer);
+        let res = cond.eval();
+
+        assert_eq!(res, Int::Signed(1));
+        assert_eq!(lexer.errors.len(), 1);
+        assert!(matches!(
+            lexer.errors[0],
+            LexerError::DefinedFromExpansion { .. }
+        ));
+    }
+
+    #[test]
+    fn test_has_cpp_attribute() {
+        let mut lexer = Lexer::<DefaultContext>::new(b"__has_cpp_attribute(likely) >= 201803L");
+        let mut cond = Condition::new(&mut lexer);
+        let res = cond.eval();
+
+        assert_eq!(res, Int::Signed(1));
+
+        let mut lexer = Lexer::<DefaultContext>::new(b"__has_cpp_attribute( __nodiscard__ )");
+        let mut cond = Condition::new(&mut lexer);
+        let res = cond.eval();
+
+        assert_eq!(res, Int::Signed(201907));
+
+        let mut lexer = Lexer::<DefaultContext>::new(
+            b"__has_cpp_attribute(foo) || __has_cpp_attribute(gnu::likely)",
+        );
+        let mut cond = Condition::new(&mut lexer);
+        let res = cond.eval();
+
+        assert_eq!(res, Int::Signed(0));
+    }
+
+    #[test]
+    fn test_condition_char() {
+        let mut lexer = Lexer::<DefaultContext>::new(b"'A' == 0x41 && '\\n' == 10");
+        let mut cond = Condition::new(&mut lexer);
+        let res = cond.eval();
+
+        assert_eq!(res, Int::Signed(1));
+
+        let mut lexer = Lexer::<DefaultContext>::new(b"'\\0' + '\\x41'");
+        let mut cond = Condition::new(&mut lexer);
+        let res = cond.eval();
+
+        assert_eq!(res, Int::Signed(0x41));
+
+        let mut lexer = Lexer::<DefaultContext>::new(b"'\\xff' < 0 && u'\\xff' > 0");
+        let mut cond = Condition::new(&mut lexer);
+        let res = cond.eval();
+
+        assert_eq!(res, Int::Signed(1));
+
+        let mut lexer = Lexer::<DefaultContext>::new(b"'ab'");
+        let mut cond = Condition::new(&mut lexer);
+        let res = cond.eval();
+
+        assert_eq!(res, Int::Signed(0x6162));
     }
 }
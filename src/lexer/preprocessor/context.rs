@@ -3,7 +3,7 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -12,9 +12,36 @@ use super::include::{DefaultIncludeLocator, IncludeLocator, PathIndex};
 use super::macros::{
     Macro, MacroCounter, MacroFile, MacroFunction, MacroLine, MacroObject, MacroType,
 };
+use crate::errors::Span;
 use crate::lexer::buffer::{BufferData, Position};
+use crate::lexer::lexer::Location;
 use crate::lexer::source::{FileId, SourceMutex};
 
+/// A region defined with #pragma region/#pragma endregion
+/// The end is None when the region isn't closed
+#[derive(Clone, Debug, PartialEq)]
+pub struct Region {
+    pub name: String,
+    pub start: Location,
+    pub end: Option<Location>,
+}
+
+/// An #include or #include_next directive: resolved is None when the file isn't found
+#[derive(Clone, Debug, PartialEq)]
+pub struct Include {
+    pub requested: String,
+    pub resolved: Option<PathBuf>,
+    pub system: bool,
+}
+
+/// A conditional directive (#elif is an If) with the macros written in its condition
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conditional {
+    pub kind: IfKind,
+    pub span: Span,
+    pub macros: Vec<String>,
+}
+
 /// Indicate the state of the if statement
 /// Eval: indicates that we're evaluating the tokens
 /// Skip: indicates that we're skipping everything until the corresponding endif
@@ -40,6 +67,14 @@ pub trait PreprocContext: Default + IncludeLocator {
     /// For example if we're in SkipAndSwitch state then switch to Eval on else
     fn if_change(&mut self, state: IfState);
 
+    /// Get a copy of the if states (used to save the lexer state)
+    fn get_if_stack(&self) -> Vec<IfState> {
+        Vec::new()
+    }
+
+    /// Replace the if states
+    fn set_if_stack(&mut self, _stack: Vec<IfState>) {}
+
     /// Add a macro function: #define foo(a, b)...
     fn add_function(&mut self, name: String, mac: MacroFunction);
 
@@ -68,6 +103,83 @@ pub trait PreprocContext: Default + IncludeLocator {
     /// Save the position of matching #if/#else|#endif
     fn save_switch(&self, file: FileId, pos: usize, next: Position);
 
+    /// Open a region: #pragma region name
+    fn start_region(&mut self, _name: String, _start: Location) {}
+
+    /// Close the last opened region: #pragma endregion
+    fn end_region(&mut self, _end: Location) {}
+
+    /// Get the regions in the order they've been opened
+    fn regions(&self) -> &[Region] {
+        &[]
+    }
+
+    /// Save the diagnostic state: #pragma GCC diagnostic push
+    fn push_diagnostics(&mut self) {}
+
+    /// Restore the last saved diagnostic state: #pragma GCC diagnostic pop
+    /// Return false when there is no saved state
+    fn pop_diagnostics(&mut self) -> bool {
+        true
+    }
+
+    /// Ignore or restore a warning: #pragma GCC diagnostic ignored "-Wfoo"
+    fn ignore_diagnostic(&mut self, _option: &str, _ignored: bool) {}
+
+    /// Get the warnings currently ignored
+    fn ignored_diagnostics(&self) -> &[String] {
+        &[]
+    }
+
+    /// Record that a file contains #pragma once
+    fn add_pragma_once(&mut self, _file: FileId) {}
+
+    /// Check if a file contains #pragma once
+    fn is_pragma_once(&self, _file: FileId) -> bool {
+        false
+    }
+
+    /// Check if the files with #pragma once must be included only once
+    /// When false, the pragma is only recorded (e.g. for analysis)
+    fn enforce_pragma_once(&self) -> bool {
+        false
+    }
+
+    /// Check that the conditionals nested in a skipped block are balanced
+    /// When false, an unterminated skipped block is silently closed by the end of the file
+    fn strict_conditionals(&self) -> bool {
+        false
+    }
+
+    /// Check if the conditional directives must be scanned and reported with `conditional`
+    /// When false, the conditions aren't lexed twice
+    fn record_conditionals(&self) -> bool {
+        false
+    }
+
+    /// Add an answer to a predicate: #assert predicate(answer)
+    fn add_assertion(&mut self, _pred: &str, _answer: String) {}
+
+    /// Remove an answer or all the answers of a predicate: #unassert predicate[(answer)]
+    fn rm_assertion(&mut self, _pred: &str, _answer: Option<&str>) {}
+
+    /// Check if a predicate has the given answer or any answer (used in condition #predicate(answer))
+    fn is_asserted(&self, _pred: &str, _answer: Option<&str>) -> bool {
+        false
+    }
+
+    /// Called on each #include or #include_next (e.g. to write a depfile)
+    fn included(&mut self, _requested: &str, _resolved: Option<&Path>, _is_system: bool) {}
+
+    /// Called on each conditional directive, even in a skipped block (e.g. for an impact analysis)
+    fn conditional(&mut self, _cond: Conditional) {}
+
+    /// Get the definition of a macro as it could be written after #define
+    /// (e.g. FOO(x) ((x) + 1))
+    fn render_macro(&self, name: &str) -> Option<String> {
+        self.get(name).and_then(|mac| mac.render(name))
+    }
+
     fn new_with_if_cache(if_cache: Arc<IfCache>) -> Self;
 }
 
@@ -152,6 +264,17 @@ pub struct Context<IL: IncludeLocator> {
     if_cache: Arc<IfCache>,
     include: IL,
     buffer: Option<()>,
+    regions: Vec<Region>,
+    open_regions: Vec<usize>,
+    diagnostics: Vec<String>,
+    diagnostics_stack: Vec<Vec<String>>,
+    once: HashSet<FileId>,
+    enforce_once: bool,
+    strict_if: bool,
+    record_if: bool,
+    assertions: HashMap<String, Vec<String>>,
+    includes: Vec<Include>,
+    conditionals: Vec<Conditional>,
 }
 
 pub type DefaultContext = Context<DefaultIncludeLocator>;
@@ -173,6 +296,17 @@ impl<IL: IncludeLocator> Default for Context<IL> {
             if_cache: Arc::new(IfCache::default()),
             include: IL::default(),
             buffer: None,
+            regions: Vec::new(),
+            open_regions: Vec::new(),
+            diagnostics: Vec::new(),
+            diagnostics_stack: Vec::new(),
+            once: HashSet::default(),
+            enforce_once: true,
+            strict_if: false,
+            record_if: false,
+            assertions: HashMap::default(),
+            includes: Vec::new(),
+            conditionals: Vec::new(),
         }
     }
 }
@@ -185,8 +319,52 @@ impl<IL: IncludeLocator> Context<IL> {
             if_cache: Arc::new(IfCache::default()),
             include,
             buffer: None,
+            regions: Vec::new(),
+            open_regions: Vec::new(),
+            diagnostics: Vec::new(),
+            diagnostics_stack: Vec::new(),
+            once: HashSet::default(),
+            enforce_once: true,
+            strict_if: false,
+            record_if: false,
+            assertions: HashMap::default(),
+            includes: Vec::new(),
+            conditionals: Vec::new(),
         }
     }
+
+    /// Skip or not the files already included which contain #pragma once
+    pub fn set_enforce_pragma_once(&mut self, enforce: bool) {
+        self.enforce_once = enforce;
+    }
+
+    /// Report the unbalanced conditionals in the skipped blocks
+    pub fn set_strict_conditionals(&mut self, strict: bool) {
+        self.strict_if = strict;
+    }
+
+    /// Record the conditional directives and the macros used in their condition
+    pub fn set_record_conditionals(&mut self, record: bool) {
+        self.record_if = record;
+    }
+
+    /// Get the included files in the order the directives have been met
+    pub fn includes(&self) -> &[Include] {
+        &self.includes
+    }
+
+    /// Get the conditionals in the order the directives have been met
+    pub fn conditionals(&self) -> &[Conditional] {
+        &self.conditionals
+    }
+
+    /// Get the conditionals whose condition references the given macro
+    pub fn conditionals_using(&self, name: &str) -> Vec<&Conditional> {
+        self.conditionals
+            .iter()
+            .filter(|c| c.macros.iter().any(|m| m == name))
+            .collect()
+    }
 }
 
 impl<IL: IncludeLocator> PreprocContext for Context<IL> {
@@ -206,6 +384,14 @@ impl<IL: IncludeLocator> PreprocContext for Context<IL> {
         *self.if_stack.last_mut().unwrap() = state;
     }
 
+    fn get_if_stack(&self) -> Vec<IfState> {
+        self.if_stack.clone()
+    }
+
+    fn set_if_stack(&mut self, stack: Vec<IfState>) {
+        self.if_stack = stack;
+    }
+
     fn add_function(&mut self, name: String, mac: MacroFunction) {
         self.macros.insert(name, Macro::Function(mac));
     }
@@ -268,6 +454,112 @@ impl<IL: IncludeLocator> PreprocContext for Context<IL> {
         self.if_cache.save_next(file, pos, next);
     }
 
+    fn start_region(&mut self, name: String, start: Location) {
+        self.open_regions.push(self.regions.len());
+        self.regions.push(Region {
+            name,
+            start,
+            end: None,
+        });
+    }
+
+    fn end_region(&mut self, end: Location) {
+        // an endregion without region is just ignored
+        if let Some(i) = self.open_regions.pop() {
+            self.regions[i].end = Some(end);
+        }
+    }
+
+    fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    fn push_diagnostics(&mut self) {
+        self.diagnostics_stack.push(self.diagnostics.clone());
+    }
+
+    fn pop_diagnostics(&mut self) -> bool {
+        if let Some(diagnostics) = self.diagnostics_stack.pop() {
+            self.diagnostics = diagnostics;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn ignore_diagnostic(&mut self, option: &str, ignored: bool) {
+        let pos = self.diagnostics.iter().position(|d| d == option);
+        match (pos, ignored) {
+            (None, true) => self.diagnostics.push(option.to_string()),
+            (Some(pos), false) => {
+                self.diagnostics.remove(pos);
+            }
+            _ => {}
+        }
+    }
+
+    fn ignored_diagnostics(&self) -> &[String] {
+        &self.diagnostics
+    }
+
+    fn add_pragma_once(&mut self, file: FileId) {
+        self.once.insert(file);
+    }
+
+    fn is_pragma_once(&self, file: FileId) -> bool {
+        self.once.contains(&file)
+    }
+
+    fn enforce_pragma_once(&self) -> bool {
+        self.enforce_once
+    }
+
+    fn strict_conditionals(&self) -> bool {
+        self.strict_if
+    }
+
+    fn record_conditionals(&self) -> bool {
+        self.record_if
+    }
+
+    fn add_assertion(&mut self, pred: &str, answer: String) {
+        let answers = self.assertions.entry(pred.to_string()).or_default();
+        if !answers.contains(&answer) {
+            answers.push(answer);
+        }
+    }
+
+    fn rm_assertion(&mut self, pred: &str, answer: Option<&str>) {
+        if let Some(answer) = answer {
+            if let Some(answers) = self.assertions.get_mut(pred) {
+                answers.retain(|a| a != answer);
+            }
+        } else {
+            self.assertions.remove(pred);
+        }
+    }
+
+    fn is_asserted(&self, pred: &str, answer: Option<&str>) -> bool {
+        match self.assertions.get(pred) {
+            Some(answers) => answer.map_or(!answers.is_empty(), |answer| {
+                answers.iter().any(|a| a == answer)
+            }),
+            None => false,
+        }
+    }
+
+    fn included(&mut self, requested: &str, resolved: Option<&Path>, is_system: bool) {
+        self.includes.push(Include {
+            requested: requested.to_string(),
+            resolved: resolved.map(|p| p.to_path_buf()),
+            system: is_system,
+        });
+    }
+
+    fn conditional(&mut self, cond: Conditional) {
+        self.conditionals.push(cond);
+    }
+
     fn new_with_if_cache(if_cache: Arc<IfCache>) -> Self {
         Self {
             macros: HashMap::default(),
@@ -275,6 +567,17 @@ impl<IL: IncludeLocator> PreprocContext for Context<IL> {
             if_cache,
             include: IL::default(),
             buffer: None,
+            regions: Vec::new(),
+            open_regions: Vec::new(),
+            diagnostics: Vec::new(),
+            diagnostics_stack: Vec::new(),
+            once: HashSet::default(),
+            enforce_once: true,
+            strict_if: false,
+            record_if: false,
+            assertions: HashMap::default(),
+            includes: Vec::new(),
+            conditionals: Vec::new(),
         }
     }
 }
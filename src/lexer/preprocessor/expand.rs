@@ -0,0 +1,116 @@
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::context::PreprocContext;
+use crate::lexer::errors::LexerError;
+use crate::lexer::source::FileId;
+use crate::lexer::{Lexer, Token};
+
+/// Run the preprocessor on the input and get the expanded source (as gcc -E)
+pub fn preprocess<PC: PreprocContext>(input: &[u8], context: PC) -> (Vec<u8>, Vec<LexerError>) {
+    let mut lexer = Lexer::new_with_context(input, FileId(0), context);
+    let mut out = Vec::with_capacity(input.len());
+    // end of the last token: (in macro expansion, position)
+    let mut last_end = None;
+
+    loop {
+        let tok = lexer.next_token();
+        match tok {
+            Token::Eof => {
+                break;
+            }
+            Token::Comment | Token::RetainedComment(_) => {}
+            // a directive is replaced by a new line
+            Token::Eol
+            | Token::PreprocDefine
+            | Token::PreprocElif
+            | Token::PreprocElse
+            | Token::PreprocEndif
+            | Token::PreprocError
+            | Token::PreprocIf
+            | Token::PreprocIfdef
+            | Token::PreprocIfndef
+            | Token::PreprocInclude
+            | Token::PreprocIncludeNext
+            | Token::PreprocLine
+            | Token::PreprocPragma
+            | Token::PreprocUndef
+            | Token::PreprocAssert
+            | Token::PreprocUnassert => {
+                // the pragmas are kept for the compiler
+                if let Some(pragma) = lexer.pragma.take() {
+                    out.extend_from_slice(b"#pragma ");
+                    out.extend_from_slice(pragma);
+                }
+                out.push(b'\n');
+                last_end = None;
+            }
+            _ => {
+                let start = lexer.start.pos;
+                let in_macro = lexer.in_macro_expansion();
+
+                // keep a space where there was one to avoid to paste tokens
+                if let Some(end) = last_end {
+                    if end != (in_macro, start) {
+                        out.push(b' ');
+                    }
+                }
+
+                out.extend_from_slice(lexer.buf.slice(start));
+                last_end = Some((in_macro, lexer.buf.pos()));
+            }
+        }
+    }
+
+    (out, lexer.errors)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lexer::preprocessor::context::DefaultContext;
+    use pretty_assertions::assert_eq;
+
+    fn get_lines(out: &[u8]) -> Vec<String> {
+        String::from_utf8_lossy(out)
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect()
+    }
+
+    #[test]
+    fn test_preprocess() {
+        let (out, errors) = preprocess(
+            concat!(
+                "#define N 4\n",
+                "#define MAX(a, b) ((a) > (b) ? (a) : (b))\n",
+                "#if N > 2\n",
+                "int x[N]; // comment\n",
+                "#else\n",
+                "int y;\n",
+                "#endif\n",
+                "int m = MAX(x[0],   1)+N;\n",
+                "#pragma GCC diagnostic ignored \"-Wunused\"\n",
+                "#pragma once\n",
+            )
+            .as_bytes(),
+            DefaultContext::default(),
+        );
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            get_lines(&out),
+            // macro expansions are surrounded by spaces to avoid token pasting
+            vec![
+                "int x[ 4 ];",
+                "int m = (( x[0] ) > ( 1 ) ? ( x[0] ) : ( 1 )) + 4 ;",
+                "#pragma GCC diagnostic ignored \"-Wunused\"",
+                "#pragma once",
+            ]
+        );
+    }
+}
@@ -17,9 +17,10 @@ use crate::lexer::source::{FileId, SourceMutex};
 pub struct PathIndex(pub usize);
 
 #[derive(Clone, Copy, Debug, PartialEq)]
-enum IncludeType<'a> {
+pub(crate) enum IncludeType<'a> {
     Quote(&'a str),
     Angle(&'a str),
+    Unterminated(char),
     Other,
 }
 
@@ -181,7 +182,7 @@ impl IncludeLocator for DefaultIncludeLocator {
 }
 
 impl<'a, PC: PreprocContext> Lexer<'a, PC> {
-    pub(crate) fn get_include_content(&mut self, term: u8) -> &'a [u8] {
+    pub(crate) fn get_include_content(&mut self, term: u8) -> Option<&'a [u8]> {
         let spos = self.buf.pos();
         loop {
             if self.buf.has_char() {
@@ -199,17 +200,20 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                         self.buf.inc();
                         s
                     };
-                    return &s;
+                    return Some(s);
+                } else if c == b'\n' {
+                    // Unterminated header name: don't go further than the directive line
+                    return None;
                 } else {
                     self.buf.inc();
                 }
             } else {
-                return self.buf.slice(spos);
+                return None;
             }
         }
     }
 
-    fn get_path(&mut self) -> IncludeType<'a> {
+    pub(crate) fn get_path(&mut self) -> IncludeType<'a> {
         skip_whites!(self);
         if self.buf.has_char() {
             let c = self.buf.next_char();
@@ -217,78 +221,77 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                 // Quoted path
                 self.buf.inc();
                 skip_whites!(self);
-                let path = self.get_include_content(b'\"');
-                let path = std::str::from_utf8(path).unwrap();
-                return IncludeType::Quote(path);
+                return match self.get_include_content(b'\"') {
+                    Some(path) => IncludeType::Quote(std::str::from_utf8(path).unwrap()),
+                    None => IncludeType::Unterminated('\"'),
+                };
             } else if c == b'<' {
                 // Angled path
                 self.buf.inc();
                 skip_whites!(self);
-                let path = self.get_include_content(b'>');
-                let path = std::str::from_utf8(path).unwrap();
-                return IncludeType::Angle(path);
+                return match self.get_include_content(b'>') {
+                    Some(path) => IncludeType::Angle(std::str::from_utf8(path).unwrap()),
+                    None => IncludeType::Unterminated('>'),
+                };
             }
         }
         IncludeType::Other
     }
 
+    fn add_include(&mut self, buf: BufferData) {
+        let file = buf.get_source_id();
+        if !(self.context.enforce_pragma_once() && self.context.is_pragma_once(file)) {
+            self.buf.add_buffer(buf);
+        }
+    }
+
+    fn include_file(&mut self, angle: bool, path: &str, next: bool) -> Result<(), LexerError> {
+        let source_id = self.buf.get_source_id().unwrap();
+        let path_index = self.buf.get_path_index().unwrap();
+        let buf = self.context.find(angle, path, next, source_id, path_index);
+
+        let resolved = buf
+            .as_ref()
+            .map(|buf| self.context.get_path(buf.get_source_id()));
+        self.context.included(path, resolved.as_deref(), angle);
+
+        let buf = buf.ok_or_else(|| LexerError::FileIncludeError {
+            sp: self.span(),
+            file: path.to_string(),
+        })?;
+        self.add_include(buf);
+        Ok(())
+    }
+
+    fn unterminated_path(&mut self, term: char) {
+        // the directive is just dropped so the lexing can go on
+        self.errors.push(LexerError::UnterminatedHeaderName {
+            sp: self.span(),
+            term,
+        });
+    }
+
     pub(crate) fn get_include(&mut self, next: bool) -> Result<(), LexerError> {
         match self.get_path() {
-            IncludeType::Quote(path) => {
-                let source_id = self.buf.get_source_id().unwrap();
-                let path_index = self.buf.get_path_index().unwrap();
-                let buf = self
-                    .context
-                    .find(false, path, next, source_id, path_index)
-                    .ok_or_else(|| LexerError::FileIncludeError {
-                        sp: self.span(),
-                        file: path.to_string(),
-                    })?;
-                self.buf.add_buffer(buf);
-            }
-            IncludeType::Angle(path) => {
-                let source_id = self.buf.get_source_id().unwrap();
-                let path_index = self.buf.get_path_index().unwrap();
-                let buf = self
-                    .context
-                    .find(true, path, next, source_id, path_index)
-                    .ok_or_else(|| LexerError::FileIncludeError {
-                        sp: self.span(),
-                        file: path.to_string(),
-                    })?;
-                self.buf.add_buffer(buf);
-            }
+            IncludeType::Quote(path) => self.include_file(false, path, next)?,
+            IncludeType::Angle(path) => self.include_file(true, path, next)?,
+            IncludeType::Unterminated(term) => self.unterminated_path(term),
             IncludeType::Other => {
                 skip_whites!(self);
                 let id = self.get_preproc_identifier();
-                if self.macro_eval(id) {
+                // the spaces in the header name are the ones written in the definitions
+                self.buf.get_preproc_buf().paste_avoidance = false;
+                let expanded = self.macro_eval(id);
+                self.buf.get_preproc_buf().paste_avoidance = true;
+                if expanded {
                     self.buf.switch_to_preproc();
                     let path = self.get_path();
                     self.buf.rm_buffer();
-                    let source_id = self.buf.get_source_id().unwrap();
-                    let path_index = self.buf.get_path_index().unwrap();
 
                     match path {
-                        IncludeType::Quote(path) => {
-                            let buf = self
-                                .context
-                                .find(false, path, next, source_id, path_index)
-                                .ok_or_else(|| LexerError::FileIncludeError {
-                                    sp: self.span(),
-                                    file: path.to_string(),
-                                })?;
-                            self.buf.add_buffer(buf);
-                        }
-                        IncludeType::Angle(path) => {
-                            let buf = self
-                                .context
-                                .find(true, path, next, source_id, path_index)
-                                .ok_or_else(|| LexerError::FileIncludeError {
-                                    sp: self.span(),
-                                    file: path.to_string(),
-                                })?;
-                            self.buf.add_buffer(buf);
-                        }
+                        IncludeType::Quote(path) => self.include_file(false, path, next)?,
+                        IncludeType::Angle(path) => self.include_file(true, path, next)?,
+                        IncludeType::Unterminated(term) => self.unterminated_path(term),
                         _ => {
                             unreachable!();
                         }
@@ -312,6 +315,7 @@ mod tests {
     use crate::lexer::buffer::OutBuf;
     use crate::lexer::lexer::Token;
     use crate::lexer::preprocessor::context::{Context, DefaultContext};
+    use crate::lexer::preprocessor::expand::preprocess;
     use crate::lexer::preprocessor::macros::Macro;
     use crate::lexer::source::SourceLocator;
     use pretty_assertions::assert_eq;
@@ -359,6 +363,9 @@ mod tests {
                 match path {
                     "path1" => b"#define foo 123\n".to_vec(),
                     "path2" => b"#include <path1>\n#define bar(x) foo x\n".to_vec(),
+                    "vector" => b"#define vector_included\n".to_vec(),
+                    "bits/vector.h" => b"#define bits_vector_included\n".to_vec(),
+                    "my bits/vector.h" => b"#define my_bits_vector_included\n".to_vec(),
                     _ => return None,
                 }
             } else {
@@ -377,6 +384,7 @@ mod tests {
                     "path14" => b"#define hello world\n".to_vec(),
                     "path15" => b"#define MAC1(x) #x\n".to_vec(),
                     "path16" => b"#define pi 3.14159\n".to_vec(),
+                    "path19" => b"#pragma pack(1)\nint N;\n".to_vec(),
                     "path17" => concat!(
                         "#ifndef test\n",
                         "#endif\n",
@@ -387,11 +395,20 @@ mod tests {
                     )
                     .as_bytes()
                     .to_vec(),
+                    "path18" => concat!(
+                        "#pragma once\n",
+                        "#ifdef seen\n",
+                        "#define twice\n",
+                        "#endif\n",
+                        "#define seen\n",
+                    )
+                    .as_bytes()
+                    .to_vec(),
                     _ => return None,
                 }
             };
-            let id = path[4..].parse::<u32>().unwrap();
-            Some(BufferData::new(buf, FileId(id), PathIndex(0)))
+            let id = path.get(4..).and_then(|n| n.parse::<u32>().ok());
+            Some(BufferData::new(buf, FileId(id.unwrap_or(0)), PathIndex(0)))
         }
 
         fn get_id(&mut self, path: &PathBuf) -> FileId {
@@ -456,6 +473,111 @@ mod tests {
         assert_eq!(eval!("test1", p), "123 ");
     }
 
+    #[test]
+    fn test_include_macro_angle() {
+        let mut p = Lexer::<Context<TestIncludeLocator>>::new(
+            concat!(
+                "#define HDR <vector>\n",
+                "#include HDR\n",
+                "#define DIR bits\n",
+                "#define BITS <DIR/vector.h>\n",
+                "#include BITS\n",
+                "#define MY(x) <my x/vector.h>\n",
+                "#include MY(DIR)\n",
+            )
+            .as_bytes(),
+        );
+        p.consume_all();
+        assert!(p.get_errors().is_empty());
+        assert!(p.context.defined("vector_included"));
+        assert!(p.context.defined("bits_vector_included"));
+        assert!(p.context.defined("my_bits_vector_included"));
+    }
+
+    #[test]
+    fn test_include_trailing_comment() {
+        let mut p = Lexer::<Context<TestIncludeLocator>>::new(
+            concat!(
+                "#include <vector> // the vector header\n",
+                "#include \"path14\" /* hello > world */\n",
+                "hello\n",
+            )
+            .as_bytes(),
+        );
+        let toks: Vec<_> = std::iter::repeat_with(|| p.next_useful())
+            .take_while(|t| *t != Token::Eof)
+            .filter(|t| *t != Token::Comment)
+            .collect();
+        assert!(p.get_errors().is_empty());
+        assert!(p.context.defined("vector_included"));
+        assert_eq!(toks.last(), Some(&Token::Identifier("world".to_string())));
+
+        let mut p = Lexer::<Context<TestIncludeLocator>>::new(b"#include <path1\n#define bar >\n");
+        p.consume_all();
+        assert!(p.context.defined("bar"));
+        assert!(!p.context.defined("foo"));
+        assert_eq!(p.errors.len(), 1);
+        assert!(matches!(
+            p.errors[0],
+            LexerError::UnterminatedHeaderName { term: '>', .. }
+        ));
+
+        let mut p = Lexer::<Context<TestIncludeLocator>>::new(b"#include \"path14\n");
+        p.consume_all();
+        assert!(!p.context.defined("hello"));
+        assert!(matches!(
+            p.errors[..],
+            [LexerError::UnterminatedHeaderName { term: '"', .. }]
+        ));
+    }
+
+    #[test]
+    fn test_has_include() {
+        let mut p = Lexer::<Context<TestIncludeLocator>>::new(
+            concat!(
+                "#if defined(__has_include) && __has_include(<vector>)\n",
+                "#define has_vector\n",
+                "#endif\n",
+                "#if __has_include( \"path14\" ) && !__has_include(<nonexistent>)\n",
+                "#define has_path14\n",
+                "#endif\n",
+                "#ifdef __has_builtin\n",
+                "#define has_builtin\n",
+                "#endif\n",
+            )
+            .as_bytes(),
+        );
+        p.consume_all();
+        assert!(p.get_errors().is_empty());
+        assert!(p.context.defined("has_vector"));
+        assert!(p.context.defined("has_path14"));
+        assert!(!p.context.defined("has_builtin"));
+        assert!(!p.context.defined("vector_included"));
+    }
+
+    #[test]
+    fn test_include_preprocess() {
+        let (out, errors) = preprocess(
+            concat!(
+                "#define N included\n",
+                "#include \"path19\"\n",
+                "int x = N;\n",
+            )
+            .as_bytes(),
+            Context::<TestIncludeLocator>::default(),
+        );
+
+        assert!(errors.is_empty());
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(
+            out.lines()
+                .map(|l| l.trim())
+                .filter(|l| !l.is_empty())
+                .collect::<Vec<_>>(),
+            vec!["#pragma pack(1)", "int included ;", "int x = included ;"]
+        );
+    }
+
     #[test]
     fn test_include_macro_call() {
         let mut p = Lexer::<Context<TestIncludeLocator>>::new(
@@ -493,6 +615,24 @@ mod tests {
         assert_eq!(eval!("test", p), "ok ");
     }
 
+    #[test]
+    fn test_include_pragma_once() {
+        let code = concat!("#include \"path18\"\n", "#include \"path18\"\n",).as_bytes();
+
+        let mut p = Lexer::<Context<TestIncludeLocator>>::new(code);
+        p.consume_all();
+        assert!(p.context.is_pragma_once(FileId(18)));
+        assert!(p.context.defined("seen"));
+        assert!(!p.context.defined("twice"));
+
+        // detection only: the file is included twice
+        let mut p = Lexer::<Context<TestIncludeLocator>>::new(code);
+        p.context.set_enforce_pragma_once(false);
+        p.consume_all();
+        assert!(p.context.is_pragma_once(FileId(18)));
+        assert!(p.context.defined("twice"));
+    }
+
     #[test]
     fn test_include_sys() {
         let tmp = TempDir::new("test").unwrap();
@@ -573,6 +713,55 @@ mod tests {
         assert_eq!(p.next_token(), Token::Identifier("sys_foo".to_string()));
     }
 
+    #[test]
+    fn test_include_callback() {
+        let tmp = TempDir::new("test").unwrap();
+        let sys = tmp.path().join("sys");
+        let cur = tmp.path().join("cur");
+
+        std::fs::create_dir_all(&sys).unwrap();
+        std::fs::create_dir_all(&cur).unwrap();
+
+        std::fs::write(sys.join("a.h"), "#include \"b.h\"\n").unwrap();
+        std::fs::write(sys.join("b.h"), "#define B\n").unwrap();
+        std::fs::write(cur.join("c.h"), "#define C\n").unwrap();
+
+        let source = Arc::new(Mutex::new(SourceLocator::default()));
+        let include = DefaultIncludeLocator::new(vec![sys.to_str().unwrap()], source.clone());
+        let mut context = DefaultContext::new(include);
+
+        let foo = cur.join("foo.c");
+        std::fs::write(&foo, "").unwrap();
+        let foo = std::fs::canonicalize(foo).unwrap();
+        let id = context.get_id(&foo);
+        let cur = foo.parent().unwrap();
+
+        lexer_for_file!(
+            p,
+            "#include <a.h>\n#include \"c.h\"\n#include <missing.h>\n",
+            &foo,
+            id,
+            context
+        );
+        p.consume_all();
+
+        let includes: Vec<_> = p
+            .context
+            .includes()
+            .iter()
+            .map(|i| (i.requested.as_str(), i.resolved.clone(), i.system))
+            .collect();
+        assert_eq!(
+            includes,
+            vec![
+                ("a.h", Some(sys.join("a.h")), true),
+                ("b.h", Some(sys.join("b.h")), false),
+                ("c.h", Some(cur.join("c.h")), false),
+                ("missing.h", None, true),
+            ]
+        );
+    }
+
     #[test]
     fn test_include_nonexistent() {
         let mut p = Lexer::<Context<TestIncludeLocator>>::new(
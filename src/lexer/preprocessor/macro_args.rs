@@ -301,8 +301,8 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                         if self.buf.has_char() {
                             let c = self.buf.next_char();
                             if c == b'\n' {
-                                self.buf.add_new_line();
                                 self.buf.inc();
+                                self.buf.add_new_line();
                             } else {
                                 return MacroArgToken::None(b"\\");
                             }
@@ -394,6 +394,46 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
         args
     }
 
+    /// Get the nodes of an argument already split from the others:
+    /// the commas and the parenthesis at top level are just some text
+    pub(crate) fn get_macro_argument(&mut self) -> Vec<MacroNode<'a>> {
+        let mut arg = Vec::new();
+
+        loop {
+            let tok = self.next_arg_token();
+            match tok {
+                MacroArgToken::None(s) => {
+                    arg.push(MacroNode::Nothing(s));
+                }
+                MacroArgToken::String(s) => {
+                    arg.push(MacroNode::String(s));
+                }
+                MacroArgToken::Id(id) => {
+                    arg.push(MacroNode::Id(id));
+                }
+                MacroArgToken::Space => {
+                    if arg.last() != Some(&MacroNode::Space) {
+                        arg.push(MacroNode::Space);
+                    }
+                }
+                MacroArgToken::OpenPar => {
+                    let args = self.get_macro_tokens(0);
+                    arg.push(MacroNode::Args(args));
+                }
+                MacroArgToken::ClosePar => {
+                    arg.push(MacroNode::Nothing(b")"));
+                }
+                MacroArgToken::Comma => {
+                    arg.push(MacroNode::Nothing(b","));
+                }
+                MacroArgToken::Eom => {
+                    break;
+                }
+            }
+        }
+        arg
+    }
+
     #[inline(always)]
     pub(crate) fn get_arguments(
         &mut self,
@@ -434,6 +474,15 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
 }
 
 impl<'a> MacroNode<'a> {
+    /// Check if an argument has no tokens: only spaces or some empty variadic arguments
+    pub(crate) fn is_empty_arg(nodes: &[MacroNode<'a>]) -> bool {
+        nodes.iter().all(|node| match node {
+            MacroNode::Space => true,
+            MacroNode::VaArgs(args) => args.iter().all(|arg| Self::is_empty_arg(arg)),
+            _ => false,
+        })
+    }
+
     pub(crate) fn eval_nodes<PC: PreprocContext>(
         nodes: &[MacroNode<'a>],
         context: &PC,
@@ -11,6 +11,15 @@ use super::macro_args::MacroNode;
 use crate::lexer::buffer::{FileInfo, OutBuf};
 use crate::lexer::{tools, Lexer};
 
+#[inline(always)]
+fn trim_end_whites(out: &[u8]) -> &[u8] {
+    let len = out
+        .iter()
+        .rposition(|c| !c.is_ascii_whitespace())
+        .map_or(0, |p| p + 1);
+    &out[..len]
+}
+
 #[derive(Clone)]
 pub struct MacroObject {
     out: Vec<u8>,
@@ -23,6 +32,7 @@ pub struct MacroObject {
 pub struct MacroFunction {
     out: Vec<u8>,
     actions: Vec<Action>,
+    params: Vec<String>,
     pub(crate) n_args: usize,
     pub(crate) in_use: Cell<bool>,
     pub(crate) va_args: Option<usize>,
@@ -56,6 +66,35 @@ pub enum Macro {
     Counter(MacroCounter),
 }
 
+impl Macro {
+    /// Render the definition as it could be written after #define:
+    /// the builtin macros (__LINE__, ...) have no definition
+    pub fn render(&self, name: &str) -> Option<String> {
+        match self {
+            Macro::Object(mac) => Some(format!("{} {}", name, mac.render()).trim_end().to_string()),
+            Macro::Function(mac) => Some(
+                format!("{}({}) {}", name, mac.render_params(), mac.render_body())
+                    .trim_end()
+                    .to_string(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Check if the two macros have the same definition:
+    /// a redefinition is valid only in this case
+    pub fn is_same_definition(&self, other: &Macro) -> bool {
+        match (self, other) {
+            (Macro::Object(x), Macro::Object(y)) => x.is_same_definition(y),
+            (Macro::Function(x), Macro::Function(y)) => x.is_same_definition(y),
+            (Macro::Line(_), Macro::Line(_))
+            | (Macro::File(_), Macro::File(_))
+            | (Macro::Counter(_), Macro::Counter(_)) => true,
+            _ => false,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum MacroType<'a> {
     None,
@@ -70,6 +109,8 @@ pub enum MacroType<'a> {
 pub enum Action {
     Arg(usize),
     Concat(usize),
+    /// The variadic parameter pasted to a comma: ", ## __VA_ARGS__"
+    ConcatComma(usize),
     Stringify(usize),
     Chunk(usize),
 }
@@ -79,14 +120,15 @@ impl MacroFunction {
     pub(crate) fn new(
         out: Vec<u8>,
         actions: Vec<Action>,
-        n_args: usize,
+        params: Vec<String>,
         va_args: Option<usize>,
         file_info: FileInfo,
     ) -> Self {
         Self {
             out,
             actions,
-            n_args,
+            n_args: params.len(),
+            params,
             in_use: Cell::new(false),
             va_args,
             file_info,
@@ -106,25 +148,20 @@ impl MacroFunction {
         out: &mut OutBuf,
     ) {
         let mut out_pos = 0;
-        let mut output = OutBuf::default();
+        let mut output = OutBuf {
+            paste_avoidance: out.paste_avoidance,
+            ..Default::default()
+        };
 
         for action in self.actions.iter() {
             match action {
                 Action::Arg(pos) => {
                     // gcc/clang are smart: they add whites only when it's required
                     // tbh, I don't care so much, the goal is just to have paste avoidance
-                    if let Some(last) = output.buf.last() {
-                        if *last != b' ' {
-                            output.buf.push(b' ');
-                        }
-                    }
+                    output.pad();
                     if let Some(arg) = args.get(*pos) {
                         MacroNode::eval_nodes(arg, context, info, &mut output, false);
-                        if let Some(last) = output.buf.last() {
-                            if *last != b' ' {
-                                output.buf.push(b' ');
-                            }
-                        }
+                        output.pad();
                         /*if *output.last().unwrap() != b' ' {
                             output.push(b' ');
                         }*/
@@ -133,6 +170,20 @@ impl MacroFunction {
                 Action::Concat(pos) => {
                     MacroNode::make_expr(&args[*pos], &mut output);
                 }
+                Action::ConcatComma(pos) => {
+                    let arg = &args[*pos];
+                    if MacroNode::is_empty_arg(arg) {
+                        // GNU extension: the comma in ", ## __VA_ARGS__" is removed
+                        // when the variadic arguments are omitted or empty
+                        let trimmed = trim_end_whites(&output.buf);
+                        if trimmed.last() == Some(&b',') {
+                            let len = trimmed.len();
+                            output.buf.truncate(len - 1);
+                        }
+                    } else {
+                        MacroNode::make_expr(arg, &mut output);
+                    }
+                }
                 Action::Stringify(pos) => {
                     MacroNode::make_string(&args[*pos], &mut output);
                 }
@@ -154,6 +205,38 @@ impl MacroFunction {
         self.in_use.set(false);
     }
 
+    /// Expand the macro with the given arguments (the variadic ones included):
+    /// each argument is taken as a whole, even if it contains some commas.
+    /// The missing arguments are empty and the extra ones are ignored when the macro isn't variadic
+    pub fn expand<PC: PreprocContext>(&self, args: &[&[u8]], context: &PC) -> Vec<u8> {
+        // the nodes point into the lexers buffers so they must live until the evaluation
+        let mut lexers = args
+            .iter()
+            .map(|arg| Lexer::<EmptyContext>::new(arg))
+            .collect::<Vec<_>>();
+        let mut args = lexers
+            .iter_mut()
+            .map(|lexer| lexer.get_macro_argument())
+            .collect::<Vec<_>>();
+
+        if let Some(va_pos) = self.va_args {
+            args.resize_with(args.len().max(va_pos), Vec::new);
+            let va = args.split_off(va_pos);
+            args.push(vec![MacroNode::VaArgs(va)]);
+        } else {
+            args.resize_with(self.n_args, Vec::new);
+        }
+
+        let mut out = OutBuf::default();
+        self.eval_parsed_args(&args, context, &FileInfo::default(), &mut out);
+        if let Some(last) = out.last.take() {
+            // a function-like macro name without arguments
+            out.buf.extend_from_slice(last.as_bytes());
+        }
+
+        out.buf
+    }
+
     #[inline(always)]
     pub(crate) fn len(&self) -> usize {
         self.n_args
@@ -164,6 +247,90 @@ impl MacroFunction {
         self.n_args == 0
     }
 
+    /// Check if the two definitions are identical (C++ [cpp.replace]):
+    /// same parameters (with the same spelling) and same replacement lists,
+    /// where all the whites separating tokens are considered identical
+    pub fn is_same_definition(&self, other: &MacroFunction) -> bool {
+        self.params == other.params
+            && self.va_args == other.va_args
+            && self.actions == other.actions
+            && trim_end_whites(&self.out) == trim_end_whites(&other.out)
+    }
+
+    /// Render the parameter list (without the parenthesis)
+    pub fn render_params(&self) -> String {
+        self.params
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                if self.va_args != Some(i) {
+                    p.clone()
+                } else if p == "__VA_ARGS__" {
+                    "...".to_string()
+                } else {
+                    format!("{}...", p)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Render the replacement list where the actions are replaced by the parameters.
+    /// The side of a concatenation with a chunk is guessed: an identifier or a number
+    /// stuck to the argument is an operand of ##
+    pub fn render_body(&self) -> String {
+        #[inline(always)]
+        fn is_id_char(c: u8) -> bool {
+            c.is_ascii_alphanumeric() || c == b'_'
+        }
+
+        fn push_chunk(res: &mut Vec<u8>, chunk: &[u8], concat_right: bool, skip_quote: bool) {
+            let chunk = if skip_quote { &chunk[1..] } else { chunk };
+            if concat_right && matches!(chunk.first(), Some(c) if is_id_char(*c)) {
+                res.extend_from_slice(b" ## ");
+            }
+            res.extend_from_slice(chunk);
+        }
+
+        let mut res = Vec::with_capacity(self.out.len() + 16);
+        let mut out_pos = 0;
+        let mut concat_right = false;
+        let mut skip_quote = false;
+
+        for action in self.actions.iter() {
+            match action {
+                Action::Arg(n) => {
+                    res.extend_from_slice(self.params[*n].as_bytes());
+                    concat_right = false;
+                }
+                Action::Concat(n) | Action::ConcatComma(n) => {
+                    if matches!(res.last(), Some(c) if is_id_char(*c)) {
+                        res.extend_from_slice(b" ## ");
+                    }
+                    res.extend_from_slice(self.params[*n].as_bytes());
+                    concat_right = true;
+                }
+                Action::Stringify(n) => {
+                    // remove the opening quote of the placeholder
+                    res.pop();
+                    res.push(b'#');
+                    res.extend_from_slice(self.params[*n].as_bytes());
+                    concat_right = false;
+                    skip_quote = true;
+                }
+                Action::Chunk(pos) => {
+                    push_chunk(&mut res, &self.out[out_pos..*pos], concat_right, skip_quote);
+                    out_pos = *pos;
+                    concat_right = false;
+                    skip_quote = false;
+                }
+            }
+        }
+        push_chunk(&mut res, &self.out[out_pos..], concat_right, skip_quote);
+
+        String::from_utf8_lossy(trim_end_whites(&res)).into_owned()
+    }
+
     #[inline(always)]
     pub(crate) fn is_valid(&self, n: usize) -> bool {
         if self.va_args.is_none() {
@@ -192,11 +359,7 @@ impl MacroObject {
         context: &PC,
         info: &FileInfo,
     ) {
-        if let Some(last) = out.buf.last() {
-            if *last != b' ' {
-                out.buf.push(b' ');
-            }
-        }
+        out.pad();
 
         if self.has_id {
             let mut lexer = Lexer::<EmptyContext>::new(&self.out);
@@ -208,16 +371,23 @@ impl MacroObject {
         } else {
             out.buf.extend_from_slice(&self.out);
         }
-        if let Some(last) = out.buf.last() {
-            if *last != b' ' {
-                out.buf.push(b' ');
-            }
-        }
+        out.pad();
     }
 
     pub fn get_file_info(&self) -> &FileInfo {
         &self.file_info
     }
+
+    /// Render the replacement list
+    pub fn render(&self) -> String {
+        String::from_utf8_lossy(trim_end_whites(&self.out)).into_owned()
+    }
+
+    /// Check if the two definitions are identical (C++ [cpp.replace]):
+    /// whites separating tokens are considered identical
+    pub fn is_same_definition(&self, other: &MacroObject) -> bool {
+        trim_end_whites(&self.out) == trim_end_whites(&other.out)
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -231,14 +401,10 @@ impl MacroLine {
 
     #[inline(always)]
     pub(crate) fn eval(self, out: &mut OutBuf, info: &FileInfo) {
-        if let Some(last) = out.buf.last() {
-            if *last != b' ' {
-                out.buf.push(b' ');
-            }
-        }
+        out.pad();
 
         tools::extend_with_u32(&mut out.buf, info.line);
-        out.buf.push(b' ');
+        out.pad();
     }
 }
 
@@ -253,16 +419,12 @@ impl MacroFile {
 
     #[inline(always)]
     pub(crate) fn eval<PC: PreprocContext>(self, out: &mut OutBuf, context: &PC, info: &FileInfo) {
-        if let Some(last) = out.buf.last() {
-            if *last != b' ' {
-                out.buf.push(b' ');
-            }
-        }
+        out.pad();
 
         let path = context.get_path(info.source_id.unwrap());
         let path = path.to_str().unwrap();
         out.buf.extend_from_slice(path.as_bytes());
-        out.buf.push(b' ');
+        out.pad();
     }
 }
 
@@ -279,19 +441,24 @@ impl MacroCounter {
         }
     }
 
+    /// The next value
+    pub(crate) fn get(&self) -> u64 {
+        self.value.get()
+    }
+
+    pub(crate) fn set(&self, value: u64) {
+        self.value.set(value);
+    }
+
     #[inline(always)]
     pub(crate) fn eval<'a>(&'a self, out: &mut OutBuf) {
-        if let Some(last) = out.buf.last() {
-            if *last != b' ' {
-                out.buf.push(b' ');
-            }
-        }
+        out.pad();
 
         let n = self.value.get();
         tools::extend_with_u64(&mut out.buf, n);
         self.value.set(n + 1);
 
-        out.buf.push(b' ');
+        out.pad();
     }
 }
 
@@ -752,4 +919,170 @@ mod tests {
 
         assert_eq!(eval!("E", p), "F ");
     }
+
+    #[test]
+    fn test_same_definition() {
+        let mut p = Lexer::<DefaultContext>::new(
+            concat!(
+                "#define A(x, y) x + y\n",
+                "#define B(x, y)   x   +   /* comment */ y  \n",
+                "#define C(x, y) x - y\n",
+                "#define D(a, b) a + b\n",
+                "#define E(x, y) x+y\n",
+                "#define F(x) x + y\n",
+                "#define G 1 + 2\n",
+                "#define H 1\t+     2 \n",
+                "#define I 1 + 3\n",
+            )
+            .as_bytes(),
+        );
+        p.consume_all();
+
+        let same = |x: &str, y: &str| {
+            p.context
+                .get(x)
+                .unwrap()
+                .is_same_definition(p.context.get(y).unwrap())
+        };
+
+        // only the whites differ
+        assert!(same("A", "B"));
+        assert!(same("G", "H"));
+        // a token differs
+        assert!(!same("A", "C"));
+        assert!(!same("G", "I"));
+        // the parameters have a different spelling
+        assert!(!same("A", "D"));
+        // whites separation differs
+        assert!(!same("A", "E"));
+        // not the same number of parameters
+        assert!(!same("A", "F"));
+        // an object and a function
+        assert!(!same("A", "G"));
+    }
+
+    #[test]
+    fn test_render_macro() {
+        let mut p = Lexer::<DefaultContext>::new(
+            concat!(
+                "#define A   x   +  /* one */ 1  \n",
+                "#define B\n",
+                "#define FOO(x) ((x)+1)\n",
+                "#define STR(x, y) #x  #y \"z\"\n",
+                "#define CAT(a, b) a ## b foo ## a a ## _t (a##b)\n",
+                "#define LOG(fmt, ...) printf(fmt, __VA_ARGS__)\n",
+                "#define LOG2(fmt, args...) printf(fmt, args)\n",
+            )
+            .as_bytes(),
+        );
+        p.consume_all();
+
+        assert_eq!(p.context.render_macro("A").unwrap(), "A x + 1");
+        assert_eq!(p.context.render_macro("B").unwrap(), "B");
+        assert_eq!(p.context.render_macro("FOO").unwrap(), "FOO(x) ((x)+1)");
+        assert_eq!(
+            p.context.render_macro("STR").unwrap(),
+            "STR(x, y) #x #y \"z\""
+        );
+        assert_eq!(
+            p.context.render_macro("CAT").unwrap(),
+            "CAT(a, b) a ## b foo ## a a ## _t (a ## b)"
+        );
+        assert_eq!(
+            p.context.render_macro("LOG").unwrap(),
+            "LOG(fmt, ...) printf(fmt, __VA_ARGS__)"
+        );
+        assert_eq!(
+            p.context.render_macro("LOG2").unwrap(),
+            "LOG2(fmt, args...) printf(fmt, args)"
+        );
+        assert_eq!(p.context.render_macro("__LINE__"), None);
+        assert_eq!(p.context.render_macro("NOT_DEFINED"), None);
+    }
+
+    #[test]
+    fn test_eval_named_va() {
+        let mut p = Lexer::<DefaultContext>::new(
+            concat!(
+                "#define LOG(fmt, args...) printf(fmt, args)\n",
+                "#define ELOG(fmt, args...) printf(fmt, ##args)\n",
+                "#define ELOG2(fmt, ...) printf(fmt , ## __VA_ARGS__)\n",
+                "#define test1 LOG(\"x\", a, b)\n",
+                "#define test2 ELOG(\"x\")\n",
+                "#define test3 ELOG(\"x\",)\n",
+                "#define test4 ELOG(\"x\", a, b)\n",
+                "#define test5 ELOG2(\"x\")\n",
+                "#define test6 ELOG2(\"x\", a)\n",
+                "#define G(a, ...) f(a, __VA_ARGS__ ## _x)\n",
+                "#define test7 G(1)\n",
+                "#define test8 G(1, y)\n",
+                "#define ELOG3(fmt, ...) printf(fmt,\t\\\n ## __VA_ARGS__)\n",
+                "#define test9 ELOG3(\"x\")\n",
+                "#define COMMA(...) , ## __VA_ARGS__\n",
+                "#define test10 COMMA()\n",
+            )
+            .as_bytes(),
+        );
+        p.consume_all();
+
+        assert_eq!(eval!("test1", p), "printf( \"x\" , a, b ) ");
+        assert_eq!(eval!("test2", p), "printf( \"x\" ) ");
+        assert_eq!(eval!("test3", p), "printf( \"x\" ) ");
+        assert_eq!(eval!("test4", p), "printf( \"x\" ,a,b) ");
+        assert_eq!(eval!("test5", p), "printf( \"x\" ) ");
+        assert_eq!(eval!("test6", p), "printf( \"x\" ,a) ");
+        assert_eq!(eval!("test7", p), "f( 1 , _x) ");
+        assert_eq!(eval!("test8", p), "f( 1 , y_x) ");
+        assert_eq!(eval!("test9", p), "printf( \"x\" ) ");
+        assert_eq!(eval!("test10", p), "");
+    }
+
+    #[test]
+    fn test_eval_defined_outside_condition() {
+        let mut p = Lexer::<DefaultContext>::new(
+            concat!(
+                "#define N 3\n",
+                "#define M(a) a.defined N\n",
+                "#define test M(s)\n",
+            )
+            .as_bytes(),
+        );
+        p.consume_all();
+
+        assert_eq!(eval!("test", p), "s .defined 3 ");
+    }
+
+    #[test]
+    fn test_expand_function() {
+        let mut p = Lexer::<DefaultContext>::new(
+            concat!(
+                "#define CAT(a, b) a ## b\n",
+                "#define STR(x) #x\n",
+                "#define LOG(fmt, ...) printf(fmt, ## __VA_ARGS__)\n",
+                "#define TWICE(x) x x\n",
+                "#define ONE 1\n",
+            )
+            .as_bytes(),
+        );
+        p.consume_all();
+
+        let expand = |name: &str, args: &[&[u8]]| match p.context.get(name).unwrap() {
+            Macro::Function(mac) => String::from_utf8(mac.expand(args, &p.context)).unwrap(),
+            _ => panic!("not a function macro"),
+        };
+
+        assert_eq!(expand("CAT", &[b"foo", b"bar"]), "foobar");
+        assert_eq!(expand("CAT", &[b"x", b"ONE"]), "xONE");
+        assert_eq!(expand("STR", &[b" a  +  \"b\" "]), "\"a + \\\"b\\\"\"");
+        assert_eq!(expand("STR", &[b"a, (b, c)"]), "\"a, (b,c)\"");
+        assert_eq!(expand("LOG", &[b"\"x\""]), "printf( \"x\" )");
+        assert_eq!(
+            expand("LOG", &[b"\"x\"", b"a", b"b"]),
+            "printf( \"x\" ,a,b)"
+        );
+        assert_eq!(expand("LOG", &[b"\"x\"", b"a, b"]), "printf( \"x\" ,a, b)");
+        assert_eq!(expand("TWICE", &[b"ONE"]), "1 1 ");
+        assert_eq!(expand("TWICE", &[b"f(ONE, 2)"]), "f( 1 ,2) f( 1 ,2) ");
+        assert_eq!(expand("CAT", &[b"foo"]), "foo");
+    }
 }
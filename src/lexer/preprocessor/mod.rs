@@ -7,6 +7,9 @@ pub mod context;
 pub use self::context::*;
 
 pub mod cache;
+pub mod expand;
+pub use self::expand::*;
+
 pub mod include;
 pub mod macros;
 
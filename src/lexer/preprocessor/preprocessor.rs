@@ -6,11 +6,13 @@
 use hashbrown::HashMap;
 
 use super::condition::Condition;
-use super::context::{IfKind, IfState, PreprocContext};
+use super::context::{Conditional, EmptyContext, IfKind, IfState, PreprocContext};
+use super::include::IncludeType;
 use super::macros::{Action, Macro, MacroFunction, MacroObject, MacroType};
+use crate::errors::Span;
 use crate::lexer::buffer::{FileInfo, OutBuf, Position};
 use crate::lexer::errors::LexerError;
-use crate::lexer::lexer::{Lexer, TLexer, Token};
+use crate::lexer::lexer::{Lexer, Location, TLexer, Token};
 use crate::lexer::string::StringType;
 
 #[derive(Clone, Debug, Copy, PartialEq, PartialOrd)]
@@ -118,6 +120,16 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
             Token::PreprocUndef => {
                 self.get_undef();
             }
+            Token::PreprocAssert => {
+                let (pred, answer) = self.get_assertion();
+                if let Some(answer) = answer {
+                    self.context.add_assertion(pred, answer);
+                }
+            }
+            Token::PreprocUnassert => {
+                let (pred, answer) = self.get_assertion();
+                self.context.rm_assertion(pred, answer.as_deref());
+            }
             Token::PreprocIf => {
                 if !self.get_if(IfKind::If, pos.pos) {
                     self.skip_until_else_endif()?;
@@ -152,7 +164,7 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                 self.get_define();
             }
             Token::PreprocPragma => {
-                skip_until!(self, b'\n');
+                self.get_pragma();
                 // we're on the \n so consume it
                 self.buf.inc();
                 self.buf.add_new_line();
@@ -379,8 +391,8 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                         if self.buf.has_char() {
                             let c = self.buf.next_char();
                             if c == b'\n' {
-                                self.buf.add_new_line();
                                 self.buf.inc();
+                                self.buf.add_new_line();
                             } else {
                                 return MacroToken::None(b"\\");
                             }
@@ -429,6 +441,13 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                             last_chunk_end = out.len();
                         }
                         match last_kind {
+                            LastKind::Concat
+                                if va_args == Some(n)
+                                    && out.iter().rev().find(|c| !c.is_ascii_whitespace())
+                                        == Some(&b',') =>
+                            {
+                                actions.push(Action::ConcatComma(n));
+                            }
                             LastKind::Concat => {
                                 actions.push(Action::Concat(n));
                             }
@@ -479,7 +498,15 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
             }
         }
 
-        MacroFunction::new(out, actions, args.len(), va_args, info)
+        // the parameter names are required to compare definitions
+        let mut params: Vec<_> = args.into_iter().map(|(name, i)| (i, name)).collect();
+        params.sort_unstable();
+        let params = params
+            .into_iter()
+            .map(|(_, name)| name.to_string())
+            .collect();
+
+        MacroFunction::new(out, actions, params, va_args, info)
     }
 
     #[inline(always)]
@@ -526,6 +553,8 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
         info: &FileInfo,
     ) {
         let mut fake: Option<String> = None;
+        // in #if, the operand of defined mustn't be expanded (as in GCC)
+        let mut defined_op = false;
         loop {
             let tok = fake
                 .as_ref()
@@ -534,6 +563,13 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                 MacroToken::None(s) => {
                     out.invalidate();
                     out.buf.extend_from_slice(s);
+                    defined_op &= s.iter().all(|c| *c == b'(' || c.is_ascii_whitespace());
+                }
+                MacroToken::Id(id) if out.in_condition && (defined_op || id == "defined") => {
+                    out.invalidate();
+                    out.buf.extend_from_slice(id.as_bytes());
+                    defined_op = !defined_op;
+                    fake = None;
                 }
                 MacroToken::Id(id) => {
                     out.invalidate();
@@ -645,7 +681,7 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
             let spos = self.buf.pos();
             skip_whites!(self);
             if self.stop_skipping()? {
-                return Ok(());
+                return self.end_skipping();
             }
             if spos == self.buf.pos() || self.buf.prev_char() != b'\n' {
                 break;
@@ -671,7 +707,7 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                             let spos = self.buf.pos();
                             skip_whites!(self);
                             if self.stop_skipping()? {
-                                return Ok(());
+                                return self.end_skipping();
                             }
                             if spos == self.buf.pos() || self.buf.prev_char() != b'\n' {
                                 break;
@@ -688,6 +724,20 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                 break;
             }
         }
+        self.end_skipping()
+    }
+
+    /// In strict mode, a skipped block must be terminated before the end of the buffer:
+    /// a nested #if without #endif eats the #endif of the enclosing block
+    fn end_skipping(&self) -> Result<(), LexerError> {
+        if self.context.strict_conditionals()
+            && matches!(
+                self.context.if_state(),
+                Some(IfState::Skip(_)) | Some(IfState::SkipAndSwitch(_))
+            )
+        {
+            return Err(LexerError::UnterminatedConditional { sp: self.span() });
+        }
         Ok(())
     }
 
@@ -733,6 +783,7 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
 
     #[inline(always)]
     pub(crate) fn get_if(&mut self, kind: IfKind, pos: usize) -> bool {
+        self.record_conditional(kind.clone());
         let must_eval = if let Some(state) = self.context.if_state() {
             std::mem::discriminant(state) == std::mem::discriminant(&IfState::Eval(0))
         } else {
@@ -748,11 +799,13 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                 }
                 IfKind::Ifdef => {
                     let id = self.get_preproc_identifier();
-                    self.context.defined(id)
+                    self.check_extra_tokens("ifdef");
+                    self.is_defined(id)
                 }
                 IfKind::Ifndef => {
                     let id = self.get_preproc_identifier();
-                    !self.context.defined(id)
+                    self.check_extra_tokens("ifndef");
+                    !self.is_defined(id)
                 }
             };
 
@@ -775,29 +828,117 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
         }
     }
 
+    /// Report the conditional directive, evaluated or not, with the macros written in
+    /// its condition: the condition is lexed without expansion and isn't consumed
+    fn record_conditional(&mut self, kind: IfKind) {
+        if !self.context.record_conditionals() {
+            return;
+        }
+
+        skip_whites!(self);
+        let start = self.location();
+        let text = self.buf.remainder_as_str().as_bytes();
+        let mut lexer = Lexer::<EmptyContext>::new(text);
+        let mut macros: Vec<String> = Vec::new();
+        let mut len = 0;
+
+        loop {
+            // GNU assertion: #predicate(answer)
+            skip_whites!(lexer);
+            if lexer.buf.has_char() && lexer.buf.next_char() == b'#' {
+                lexer.buf.inc();
+                lexer.get_assertion();
+                len = lexer.buf.pos();
+                continue;
+            }
+
+            match lexer.next_token() {
+                Token::Eol | Token::Eof => break,
+                Token::Comment | Token::RetainedComment(_) => continue,
+                Token::Identifier(id) => match id.as_str() {
+                    "defined" => {}
+                    "__has_cpp_attribute"
+                    | "__has_attribute"
+                    | "__has_include"
+                    | "__has_include_next" => {
+                        // the argument isn't a macro
+                        if lexer.next_useful() == Token::LeftParen {
+                            let mut level = 0;
+                            loop {
+                                match lexer.next_token() {
+                                    Token::LeftParen => level += 1,
+                                    Token::RightParen if level == 0 => break,
+                                    Token::RightParen => level -= 1,
+                                    Token::Eol | Token::Eof => break,
+                                    _ => {}
+                                }
+                            }
+                        }
+                    }
+                    _ => {
+                        if !macros.contains(&id) {
+                            macros.push(id);
+                        }
+                        if kind != IfKind::If {
+                            len = lexer.buf.pos();
+                            break;
+                        }
+                    }
+                },
+                _ => {}
+            }
+            len = lexer.buf.pos();
+        }
+
+        let span = self.condition_span(start, &text[..len]);
+        self.context.conditional(Conditional { kind, span, macros });
+    }
+
+    /// Get the span of the condition text starting at start
+    fn condition_span(&self, start: Location, text: &[u8]) -> Span {
+        let lines = text.iter().filter(|c| **c == b'\n').count() as u32;
+        let column = match text.iter().rposition(|c| *c == b'\n') {
+            Some(nl) => (text.len() - nl) as u32,
+            None => start.column + text.len() as u32,
+        };
+        let end = Location {
+            pos: start.pos + text.len(),
+            line: start.line + lines,
+            column,
+        };
+
+        Span {
+            file: self.buf.get_source_id(),
+            start,
+            end,
+        }
+    }
+
     #[inline(always)]
     pub(crate) fn get_elif(&mut self, pos: Position) -> bool {
         // elif == else if
         if let Some(state) = self.context.if_state() {
             let file_id = self.buf.get_source_id().unwrap();
             let spos = pos.pos;
-            match state {
+            match state.clone() {
                 IfState::Eval(prev) => {
+                    self.record_conditional(IfKind::If);
                     if let Some(next) = self.context.skip_until_next(file_id, spos) {
                         self.buf.reset_pos(next);
                     } else {
-                        self.context.save_switch(file_id, *prev, pos);
+                        self.context.save_switch(file_id, prev, pos);
                     }
                     self.context.if_change(IfState::Skip(spos));
                     false
                 }
                 IfState::Skip(prev) => {
-                    self.context.save_switch(file_id, *prev, pos);
+                    self.record_conditional(IfKind::If);
+                    self.context.save_switch(file_id, prev, pos);
                     self.context.if_change(IfState::Skip(spos));
                     false
                 }
                 IfState::SkipAndSwitch(prev) => {
-                    self.context.save_switch(file_id, *prev, pos);
+                    self.context.save_switch(file_id, prev, pos);
                     self.context.rm_if();
                     self.get_if(IfKind::If, spos)
                 }
@@ -865,22 +1006,152 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
         skip_whites!(self);
         let name = self.get_preproc_identifier();
         //self.debug(&format!("DEFINE {}", name));
-        if self.buf.has_char() {
+
+        // a function-like macro has a '(' just after its name:
+        // #define F() is a function and #define F () is an object expanding to ()
+        if self.buf.has_char() && self.buf.next_char() == b'(' {
+            self.buf.inc();
+            let (args, va_args) = self.get_macro_arguments();
+            skip_whites!(self);
+            let mac = self.get_function_definition(args, va_args, info);
+            let identical = match self.context.get(name) {
+                Some(Macro::Function(old)) => old.is_same_definition(&mac),
+                Some(_) => false,
+                None => true,
+            };
+            self.check_redefinition(name, identical);
+            self.context.add_function(name.to_string(), mac);
+        } else {
+            skip_whites!(self);
+            let obj = self.get_object_definition(info);
+            let identical = match self.context.get(name) {
+                Some(Macro::Object(old)) => old.is_same_definition(&obj),
+                Some(_) => false,
+                None => true,
+            };
+            self.check_redefinition(name, identical);
+            self.context.add_object(name.to_string(), obj);
+        }
+    }
+
+    #[inline(always)]
+    fn get_pragma(&mut self) {
+        // only #pragma once, region/endregion and GCC diagnostic are handled,
+        // the others are skipped
+        let start = self.start;
+        let kpos = self.buf.pos();
+        let kind = self.get_preproc_identifier();
+        skip_whites!(self);
+        let spos = self.buf.pos();
+        skip_until!(self, b'\n');
+        self.pragma = Some(self.buf.slice(kpos));
+
+        match kind {
+            "region" => {
+                let name = String::from_utf8_lossy(self.buf.slice(spos))
+                    .trim_end()
+                    .to_string();
+                self.context.start_region(name, start);
+            }
+            "endregion" => {
+                let end = self.location();
+                self.context.end_region(end);
+            }
+            "once" => {
+                if let Some(file) = self.buf.get_file_id() {
+                    self.context.add_pragma_once(file);
+                }
+            }
+            "GCC" => {
+                let args = String::from_utf8_lossy(self.buf.slice(spos));
+                let mut args = args.split_whitespace();
+                if args.next() == Some("diagnostic") {
+                    // the unknown diagnostic kinds are ignored
+                    match (args.next(), args.next()) {
+                        (Some("push"), _) => self.context.push_diagnostics(),
+                        (Some("pop"), _) if !self.context.pop_diagnostics() => {
+                            self.errors
+                                .push(LexerError::DiagnosticPopWithoutPush { sp: self.span() });
+                        }
+                        (Some("pop"), _) => {}
+                        (Some(kind), Some(option)) => {
+                            let option = option.trim_matches('"');
+                            match kind {
+                                "ignored" => self.context.ignore_diagnostic(option, true),
+                                "warning" | "error" => {
+                                    self.context.ignore_diagnostic(option, false)
+                                }
+                                _ => {}
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    #[inline(always)]
+    fn check_extra_tokens(&mut self, directive: &str) {
+        // only whites and comments are expected until the end of the line:
+        // the extra tokens are skipped (as gcc does) but a diagnostic is recorded
+        loop {
+            skip_whites!(self);
+            if !self.buf.has_char() {
+                return;
+            }
+
             let c = self.buf.next_char();
-            if c == b'(' {
-                self.buf.inc();
-                let (args, va_args) = self.get_macro_arguments();
-                skip_whites!(self);
-                let mac = self.get_function_definition(args, va_args, info);
-                self.context.add_function(name.to_string(), mac);
-            } else {
-                skip_whites!(self);
-                let obj = self.get_object_definition(info);
-                self.context.add_object(name.to_string(), obj);
+            if c == b'\n' {
+                return;
             }
+
+            if c == b'/' && self.buf.has_char_n(1) {
+                match self.buf.next_char_n(1) {
+                    b'/' => {
+                        self.skip_single_comment();
+                        return;
+                    }
+                    b'*' => {
+                        self.buf.inc_n(2);
+                        self.skip_multiline_comment();
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+
+            self.errors.push(LexerError::ExtraTokens {
+                sp: self.span(),
+                directive: directive.to_string(),
+            });
+            skip_until!(self, b'\n');
+            return;
         }
     }
 
+    #[inline(always)]
+    fn check_redefinition(&mut self, name: &str, identical: bool) {
+        // a redefinition is only allowed when the replacement lists are identical:
+        // it's just a diagnostic so the new definition is used anyway
+        if !identical {
+            self.errors.push(LexerError::MacroRedefinition {
+                sp: self.span(),
+                name: name.to_string(),
+            });
+        }
+    }
+
+    /// Check if a macro is defined: the operators handled by the preprocessor
+    /// (e.g. __has_include) are defined too
+    fn is_defined(&mut self, name: &str) -> bool {
+        matches!(
+            name,
+            "__has_cpp_attribute" | "__has_attribute" | "__has_include" | "__has_include_next"
+        ) || self.context.defined(name)
+    }
+
     #[inline(always)]
     pub(crate) fn get_defined(&mut self, skip: bool) -> u64 {
         skip_whites!(self);
@@ -902,13 +1173,81 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                 self.get_preproc_identifier()
             };
             if !skip {
-                return self.context.defined(name) as u64;
+                return self.is_defined(name) as u64;
             }
         }
 
         0
     }
 
+    #[inline(always)]
+    pub(crate) fn get_has_cpp_attribute(&mut self, skip: bool) -> u64 {
+        // __has_cpp_attribute ( attribute-token )
+        // attribute-token: identifier or attribute-namespace :: identifier
+        skip_whites!(self);
+        if !self.buf.has_char() || self.buf.next_char() != b'(' {
+            return 0;
+        }
+        self.buf.inc();
+        skip_whites!(self);
+
+        let mut name = self.get_preproc_identifier();
+        let mut namespace = None;
+        skip_whites!(self);
+
+        if self.buf.has_char() && self.buf.next_char() == b':' {
+            self.buf.inc();
+            if self.buf.has_char() && self.buf.next_char() == b':' {
+                self.buf.inc();
+            }
+            skip_whites!(self);
+            namespace = Some(name);
+            name = self.get_preproc_identifier();
+            skip_whites!(self);
+        }
+
+        if self.buf.has_char() && self.buf.next_char() == b')' {
+            self.buf.inc();
+        }
+
+        if skip || namespace.is_some() {
+            0
+        } else {
+            get_cpp_attribute_version(name)
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn get_has_include(&mut self, next: bool, skip: bool) -> u64 {
+        // __has_include ( header-name ) or __has_include_next ( header-name )
+        skip_whites!(self);
+        if !self.buf.has_char() || self.buf.next_char() != b'(' {
+            return 0;
+        }
+        self.buf.inc();
+
+        let path = self.get_path();
+        skip_whites!(self);
+        if self.buf.has_char() && self.buf.next_char() == b')' {
+            self.buf.inc();
+        }
+
+        let (angle, path) = match path {
+            IncludeType::Quote(path) => (false, path),
+            IncludeType::Angle(path) => (true, path),
+            _ => return 0,
+        };
+        if skip {
+            return 0;
+        }
+
+        let source_id = self.buf.get_source_id().unwrap();
+        let path_index = self.buf.get_path_index().unwrap();
+        self.context
+            .find(angle, path, next, source_id, path_index)
+            .is_some() as u64
+    }
+
     #[inline(always)]
     pub(crate) fn get_undef(&mut self) {
         skip_whites!(self);
@@ -916,6 +1255,65 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
         //self.debug(&format!("UNDEF {}", name));
         self.context.undef(name);
     }
+
+    /// Get the predicate and the answer in predicate(answer)
+    /// (the whitespaces in the answer are normalized)
+    pub(crate) fn get_assertion(&mut self) -> (&'a str, Option<String>) {
+        skip_whites!(self);
+        let pred = self.get_preproc_identifier();
+        skip_whites!(self);
+        if !self.buf.has_char() || self.buf.next_char() != b'(' {
+            return (pred, None);
+        }
+        self.buf.inc();
+
+        let spos = self.buf.pos();
+        let mut level = 0;
+        while self.buf.has_char() {
+            match self.buf.next_char() {
+                b'(' => level += 1,
+                b')' if level == 0 => break,
+                b')' => level -= 1,
+                b'\n' => break,
+                _ => {}
+            }
+            self.buf.inc();
+        }
+
+        let answer = String::from_utf8_lossy(self.buf.slice(spos))
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if self.buf.has_char() && self.buf.next_char() == b')' {
+            self.buf.inc();
+        }
+
+        (pred, Some(answer))
+    }
+}
+
+/// Get the value of __has_cpp_attribute for the standard attributes
+/// (the date of the standard or of the paper which introduced them)
+fn get_cpp_attribute_version(name: &str) -> u64 {
+    // [[__nodiscard__]] is the same as [[nodiscard]]
+    let name = name
+        .strip_prefix("__")
+        .and_then(|n| n.strip_suffix("__"))
+        .unwrap_or(name);
+
+    match name {
+        "assume" => 202207,
+        "carries_dependency" => 200809,
+        "deprecated" => 201309,
+        "fallthrough" => 201603,
+        "likely" => 201803,
+        "maybe_unused" => 201603,
+        "no_unique_address" => 201803,
+        "nodiscard" => 201907,
+        "noreturn" => 200809,
+        "unlikely" => 201803,
+        _ => 0,
+    }
 }
 
 #[cfg(test)]
@@ -1033,6 +1431,58 @@ mod tests {
         assert_eq!(p.next_token(), Token::LiteralInt(56));
     }
 
+    #[test]
+    fn test_assert_unassert() {
+        let mut p = Lexer::<DefaultContext>::new(
+            concat!(
+                "#assert machine(x86 64)\n",
+                "#assert machine(arm)\n",
+                "#if #machine(x86  64) && #machine (arm) && #machine\n",
+                "#define a\n",
+                "#endif\n",
+                "#if #machine(mips) || #cpu\n",
+                "#define b\n",
+                "#endif\n",
+                "#unassert machine(arm)\n",
+                "#if #machine(arm)\n",
+                "#define c\n",
+                "#endif\n",
+                "#if #machine(x86 64)\n",
+                "#define d\n",
+                "#endif\n",
+                "#unassert machine\n",
+                "#if #machine\n",
+                "#define e\n",
+                "#endif\n",
+            )
+            .as_bytes(),
+        );
+        p.consume_all();
+
+        assert!(p.context.defined("a"));
+        assert!(!p.context.defined("b"));
+        assert!(!p.context.defined("c"));
+        assert!(p.context.defined("d"));
+        assert!(!p.context.defined("e"));
+    }
+
+    #[test]
+    fn test_if_char() {
+        for cond in &["'A' == 0x41", "'\\n' == 10"] {
+            let src = format!(
+                "#if {}\n#define foo 56\n#else\n#define foo 78\n#endif\nfoo",
+                cond
+            );
+            let mut p = Lexer::<DefaultContext>::new(src.as_bytes());
+
+            assert_eq!(p.next_token(), Token::PreprocIf);
+            assert_eq!(p.next_token(), Token::PreprocDefine);
+            assert_eq!(p.next_token(), Token::PreprocElse);
+            assert_eq!(p.next_token(), Token::Eol);
+            assert_eq!(p.next_token(), Token::LiteralInt(56));
+        }
+    }
+
     #[test]
     fn test_if_else_nested() {
         let mut p = Lexer::<DefaultContext>::new(
@@ -1307,6 +1757,87 @@ mod tests {
         assert_eq!(p.next_token(), Token::LiteralInt(9));
     }
 
+    #[test]
+    fn test_line_splice() {
+        let mut p = Lexer::<DefaultContext>::new(
+            concat!(
+                "#define add(a, b) a + b\n", // 1
+                "add(__LINE__, \\\n",        // 2
+                "    __LINE__) __LINE__\n",  // 3
+                "add(1,\n",                  // 4
+                "    2) __LINE__\n",         // 5
+                "__LI\\\n",                  // 6
+                "NE__ __LINE__\n",           // 7
+                "in\\\n",                    // 8
+                "t x = __LINE__;\n",         // 9
+            )
+            .as_bytes(),
+        );
+
+        assert_eq!(p.next_token(), Token::PreprocDefine);
+        // the line of a multi-line macro invocation is the line of its end
+        assert_eq!(p.next_token(), Token::LiteralInt(3));
+        assert_eq!(p.next_token(), Token::Plus);
+        assert_eq!(p.next_token(), Token::LiteralInt(3));
+        assert_eq!(p.next_token(), Token::LiteralInt(3));
+        assert_eq!(p.next_token(), Token::Eol);
+        assert_eq!(p.next_token(), Token::LiteralInt(1));
+        assert_eq!(p.next_token(), Token::Plus);
+        assert_eq!(p.next_token(), Token::LiteralInt(2));
+        assert_eq!(p.next_token(), Token::LiteralInt(5));
+        assert_eq!(p.next_token(), Token::Eol);
+        // a token spanning continued lines
+        assert_eq!(p.next_token(), Token::LiteralInt(7));
+        assert_eq!(p.next_token(), Token::LiteralInt(7));
+        assert_eq!(p.next_token(), Token::Eol);
+        assert_eq!(p.next_token(), Token::Int);
+        assert_eq!(p.get_line(), 9);
+        assert_eq!(p.next_token(), Token::Identifier("x".to_string()));
+        assert_eq!(p.next_token(), Token::Equal);
+        assert_eq!(p.next_token(), Token::LiteralInt(9));
+    }
+
+    #[test]
+    fn test_line_raw_string() {
+        let mut p = Lexer::<DefaultContext>::new(
+            concat!(
+                "R\"(line1\n",         // 1
+                "line2)\" __LINE__\n", // 2
+                "R\"x(a)\n",           // 3
+                ")x\n",                // 4
+                ")x\"\n",              // 5
+                "__LINE__\n",          // 6
+            )
+            .as_bytes(),
+        );
+
+        assert_eq!(
+            p.next_token(),
+            Token::LiteralRString("line1\nline2".to_string())
+        );
+        assert_eq!(p.next_token(), Token::LiteralInt(2));
+        assert_eq!(p.next_token(), Token::Eol);
+        assert_eq!(
+            p.next_token(),
+            Token::LiteralRString("a)\n)x\n".to_string())
+        );
+        assert_eq!(p.next_token(), Token::Eol);
+        assert_eq!(p.next_token(), Token::LiteralInt(6));
+
+        let mut p = Lexer::<DefaultContext>::new(
+            concat!(
+                "#define id(x) x\n", // 1
+                "id(u8R\"(a\n",      // 2
+                "b)\") __LINE__\n",  // 3
+            )
+            .as_bytes(),
+        );
+
+        assert_eq!(p.next_token(), Token::PreprocDefine);
+        assert_eq!(p.next_token(), Token::LiteralU8RString("a\nb".to_string()));
+        assert_eq!(p.next_token(), Token::LiteralInt(3));
+    }
+
     #[test]
     fn test_counter() {
         let mut p = Lexer::<DefaultContext>::new(
@@ -1345,6 +1876,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_error_directive_location() {
+        let mut p = Lexer::<DefaultContext>::new(
+            concat!(
+                "int a; /* a\n",
+                "comment */ int b = \\\n",
+                "    1; // a comment \\\n",
+                "on two lines\n",
+                "  #error foo\n",
+            )
+            .as_bytes(),
+        );
+
+        p.consume_all();
+        assert_eq!(p.errors.len(), 1);
+        if let LexerError::ErrorDirective { sp, .. } = &p.errors[0] {
+            assert_eq!((sp.start.line, sp.start.column), (5, 3));
+            assert_eq!((sp.end.line, sp.end.column), (5, 13));
+        } else {
+            panic!("mismatch. Was: {:?}", p.errors[0]);
+        }
+    }
+
     #[test]
     fn test_endif_without_preceeding_if() {
         let mut p =
@@ -1362,6 +1916,332 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_strict_conditionals() {
+        let code = concat!("#if 0\n", "#if X\n", "#endif\n", "int x;\n");
+
+        let mut p = Lexer::<DefaultContext>::new(code.as_bytes());
+        p.consume_all();
+        assert!(p.errors.is_empty());
+
+        let mut p = Lexer::<DefaultContext>::new(code.as_bytes());
+        p.context.set_strict_conditionals(true);
+        p.consume_all();
+        assert_eq!(p.errors.len(), 1);
+        assert!(matches!(
+            p.errors[0],
+            LexerError::UnterminatedConditional { .. }
+        ));
+
+        let mut p = Lexer::<DefaultContext>::new(
+            concat!("#if 0\n", "#if X\n", "#endif\n", "#endif\n", "int x;\n").as_bytes(),
+        );
+        p.context.set_strict_conditionals(true);
+        p.consume_all();
+        assert!(p.errors.is_empty());
+    }
+
+    #[test]
+    fn test_pragma_region() {
+        let mut p = Lexer::<DefaultContext>::new(
+            concat!(
+                "#pragma region Outer part\n",
+                "int a;\n",
+                "#pragma once\n",
+                "  #pragma region Inner\n",
+                "int b;\n",
+                "#pragma endregion\n",
+                "#pragma endregion Outer part\n",
+                "int c;\n",
+            )
+            .as_bytes(),
+        );
+
+        let toks: Vec<_> = std::iter::repeat_with(|| p.next_token())
+            .take_while(|t| *t != Token::Eof)
+            .filter(|t| *t != Token::PreprocPragma && *t != Token::Eol)
+            .collect();
+        assert_eq!(toks.len(), 9);
+
+        let regions: Vec<_> = p
+            .context
+            .regions()
+            .iter()
+            .map(|r| {
+                (
+                    r.name.as_str(),
+                    (r.start.line, r.start.pos),
+                    r.end.map(|e| (e.line, e.pos)),
+                )
+            })
+            .collect();
+        assert_eq!(
+            regions,
+            vec![
+                ("Outer part", (1, 0), Some((7, 122))),
+                ("Inner", (4, 48), Some((6, 93))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pragma_diagnostic() {
+        let mut p = Lexer::<DefaultContext>::new(
+            concat!(
+                "#pragma GCC diagnostic push\n",
+                "#pragma GCC diagnostic ignored \"-Wunused\"\n",
+                "#pragma GCC diagnostic ignored \"-Wshadow\"\n",
+                "#pragma GCC diagnostic warning \"-Wshadow\"\n",
+                "#pragma GCC diagnostic foo \"-Wbar\"\n",
+                "int a;\n",
+                "#pragma GCC diagnostic pop\n",
+                "int b;\n",
+                "#pragma GCC diagnostic pop\n",
+            )
+            .as_bytes(),
+        );
+
+        let next_int = |p: &mut Lexer<DefaultContext>| loop {
+            match p.next_token() {
+                Token::Int | Token::Eof => break,
+                _ => {}
+            }
+        };
+
+        next_int(&mut p);
+        assert_eq!(p.context.ignored_diagnostics(), &["-Wunused".to_string()]);
+
+        next_int(&mut p);
+        assert!(p.context.ignored_diagnostics().is_empty());
+
+        p.consume_all();
+        assert!(p.context.ignored_diagnostics().is_empty());
+        assert_eq!(p.errors.len(), 1);
+        assert!(matches!(
+            p.errors[0],
+            LexerError::DiagnosticPopWithoutPush { .. }
+        ));
+    }
+
+    #[test]
+    fn test_conditionals_using() {
+        let code = concat!(
+            "#define FOO 2\n",
+            "#if FOO > 1 && defined(BAR)\n",
+            "#endif\n",
+            "#ifdef FOO\n",
+            "#endif\n",
+            "#if BAR // FOO\n",
+            "#elif BAR || \\\n",
+            "  FOOBAR + FOO\n",
+            "#endif\n",
+            "#ifndef BAR\n",
+            "#endif\n",
+        );
+
+        let mut p = Lexer::<DefaultContext>::new(code.as_bytes());
+        p.consume_all();
+        assert!(p.context.conditionals().is_empty());
+
+        let mut p = Lexer::<DefaultContext>::new(code.as_bytes());
+        p.context.set_record_conditionals(true);
+        p.consume_all();
+
+        assert_eq!(p.context.conditionals().len(), 5);
+
+        let conds = p.context.conditionals_using("FOO");
+        assert_eq!(
+            conds.iter().map(|c| c.kind.clone()).collect::<Vec<_>>(),
+            vec![IfKind::If, IfKind::Ifdef, IfKind::If]
+        );
+        assert_eq!(conds[0].macros, vec!["FOO", "BAR"]);
+        assert_eq!(conds[2].macros, vec!["BAR", "FOOBAR", "FOO"]);
+
+        let lines = conds
+            .iter()
+            .map(|c| (c.span.start.line, c.span.end.line))
+            .collect::<Vec<_>>();
+        assert_eq!(lines, vec![(2, 2), (4, 4), (7, 8)]);
+        assert_eq!(conds[0].span.start.column, 5);
+        assert_eq!(conds[0].span.end.column, 28);
+        assert_eq!(conds[2].span.end.column, 15);
+
+        assert_eq!(p.context.conditionals_using("BAR").len(), 4);
+        assert!(p.context.conditionals_using("BAZ").is_empty());
+    }
+
+    #[test]
+    fn test_conditionals_not_evaluated() {
+        let mut p = Lexer::<DefaultContext>::new(
+            concat!(
+                "#define FOO 1\n",
+                "#if FOO\n",
+                "#elif BAR && __has_include(<FOO.h>)\n",
+                "#if FOO /* BAR */ || #machine(BAR)\n",
+                "#endif\n",
+                "#endif\n",
+                "#if 0\n",
+                "#ifdef FOO\n",
+                "#elif defined(FOO)\n",
+                "#endif\n",
+                "#endif\n",
+            )
+            .as_bytes(),
+        );
+        p.context.set_record_conditionals(true);
+        p.consume_all();
+
+        let expected = |kind, line, macros: &[&str]| {
+            (
+                kind,
+                line,
+                macros.iter().map(|m| m.to_string()).collect::<Vec<_>>(),
+            )
+        };
+        assert_eq!(
+            p.context
+                .conditionals()
+                .iter()
+                .map(|c| (c.kind.clone(), c.span.start.line, c.macros.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                expected(IfKind::If, 2, &["FOO"]),
+                expected(IfKind::If, 3, &["BAR"]),
+                expected(IfKind::If, 4, &["FOO"]),
+                expected(IfKind::If, 7, &[]),
+                expected(IfKind::Ifdef, 8, &["FOO"]),
+                expected(IfKind::If, 9, &["FOO"]),
+            ]
+        );
+        assert_eq!(p.context.conditionals()[2].span.end.column, 35);
+        assert_eq!(p.context.conditionals_using("FOO").len(), 4);
+    }
+
+    #[test]
+    fn test_define_zero_params() {
+        let mut p = Lexer::<DefaultContext>::new(
+            concat!(
+                "#define F() 1\n",
+                "#define G () 2\n",
+                "F() F G G()\n",
+                "#define H",
+            )
+            .as_bytes(),
+        );
+
+        p.consume_tokens(2);
+        let toks: Vec<_> = std::iter::repeat_with(|| p.next_token())
+            .take_while(|t| *t != Token::Eof)
+            .filter(|t| *t != Token::Eol)
+            .collect();
+        assert_eq!(
+            toks,
+            vec![
+                Token::LiteralInt(1),
+                Token::Identifier("F".to_string()),
+                Token::LeftParen,
+                Token::RightParen,
+                Token::LiteralInt(2),
+                Token::LeftParen,
+                Token::RightParen,
+                Token::LiteralInt(2),
+                Token::LeftParen,
+                Token::RightParen,
+                Token::PreprocDefine,
+            ]
+        );
+        assert!(p.context.get("F").is_some());
+        assert!(p.context.get("G").is_some());
+        assert!(p.context.get("H").is_some());
+    }
+
+    #[test]
+    fn test_macro_redefinition_identical() {
+        let mut p = Lexer::<DefaultContext>::new(
+            concat!(
+                "#define foo(a, b) a   +  b\n",
+                "#define foo(a, b) a + /* comment */ b \n",
+                "#define bar 1\n",
+                "#define bar  1\n",
+                "foo(bar, 2)",
+            )
+            .as_bytes(),
+        );
+
+        p.consume_tokens(4);
+        assert_eq!(p.next_token(), Token::LiteralInt(1));
+        assert!(p.errors.is_empty());
+    }
+
+    #[test]
+    fn test_macro_redefinition_conflict() {
+        let mut p = Lexer::<DefaultContext>::new(
+            concat!(
+                "#define foo(a, b) a + b\n",
+                "#define foo(a, b) a - b\n",
+                "#define bar 1\n",
+                "#define bar 2\n",
+                "foo(bar, 2)",
+            )
+            .as_bytes(),
+        );
+
+        p.consume_tokens(4);
+        assert_eq!(p.next_token(), Token::LiteralInt(2));
+        assert_eq!(p.next_token(), Token::Minus);
+        assert_eq!(p.errors.len(), 2);
+        for (err, expected) in p.errors.iter().zip(&["foo", "bar"]) {
+            if let LexerError::MacroRedefinition { name, .. } = err {
+                assert_eq!(name, expected);
+            } else {
+                panic!("mismatch. Was: {:?}", err);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ifdef_extra_tokens() {
+        let mut p = Lexer::<DefaultContext>::new(
+            concat!(
+                "#define FOO\n",
+                "#ifdef FOO /* comment */ // comment\n",
+                "#endif\n",
+                "#ifndef FOO\t\n",
+                "#endif\n",
+                "1",
+            )
+            .as_bytes(),
+        );
+
+        let tok =
+            std::iter::repeat_with(|| p.next_token()).find(|t| matches!(t, Token::LiteralInt(_)));
+        assert_eq!(tok, Some(Token::LiteralInt(1)));
+        assert!(p.errors.is_empty());
+
+        let mut p = Lexer::<DefaultContext>::new(
+            concat!(
+                "#ifdef FOO == 1\n",
+                "#endif\n",
+                "#ifndef FOO BAR\n",
+                "2\n",
+                "#endif\n",
+            )
+            .as_bytes(),
+        );
+
+        let tok =
+            std::iter::repeat_with(|| p.next_token()).find(|t| matches!(t, Token::LiteralInt(_)));
+        assert_eq!(tok, Some(Token::LiteralInt(2)));
+        assert_eq!(p.errors.len(), 2);
+        for (err, expected) in p.errors.iter().zip(&["ifdef", "ifndef"]) {
+            if let LexerError::ExtraTokens { directive, .. } = err {
+                assert_eq!(directive, expected);
+            } else {
+                panic!("mismatch. Was: {:?}", err);
+            }
+        }
+    }
+
     #[test]
     fn test_if_cache1() {
         let cache = Arc::new(IfCache::default());
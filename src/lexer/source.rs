@@ -7,7 +7,7 @@ use hashbrown::{hash_map, HashMap};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
-#[derive(Debug, Clone, Copy, Default, Hash, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, Hash, PartialEq, Eq)]
 pub struct FileId(pub u32);
 
 #[derive(Debug)]
@@ -0,0 +1,263 @@
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use super::lexer::{cpp_kw_spelling, preproc_kw_spelling, Token};
+
+fn push_escaped(buf: &mut String, c: char, quote: char) {
+    match c {
+        '\\' => buf.push_str("\\\\"),
+        '\n' => buf.push_str("\\n"),
+        '\t' => buf.push_str("\\t"),
+        '\r' => buf.push_str("\\r"),
+        c if c == quote => {
+            buf.push('\\');
+            buf.push(c);
+        }
+        // octal escapes have at most 3 digits so the next char can't be eaten
+        c if (c as u32) < 0x20 || c as u32 == 0x7f => {
+            write!(buf, "\\{:03o}", c as u32).unwrap();
+        }
+        c => buf.push(c),
+    }
+}
+
+fn char_lit(prefix: &str, c: u32, suffix: &str) -> Cow<'static, str> {
+    let mut buf = format!("{}'", prefix);
+    match std::char::from_u32(c) {
+        Some(c) if c.is_ascii() => push_escaped(&mut buf, c, '\''),
+        _ if c <= 0xffff => write!(buf, "\\u{:04x}", c).unwrap(),
+        _ => write!(buf, "\\U{:08x}", c).unwrap(),
+    }
+    buf.push('\'');
+    buf.push_str(suffix);
+    Cow::Owned(buf)
+}
+
+fn string_lit(prefix: &str, s: &str, suffix: &str) -> Cow<'static, str> {
+    let mut buf = format!("{}\"", prefix);
+    for c in s.chars() {
+        push_escaped(&mut buf, c, '"');
+    }
+    buf.push('"');
+    buf.push_str(suffix);
+    Cow::Owned(buf)
+}
+
+fn raw_string_lit(prefix: &str, s: &str, suffix: &str) -> Cow<'static, str> {
+    // find a delimiter which doesn't appear in the string
+    let mut delim = String::new();
+    while s.contains(&format!("){}\"", delim)) {
+        delim.push('x');
+    }
+    Cow::Owned(format!(
+        "{}R\"{}({}){}\"{}",
+        prefix, delim, s, delim, suffix
+    ))
+}
+
+fn float_lit(x: f64, suffix: &str) -> Cow<'static, str> {
+    // the debug format always has a dot or an exponent
+    Cow::Owned(format!("{:?}{}", x, suffix))
+}
+
+fn int_lit(n: u64, suffix: &str) -> Cow<'static, str> {
+    Cow::Owned(format!("{}{}", n, suffix))
+}
+
+impl Token {
+    /// Get the source spelling of the token: the canonical one for punctuators and
+    /// keywords and a spelling giving back the same token for identifiers and literals.
+    /// Tokens without spelling (e.g. Eof or comments) give None.
+    pub fn spelling(&self) -> Option<Cow<'_, str>> {
+        use Token::*;
+
+        if let Some(kw) = cpp_kw_spelling(self) {
+            return Some(Cow::Borrowed(kw));
+        }
+
+        if let Some(kw) = preproc_kw_spelling(self) {
+            return Some(Cow::Owned(format!("#{}", kw)));
+        }
+
+        let s = match self {
+            None | Eof | Eol | Comment | RetainedComment(_) => {
+                return Option::None;
+            }
+            Identifier(id) => return Some(Cow::Borrowed(id)),
+            Not => "!",
+            NotEqual => "!=",
+            Dollar => "$",
+            Modulo => "%",
+            ModuloEqual => "%=",
+            AndAnd => "&&",
+            And => "&",
+            AndEqual => "&=",
+            LeftParen => "(",
+            RightParen => ")",
+            Star => "*",
+            StarEqual => "*=",
+            PlusPlus => "++",
+            Plus => "+",
+            PlusEqual => "+=",
+            Divide => "/",
+            DivideEqual => "/=",
+            Comma => ",",
+            MinusMinus => "--",
+            Minus => "-",
+            MinusEqual => "-=",
+            Arrow => "->",
+            ArrowStar => "->*",
+            Dot => ".",
+            DotStar => ".*",
+            Ellipsis => "...",
+            ColonColon => "::",
+            Colon => ":",
+            SemiColon => ";",
+            Lower => "<",
+            LowerEqual => "<=",
+            LowerEqualGreater => "<=>",
+            LeftShift => "<<",
+            LeftShiftEqual => "<<=",
+            EqualEqual => "==",
+            Equal => "=",
+            Greater => ">",
+            GreaterEqual => ">=",
+            RightShift => ">>",
+            RightShiftEqual => ">>=",
+            Question => "?",
+            At => "@",
+            LeftBrack => "[",
+            DoubleLeftBrack => "[[",
+            Backslash => "\\",
+            RightBrack => "]",
+            DoubleRightBrack => "]]",
+            Xor => "^",
+            XorEqual => "^=",
+            LeftBrace => "{",
+            OrOr => "||",
+            Or => "|",
+            OrEqual => "|=",
+            RightBrace => "}",
+            Tilde => "~",
+            LiteralChar(c) => return Some(char_lit("", *c, "")),
+            LiteralLChar(c) => return Some(char_lit("L", *c, "")),
+            LiteralUUChar(c) => return Some(char_lit("U", *c, "")),
+            LiteralUChar(c) => return Some(char_lit("u", *c, "")),
+            LiteralU8Char(c) => return Some(char_lit("u8", *c, "")),
+            LiteralCharUD(x) => return Some(char_lit("", x.0, &x.1)),
+            LiteralLCharUD(x) => return Some(char_lit("L", x.0, &x.1)),
+            LiteralUUCharUD(x) => return Some(char_lit("U", x.0, &x.1)),
+            LiteralUCharUD(x) => return Some(char_lit("u", x.0, &x.1)),
+            LiteralU8CharUD(x) => return Some(char_lit("u8", x.0, &x.1)),
+            LiteralDouble(x) => return Some(float_lit(*x, "")),
+            LiteralFloat(x) => return Some(float_lit(*x, "f")),
+            LiteralLongDouble(x) => return Some(float_lit(*x, "l")),
+            LiteralFloatUD(x) => return Some(float_lit(x.0, &x.1)),
+            LiteralInt(n) => return Some(int_lit(*n, "")),
+            LiteralUInt(n) => return Some(int_lit(*n, "u")),
+            LiteralLong(n) => return Some(int_lit(*n, "l")),
+            LiteralLongLong(n) => return Some(int_lit(*n, "ll")),
+            LiteralULong(n) => return Some(int_lit(*n, "ul")),
+            LiteralULongLong(n) => return Some(int_lit(*n, "ull")),
+            LiteralSize(n) => return Some(int_lit(*n, "z")),
+            LiteralUSize(n) => return Some(int_lit(*n, "uz")),
+            LiteralIntUD(x) => return Some(int_lit(x.0, &x.1)),
+            LiteralString(s) => return Some(string_lit("", s, "")),
+            LiteralLString(s) => return Some(string_lit("L", s, "")),
+            LiteralUString(s) => return Some(string_lit("u", s, "")),
+            LiteralUUString(s) => return Some(string_lit("U", s, "")),
+            LiteralU8String(s) => return Some(string_lit("u8", s, "")),
+            LiteralRString(s) => return Some(raw_string_lit("", s, "")),
+            LiteralLRString(s) => return Some(raw_string_lit("L", s, "")),
+            LiteralURString(s) => return Some(raw_string_lit("u", s, "")),
+            LiteralUURString(s) => return Some(raw_string_lit("U", s, "")),
+            LiteralU8RString(s) => return Some(raw_string_lit("u8", s, "")),
+            LiteralStringUD(x) => return Some(string_lit("", &x.0, &x.1)),
+            LiteralLStringUD(x) => return Some(string_lit("L", &x.0, &x.1)),
+            LiteralUStringUD(x) => return Some(string_lit("u", &x.0, &x.1)),
+            LiteralUUStringUD(x) => return Some(string_lit("U", &x.0, &x.1)),
+            LiteralU8StringUD(x) => return Some(string_lit("u8", &x.0, &x.1)),
+            LiteralRStringUD(x) => return Some(raw_string_lit("", &x.0, &x.1)),
+            LiteralLRStringUD(x) => return Some(raw_string_lit("L", &x.0, &x.1)),
+            LiteralURStringUD(x) => return Some(raw_string_lit("u", &x.0, &x.1)),
+            LiteralUURStringUD(x) => return Some(raw_string_lit("U", &x.0, &x.1)),
+            LiteralU8RStringUD(x) => return Some(raw_string_lit("u8", &x.0, &x.1)),
+            HeaderName(h) => return Some(Cow::Owned(format!("<{}>", h))),
+            _ => {
+                return Option::None;
+            }
+        };
+
+        Some(Cow::Borrowed(s))
+    }
+
+    /// Get the source spelling of a token sequence: the tokens are separated by a space
+    /// and the ones without spelling are skipped
+    pub fn spell_all(toks: &[Token]) -> String {
+        toks.iter()
+            .filter_map(|t| t.spelling())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lexer::{preprocessor::context::DefaultContext, Lexer};
+    use pretty_assertions::assert_eq;
+
+    fn lex(s: &str) -> Vec<Token> {
+        let mut l = Lexer::<DefaultContext>::new(s.as_bytes());
+        let mut toks = Vec::new();
+        loop {
+            match l.next_token() {
+                Token::Eof => break,
+                Token::Eol => {}
+                tok => toks.push(tok),
+            }
+        }
+        toks
+    }
+
+    #[test]
+    fn test_spelling() {
+        assert_eq!(Token::LeftShiftEqual.spelling().unwrap(), "<<=");
+        assert_eq!(Token::Constexpr.spelling().unwrap(), "constexpr");
+        assert_eq!(Token::PreprocDefine.spelling().unwrap(), "#define");
+        assert_eq!(Token::LiteralULong(12).spelling().unwrap(), "12ul");
+        assert_eq!(
+            Token::LiteralString("a\"b\n".to_string())
+                .spelling()
+                .unwrap(),
+            "\"a\\\"b\\n\""
+        );
+        assert_eq!(
+            Token::LiteralRString("a)\"b".to_string())
+                .spelling()
+                .unwrap(),
+            "R\"x(a)\"b)x\""
+        );
+        assert_eq!(Token::Eof.spelling(), None);
+    }
+
+    #[test]
+    fn test_spelling_round_trip() {
+        let toks = lex(concat!(
+            "template <typename T> auto f(T x) -> decltype(x <<= 2) { ",
+            "x->*p ... a <=> b; return x[[1]] != 'a' && y == L'\\n' || 1.5f * 0.25; } ",
+            "s = u8\"x\\ty\" U\"z\" R\"d(a)\"b)d\" \"q\"_ud 42ull 0x10 12_km 3.0 1e300; ",
+            "__attribute__((packed)) ::std::move(v) %= ~c ^ d | e;",
+        ));
+
+        let spelled = Token::spell_all(&toks);
+
+        assert_eq!(lex(&spelled), toks);
+    }
+}
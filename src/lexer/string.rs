@@ -279,16 +279,16 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                             return String::from_utf8(self.buf.slice_p(spos, rspos).to_vec())
                                 .unwrap();
                         } else if c == b'\n' {
-                            self.buf.add_new_line();
                             self.buf.inc();
+                            self.buf.add_new_line();
                             break;
                         } else {
                             break;
                         }
                     }
                 } else if c == b'\n' {
-                    self.buf.add_new_line();
                     self.buf.inc();
+                    self.buf.add_new_line();
                 } else {
                     self.buf.inc();
                 }
@@ -310,10 +310,10 @@ impl<'a, PC: PreprocContext> Lexer<'a, PC> {
                     self.buf.inc();
                     if self.buf.has_char() {
                         let c = self.buf.next_char();
+                        self.buf.inc();
                         if c == b'\n' {
                             self.buf.add_new_line();
                         }
-                        self.buf.inc();
                     } else {
                         break;
                     }
@@ -20,8 +20,8 @@ macro_rules! skip_whites {
                     if $lexer.buf.has_char_n(1) {
                         let c = $lexer.buf.next_char_n(1);
                         if c == b'\n' {
-                            $lexer.buf.add_new_line();
                             $lexer.buf.inc_n(2);
+                            $lexer.buf.add_new_line();
                             continue;
                         }
                     }
@@ -6,7 +6,8 @@
 use std::hash::{Hash, Hasher};
 use termcolor::StandardStreamLock;
 
-use crate::lexer::{TLexer, Token};
+use crate::errors::Span;
+use crate::lexer::{cpp_kw_spelling, TLexer, Token};
 use crate::parser::dump::Dump;
 use crate::parser::errors::ParserError;
 use crate::parser::Context;
@@ -19,6 +20,77 @@ pub struct Attribute {
     pub has_using: bool,
 }
 
+/// The semantic of a deprecated attribute whatever the spelling is
+#[derive(Clone, Debug, PartialEq)]
+pub struct Deprecated {
+    pub message: Option<String>,
+}
+
+/// The semantic of a gnu constructor or destructor attribute
+#[derive(Clone, Debug, PartialEq)]
+pub struct InitFini {
+    pub is_ctor: bool,
+    pub priority: Option<u64>,
+}
+
+impl Attribute {
+    /// Get the deprecation info for [[deprecated("msg")]], [[gnu::deprecated("msg")]]
+    /// or __attribute__((deprecated("msg")))
+    pub fn get_deprecated(&self) -> Option<Deprecated> {
+        let name = self.name.trim_start_matches("__").trim_end_matches("__");
+        let ns_ok = matches!(self.namespace.as_deref(), None | Some("gnu"));
+        if name != "deprecated" || !ns_ok {
+            return None;
+        }
+
+        let message = self.arg.as_ref().and_then(|arg| arg.get_string());
+
+        Some(Deprecated { message })
+    }
+
+    /// Get the info for [[gnu::constructor(prio)]], [[gnu::destructor(prio)]]
+    /// or __attribute__((constructor(prio)))
+    pub fn get_init_fini(&self) -> Option<InitFini> {
+        let name = self.name.trim_start_matches("__").trim_end_matches("__");
+        let is_ctor = match name {
+            "constructor" => true,
+            "destructor" => false,
+            _ => {
+                return None;
+            }
+        };
+        if self.namespace.as_deref() != Some("gnu") {
+            return None;
+        }
+
+        let priority = self.arg.as_ref().and_then(|arg| arg.get_int());
+
+        Some(InitFini { is_ctor, priority })
+    }
+
+    /// Get the argument indices for [[gnu::nonnull(1, 2)]], [[gnu::format_arg(2)]]
+    /// or __attribute__((alloc_size(1, 2))): nonnull without argument gives no index
+    pub fn get_arg_indices(&self) -> Option<Vec<u64>> {
+        let name = self.name.trim_start_matches("__").trim_end_matches("__");
+        if !matches!(name, "nonnull" | "format_arg" | "alloc_size")
+            || self.namespace.as_deref() != Some("gnu")
+        {
+            return None;
+        }
+
+        match self.arg.as_ref() {
+            Some(arg) => arg.get_int_list(),
+            None if name == "nonnull" => Some(Vec::new()),
+            None => None,
+        }
+    }
+
+    /// Check if the attribute can only appertain to a statement: [[likely]] or [[unlikely]]
+    pub fn is_stmt_only(&self) -> bool {
+        self.namespace.is_none() && (self.name == "likely" || self.name == "unlikely")
+    }
+}
+
 impl Hash for Attribute {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.namespace.hash(state);
@@ -30,8 +102,7 @@ impl Hash for Attribute {
 impl Dump for Attribute {
     fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
         let prefix = dump_start!(name, "", prefix, last, stdout);
-        // TODO: handle attribute args
-        dump_fields!(self, prefix, stdout, namespace, name);
+        dump_fields!(self, prefix, stdout, namespace, name, arg, has_using);
     }
 }
 
@@ -40,8 +111,73 @@ pub struct AttributeArg {
     pub tokens: Vec<Token>,
 }
 
+impl AttributeArg {
+    /// Get the string argument as in [[gnu::visibility("hidden")]]:
+    /// the literals are concatenated ("a" "b" is "ab")
+    pub fn get_string(&self) -> Option<String> {
+        let mut res: Option<String> = None;
+        for tok in self.tokens.iter() {
+            match tok {
+                Token::LiteralString(s)
+                | Token::LiteralU8String(s)
+                | Token::LiteralRString(s)
+                | Token::LiteralU8RString(s) => {
+                    res.get_or_insert_with(String::new).push_str(s);
+                }
+                _ => {
+                    return None;
+                }
+            }
+        }
+        res
+    }
+
+    /// Get the integer argument as in [[gnu::constructor(101)]]
+    pub fn get_int(&self) -> Option<u64> {
+        match self.tokens.as_slice() {
+            [Token::LiteralInt(n)] => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Get the comma-separated integers as in [[gnu::nonnull(1, 2)]]
+    pub fn get_int_list(&self) -> Option<Vec<u64>> {
+        self.tokens
+            .split(|tok| *tok == Token::Comma)
+            .map(|toks| match toks {
+                [Token::LiteralInt(n)] => Some(*n),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Dump for AttributeArg {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        if let Some(s) = self.get_string() {
+            dump_str!(name, format!("\"{}\"", s), prefix, last, stdout);
+        } else {
+            dump_str!(name, Token::spell_all(&self.tokens), prefix, last, stdout);
+        }
+    }
+}
+
 pub type Attributes = Vec<Attribute>;
 
+/// Reject the attributes which can only appertain to a statement in a declaration
+pub(crate) fn check_decl_attributes(
+    attributes: Option<&Attributes>,
+    sp: Span,
+) -> Result<(), ParserError> {
+    if let Some(attr) = attributes.and_then(|attrs| attrs.iter().find(|a| a.is_stmt_only())) {
+        return Err(ParserError::InvalidAttrInDecl {
+            sp,
+            name: attr.name.clone(),
+        });
+    }
+    Ok(())
+}
+
 impl Dump for Attributes {
     fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
         let prefix = dump_start!(name, "", prefix, last, stdout);
@@ -218,6 +354,83 @@ impl<'a, L: TLexer> NameParser<'a, L> {
     }
 }
 
+struct GnuAttributeParser<'a, L: TLexer> {
+    lexer: &'a mut L,
+}
+
+impl<'a, L: TLexer> GnuAttributeParser<'a, L> {
+    fn new(lexer: &'a mut L) -> Self {
+        Self { lexer }
+    }
+
+    fn parse(self, attributes: &mut Attributes, context: &mut Context) -> Result<(), ParserError> {
+        // __attribute__ (( attribute-list ))
+        // the attributes are the same as the ones in the gnu namespace: [[gnu::packed]]
+        for _ in 0..2 {
+            let tok = self.lexer.next_useful();
+            if tok != Token::LeftParen {
+                return Err(ParserError::InvalidTokenInAttrs {
+                    sp: self.lexer.span(),
+                    tok,
+                });
+            }
+        }
+
+        let mut tok = self.lexer.next_useful();
+
+        loop {
+            match tok {
+                Token::RightParen => {
+                    let tok = self.lexer.next_useful();
+                    if tok == Token::RightParen {
+                        return Ok(());
+                    }
+                    return Err(ParserError::InvalidTokenInAttrs {
+                        sp: self.lexer.span(),
+                        tok,
+                    });
+                }
+                Token::Comma => {
+                    tok = self.lexer.next_useful();
+                }
+                _ => {
+                    let id = match tok {
+                        Token::Identifier(id) => id,
+                        // a keyword is a valid attribute name: __attribute__((const))
+                        _ => match cpp_kw_spelling(&tok) {
+                            Some(kw) => kw.to_string(),
+                            None => {
+                                return Err(ParserError::InvalidTokenInAttrs {
+                                    sp: self.lexer.span(),
+                                    tok,
+                                });
+                            }
+                        },
+                    };
+
+                    let ap = ArgumentParser::new(self.lexer);
+                    let (tk, arg) = ap.parse(None, context)?;
+
+                    attributes.push(Attribute {
+                        namespace: Some("gnu".to_string()),
+                        name: id,
+                        arg,
+                        has_using: false,
+                    });
+
+                    tok = tk.unwrap_or_else(|| self.lexer.next_useful());
+                    if tok != Token::Comma && tok != Token::RightParen {
+                        return Err(ParserError::InvalidTokenInAttrs {
+                            sp: self.lexer.span(),
+                            tok,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
 struct AttributeParser<'a, L: TLexer> {
     lexer: &'a mut L,
 }
@@ -243,6 +456,12 @@ impl<'a, L: TLexer> AttributeParser<'a, L> {
         //   attribute-namespace :: identifier ( argument-list )
 
         let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        if tok == Token::Attribute {
+            let gp = GnuAttributeParser::new(self.lexer);
+            gp.parse(attributes, context)?;
+            return Ok((None, true));
+        }
+
         if tok != Token::DoubleLeftBrack {
             return Ok((Some(tok), false));
         }
@@ -254,30 +473,37 @@ impl<'a, L: TLexer> AttributeParser<'a, L> {
         let mut tok = tok.unwrap_or_else(|| self.lexer.next_useful());
 
         loop {
-            let np = NameParser::new(self.lexer);
-            let (tk, (namespace, id)) = np.parse(tok, context)?;
+            // the list can be empty or contain empty elements: [[]] or [[a,,b]]
+            if tok == Token::DoubleRightBrack {
+                return Ok((None, true));
+            }
 
-            let ap = ArgumentParser::new(self.lexer);
-            let (tk, arg) = ap.parse(tk, context)?;
+            if tok != Token::Comma {
+                let np = NameParser::new(self.lexer);
+                let (tk, (namespace, id)) = np.parse(tok, context)?;
 
-            attributes.push(Attribute {
-                namespace: namespace.or_else(|| default_ns.clone()),
-                name: id,
-                arg,
-                has_using,
-            });
+                let ap = ArgumentParser::new(self.lexer);
+                let (tk, arg) = ap.parse(tk, context)?;
 
-            tok = tk.unwrap_or_else(|| self.lexer.next_useful());
-            match tok {
-                Token::Comma => {}
-                Token::DoubleRightBrack => {
-                    return Ok((None, true));
-                }
-                _ => {
-                    return Err(ParserError::InvalidTokenInAttrs {
-                        sp: self.lexer.span(),
-                        tok,
-                    });
+                attributes.push(Attribute {
+                    namespace: namespace.or_else(|| default_ns.clone()),
+                    name: id,
+                    arg,
+                    has_using,
+                });
+
+                tok = tk.unwrap_or_else(|| self.lexer.next_useful());
+                match tok {
+                    Token::Comma => {}
+                    Token::DoubleRightBrack => {
+                        return Ok((None, true));
+                    }
+                    _ => {
+                        return Err(ParserError::InvalidTokenInAttrs {
+                            sp: self.lexer.span(),
+                            tok,
+                        });
+                    }
                 }
             }
 
@@ -445,4 +671,238 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_attr_empty() {
+        let mut l = Lexer::<DefaultContext>::new(b"[[]] [[, A,, B,]]");
+        let p = AttributesParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, a) = p.parse(None, &mut context).unwrap();
+
+        assert_eq!(
+            a.unwrap(),
+            vec![
+                Attribute {
+                    namespace: None,
+                    name: "A".to_string(),
+                    arg: None,
+                    has_using: false,
+                },
+                Attribute {
+                    namespace: None,
+                    name: "B".to_string(),
+                    arg: None,
+                    has_using: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_attr_gnu() {
+        let mut l =
+            Lexer::<DefaultContext>::new(b"__attribute__((packed, aligned(4))) __attribute__(())");
+        let p = AttributesParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, a) = p.parse(None, &mut context).unwrap();
+
+        assert_eq!(
+            a.unwrap(),
+            vec![
+                Attribute {
+                    namespace: Some("gnu".to_string()),
+                    name: "packed".to_string(),
+                    arg: None,
+                    has_using: false
+                },
+                Attribute {
+                    namespace: Some("gnu".to_string()),
+                    name: "aligned".to_string(),
+                    arg: Some(AttributeArg {
+                        tokens: vec![Token::LiteralInt(4),],
+                    }),
+                    has_using: false
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_attr_gnu_keyword() {
+        let mut l = Lexer::<DefaultContext>::new(
+            b"__attribute__((const, __const__, noreturn)) __attribute__((__volatile__))",
+        );
+        let p = AttributesParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, a) = p.parse(None, &mut context).unwrap();
+
+        let names: Vec<_> = a.unwrap().into_iter().map(|a| a.name).collect();
+        assert_eq!(
+            names,
+            vec!["const", "__const__", "noreturn", "__volatile__"]
+        );
+
+        let mut l = Lexer::<DefaultContext>::new(b"__attribute__((+))");
+        let p = AttributesParser::new(&mut l);
+        assert!(p.parse(None, &mut context).is_err());
+    }
+
+    #[test]
+    fn test_attr_deprecated() {
+        let mut context = Context::default();
+        let get = |src: &[u8], context: &mut Context| {
+            let mut l = Lexer::<DefaultContext>::new(src);
+            let p = AttributesParser::new(&mut l);
+            let (_, a) = p.parse(None, context).unwrap();
+            a.unwrap()[0].get_deprecated()
+        };
+
+        let expected = Some(Deprecated {
+            message: Some("use bar".to_string()),
+        });
+        assert_eq!(get(b"[[deprecated(\"use bar\")]]", &mut context), expected);
+        assert_eq!(
+            get(
+                b"__attribute__((deprecated(\"use \" \"bar\")))",
+                &mut context
+            ),
+            expected
+        );
+        assert_eq!(
+            get(b"[[gnu::__deprecated__(\"use bar\")]]", &mut context),
+            expected
+        );
+
+        let expected = Some(Deprecated { message: None });
+        assert_eq!(get(b"[[deprecated]]", &mut context), expected);
+        assert_eq!(get(b"__attribute__((deprecated))", &mut context), expected);
+
+        assert_eq!(get(b"[[nodiscard]]", &mut context), None);
+        assert_eq!(get(b"[[clang::deprecated]]", &mut context), None);
+    }
+
+    #[test]
+    fn test_attr_string_arg() {
+        let mut l = Lexer::<DefaultContext>::new(
+            b"[[gnu::visibility(\"hidden\")]] [[deprecated(\"x\")]] [[gnu::aligned(4)]]",
+        );
+        let p = AttributesParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, a) = p.parse(None, &mut context).unwrap();
+        let a = a.unwrap();
+
+        assert_eq!(
+            a[0],
+            Attribute {
+                namespace: Some("gnu".to_string()),
+                name: "visibility".to_string(),
+                arg: Some(AttributeArg {
+                    tokens: vec![Token::LiteralString("hidden".to_string()),],
+                }),
+                has_using: false
+            }
+        );
+        assert_eq!(
+            a[0].arg.as_ref().unwrap().get_string(),
+            Some("hidden".to_string())
+        );
+        assert_eq!(
+            a[1],
+            Attribute {
+                namespace: None,
+                name: "deprecated".to_string(),
+                arg: Some(AttributeArg {
+                    tokens: vec![Token::LiteralString("x".to_string()),],
+                }),
+                has_using: false
+            }
+        );
+        assert_eq!(
+            a[1].arg.as_ref().unwrap().get_string(),
+            Some("x".to_string())
+        );
+        assert_eq!(a[2].arg.as_ref().unwrap().get_string(), None);
+    }
+
+    #[test]
+    fn test_attr_arg_indices() {
+        let mut l = Lexer::<DefaultContext>::new(
+            b"[[gnu::nonnull(1, 2)]] [[gnu::format_arg(2)]] [[gnu::nonnull]] __attribute__((alloc_size(1, 2))) [[gnu::nonnull(1,)]]",
+        );
+        let p = AttributesParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, a) = p.parse(None, &mut context).unwrap();
+        let a = a.unwrap();
+
+        assert_eq!(
+            a[0],
+            Attribute {
+                namespace: Some("gnu".to_string()),
+                name: "nonnull".to_string(),
+                arg: Some(AttributeArg {
+                    tokens: vec![Token::LiteralInt(1), Token::Comma, Token::LiteralInt(2)],
+                }),
+                has_using: false
+            }
+        );
+        assert_eq!(a[0].get_arg_indices(), Some(vec![1, 2]));
+        assert_eq!(a[1].get_arg_indices(), Some(vec![2]));
+        assert_eq!(a[2].get_arg_indices(), Some(vec![]));
+        assert_eq!(a[3].get_arg_indices(), Some(vec![1, 2]));
+        assert_eq!(a[4].get_arg_indices(), None);
+    }
+
+    #[test]
+    fn test_attr_ns_and_string_arg() {
+        let mut l = Lexer::<DefaultContext>::new(
+            b"[[gnu::always_inline]] [[nodiscard(\"reason\")]] [[using gnu: always_inline, hot]]",
+        );
+        let p = AttributesParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, a) = p.parse(None, &mut context).unwrap();
+        let a = a.unwrap();
+
+        assert_eq!(
+            a,
+            vec![
+                Attribute {
+                    namespace: Some("gnu".to_string()),
+                    name: "always_inline".to_string(),
+                    arg: None,
+                    has_using: false,
+                },
+                Attribute {
+                    namespace: None,
+                    name: "nodiscard".to_string(),
+                    arg: Some(AttributeArg {
+                        tokens: vec![Token::LiteralString("reason".to_string()),],
+                    }),
+                    has_using: false,
+                },
+                Attribute {
+                    namespace: Some("gnu".to_string()),
+                    name: "always_inline".to_string(),
+                    arg: None,
+                    has_using: true,
+                },
+                Attribute {
+                    namespace: Some("gnu".to_string()),
+                    name: "hot".to_string(),
+                    arg: None,
+                    has_using: true,
+                },
+            ]
+        );
+
+        // the arguments are dumped with their source spelling
+        let mut l = Lexer::<DefaultContext>::new(b"[[gnu::format(printf, 1, 2)]] [[x::y(::a<b>)]]");
+        let p = AttributesParser::new(&mut l);
+        let (_, a) = p.parse(None, &mut context).unwrap();
+        let spelled: Vec<_> = a
+            .unwrap()
+            .iter()
+            .map(|a| Token::spell_all(&a.arg.as_ref().unwrap().tokens))
+            .collect();
+        assert_eq!(spelled, vec!["printf , 1 , 2", ":: a < b >"]);
+    }
 }
@@ -9,11 +9,16 @@ use hashbrown::HashMap;
 use std::cell::RefCell;
 use std::rc::Rc;
 
+use crate::args::Language;
 use crate::lexer::extra::SavedLexer;
 use crate::parser::declarations::namespace::NsNames;
-use crate::parser::declarations::TypeDeclarator;
+use crate::parser::declarations::{
+    Identifier as DeclId, Specifier, StructuredBinding, TemplateParameter, TypeDeclarator,
+};
 use crate::parser::dump::Dump;
+use crate::parser::errors::ParserError;
 use crate::parser::names::name::{Identifier, Name, Qualified};
+use crate::parser::types::{BaseType, CVQualifier, Type};
 
 #[derive(Debug, Default, PartialEq)]
 pub struct TypeToFix(Rc<RefCell<Option<Rc<TypeDeclarator>>>>);
@@ -118,6 +123,9 @@ impl Default for Scope {
 #[derive(Clone, Debug)]
 pub struct Context {
     stack: Vec<Rc<RefCell<Scope>>>,
+    lang: Language,
+    recovery: bool,
+    errors: Vec<ParserError>,
 }
 
 #[derive(Clone, Debug)]
@@ -141,6 +149,9 @@ impl Default for Context {
     fn default() -> Self {
         Self {
             stack: vec![Rc::new(RefCell::new(Scope::default()))],
+            lang: Language::CPP,
+            recovery: false,
+            errors: Vec::new(),
         }
     }
 }
@@ -237,6 +248,33 @@ impl Search for Rc<RefCell<Scope>> {
 }
 
 impl Context {
+    pub fn set_language(&mut self, lang: Language) {
+        self.lang = lang;
+    }
+
+    pub fn get_language(&self) -> &Language {
+        &self.lang
+    }
+
+    /// In recovery mode, some parsers record the errors and try to continue
+    pub fn set_recovery(&mut self, recovery: bool) {
+        self.recovery = recovery;
+    }
+
+    pub fn is_recovering(&self) -> bool {
+        self.recovery
+    }
+
+    /// Record an error which has been recovered
+    pub fn add_error(&mut self, err: ParserError) {
+        self.errors.push(err);
+    }
+
+    /// Get the recovered errors
+    pub fn errors(&self) -> &[ParserError] {
+        &self.errors
+    }
+
     pub fn search(&self, name: Option<&Qualified>) -> Option<SearchResult> {
         if let Some(name) = name {
             for scope in self.stack.iter().rev() {
@@ -353,6 +391,18 @@ impl Context {
         add!(typd, Type);
     }
 
+    /// auto [a, b] = ... => add a and b as variables referring to the hidden one
+    pub fn add_binding(&mut self, binding: &StructuredBinding) {
+        let scope = self.stack.last().unwrap();
+        for name in binding.names.iter() {
+            let name = Name::Identifier(Identifier { val: name.clone() });
+            scope
+                .borrow_mut()
+                .decls
+                .insert(name, Kind::Var(Rc::clone(&binding.decl)));
+        }
+    }
+
     pub fn add_alias(&mut self, name: &str, typ: Rc<TypeDeclarator>) {
         let scope = self.stack.last().unwrap();
         let name = Name::Identifier(Identifier {
@@ -361,6 +411,90 @@ impl Context {
         scope.borrow_mut().decls.insert(name, Kind::Type(typ));
     }
 
+    fn placeholder_type(name: &Name) -> Rc<TypeDeclarator> {
+        Rc::new(TypeDeclarator {
+            typ: Type {
+                base: BaseType::None,
+                cv: CVQualifier::empty(),
+                pointers: None,
+            },
+            specifier: Specifier::empty(),
+            identifier: DeclId {
+                identifier: Some(Qualified {
+                    names: vec![name.clone()],
+                }),
+                attributes: None,
+            },
+            init: None,
+            align: None,
+            bitfield_size: None,
+            explicit_spec: None,
+        })
+    }
+
+    /// Add a known type name in the current scope (e.g. a type coming from
+    /// a header which isn't parsed) to disambiguate declarations from expressions
+    pub fn add_type_name(&mut self, name: &str) {
+        let name = Name::Identifier(Identifier {
+            val: name.to_string(),
+        });
+        let typ = Self::placeholder_type(&name);
+        let scope = self.stack.last().unwrap();
+        scope.borrow_mut().decls.insert(name, Kind::Type(typ));
+    }
+
+    /// Check if the name is a visible type name
+    pub fn is_type_name(&self, name: &str) -> bool {
+        let name = Qualified {
+            names: vec![Name::Identifier(Identifier {
+                val: name.to_string(),
+            })],
+        };
+        matches!(self.search(Some(&name)), Some(res) if res.is_type())
+    }
+
+    /// Add the template parameters in the current scope
+    /// and get back the shadowed declarations
+    pub(crate) fn add_template_params(
+        &mut self,
+        params: &[TemplateParameter],
+    ) -> Vec<(Name, Option<Kind>)> {
+        let mut scope = self.stack.last().unwrap().borrow_mut();
+        let mut saved = Vec::new();
+
+        for param in params {
+            let name = if let Some(name) = param.get_name() {
+                Name::Identifier(Identifier {
+                    val: name.to_string(),
+                })
+            } else {
+                continue;
+            };
+
+            let kind = match param {
+                TemplateParameter::NonType(p) => Kind::Var(Rc::clone(&p.decl)),
+                _ => Kind::Type(Self::placeholder_type(&name)),
+            };
+
+            let old = scope.decls.insert(name.clone(), kind);
+            saved.push((name, old));
+        }
+
+        saved
+    }
+
+    /// Remove the template parameters and restore the shadowed declarations
+    pub(crate) fn remove_template_params(&mut self, saved: Vec<(Name, Option<Kind>)>) {
+        let mut scope = self.stack.last().unwrap().borrow_mut();
+        for (name, old) in saved.into_iter().rev() {
+            if let Some(old) = old {
+                scope.decls.insert(name, old);
+            } else {
+                scope.decls.remove(&name);
+            }
+        }
+    }
+
     pub fn add_method(&self, typ: Rc<TypeDeclarator>, saved: SavedLexer) {
         let scope = self.stack.last().unwrap();
         scope.borrow_mut().methods.push((typ, saved));
@@ -523,4 +657,25 @@ unsigned factorial(unsigned n) {
         assert_eq!(context.stack.len(), 1);
         assert!(context.search(Some(&mk_id!("factorial"))).is_some());
     }
+
+    #[test]
+    fn test_context_type_name() {
+        use crate::parser::statements::{Statement, StatementParser};
+
+        let parse = |context: &mut Context| {
+            let mut l = Lexer::<DefaultContext>::new(b"Foo * x;");
+            let p = StatementParser::new(&mut l);
+            p.parse(None, context).unwrap().1.unwrap()
+        };
+
+        let mut context = Context::default();
+        assert!(!context.is_type_name("Foo"));
+        assert!(matches!(parse(&mut context), Statement::Expression(_)));
+
+        let mut context = Context::default();
+        context.add_type_name("Foo");
+        assert!(context.is_type_name("Foo"));
+        assert!(matches!(parse(&mut context), Statement::Type(_)));
+        assert!(!context.is_type_name("x"));
+    }
 }
@@ -10,18 +10,21 @@ use crate::parser::attributes::{Attributes, AttributesParser};
 use crate::parser::dump::Dump;
 use crate::parser::errors::ParserError;
 use crate::parser::expressions::{ExprNode, ExpressionParser};
-use crate::parser::types::Type;
+use crate::parser::types::{CVQualifier, Type};
 use crate::parser::Context;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Dimension {
     pub size: Option<ExprNode>,
     pub attributes: Option<Attributes>,
+    /// C99 qualifiers in a parameter declaration: `int a[static const 10]`
+    pub is_static: bool,
+    pub cv: CVQualifier,
 }
 
 impl Dump for Dimension {
     fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
-        dump_obj!(self, name, "", prefix, last, stdout, size, attributes);
+        dump_obj!(self, name, "", prefix, last, stdout, size, attributes, is_static, cv);
     }
 }
 
@@ -45,16 +48,25 @@ impl Dump for Array {
     }
 }
 
+impl Array {
+    /// Check if the outermost dimension has some qualifiers (static or cv)
+    pub(crate) fn has_qualifiers(&self) -> bool {
+        self.dimensions
+            .first()
+            .is_some_and(|dim| dim.is_static || !dim.cv.is_empty())
+    }
+}
+
 pub struct ArrayParser<'a, L: TLexer> {
     lexer: &'a mut L,
 }
 
 impl<'a, L: TLexer> ArrayParser<'a, L> {
-    pub(super) fn new(lexer: &'a mut L) -> Self {
+    pub(crate) fn new(lexer: &'a mut L) -> Self {
         Self { lexer }
     }
 
-    pub(super) fn parse(
+    pub(crate) fn parse(
         self,
         tok: Option<Token>,
         context: &mut Context,
@@ -67,8 +79,27 @@ impl<'a, L: TLexer> ArrayParser<'a, L> {
                 break;
             }
 
+            let mut is_static = false;
+            let mut cv = CVQualifier::empty();
+            let mut tk = self.lexer.next_useful();
+            loop {
+                if tk == Token::Static {
+                    is_static = true;
+                } else if !cv.from_tok(&tk) {
+                    break;
+                }
+
+                // int a[2][static 3] is invalid
+                if !dimensions.is_empty() {
+                    return Err(ParserError::InvalidArrayQualifiers {
+                        sp: self.lexer.span(),
+                    });
+                }
+                tk = self.lexer.next_useful();
+            }
+
             let mut ep = ExpressionParser::new(self.lexer, Token::RightBrack);
-            let (tk, size) = ep.parse(None, context)?;
+            let (tk, size) = ep.parse(Some(tk), context)?;
 
             let tk = tk.unwrap_or_else(|| self.lexer.next_useful());
             if tk != Token::RightBrack {
@@ -83,7 +114,12 @@ impl<'a, L: TLexer> ArrayParser<'a, L> {
 
             tok = tk.unwrap_or_else(|| self.lexer.next_useful());
 
-            dimensions.push(Dimension { size, attributes });
+            dimensions.push(Dimension {
+                size,
+                attributes,
+                is_static,
+                cv,
+            });
         }
 
         Ok(if dimensions.is_empty() {
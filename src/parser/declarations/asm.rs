@@ -3,24 +3,110 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+use bitflags::bitflags;
 use termcolor::StandardStreamLock;
 
 use crate::lexer::{TLexer, Token};
 use crate::parser::attributes::Attributes;
 use crate::parser::dump::Dump;
 use crate::parser::errors::ParserError;
+use crate::parser::expressions::{ExprNode, ExpressionParser};
 use crate::parser::literals::StringLiteralParser;
 use crate::parser::Context;
 
+bitflags! {
+    pub struct AsmQualifier: u8 {
+        const VOLATILE = 0b1;
+        const INLINE = 0b10;
+        const GOTO = 0b100;
+    }
+}
+
+impl AsmQualifier {
+    fn add_tok(&mut self, tok: &Token) -> bool {
+        match tok {
+            Token::Volatile | Token::GnuVolatile => {
+                *self |= Self::VOLATILE;
+                true
+            }
+            Token::Inline | Token::UInline | Token::UInlineU => {
+                *self |= Self::INLINE;
+                true
+            }
+            Token::Goto => {
+                *self |= Self::GOTO;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Dump for AsmQualifier {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        let s = bitflags_to_str!(self, Self, VOLATILE, "volatile", INLINE, "inline", GOTO, "goto");
+        dump_str!(name, s, Cyan, prefix, last, stdout);
+    }
+}
+
+/// An operand in an extended asm: [name] "constraint" (expr)
+#[derive(Clone, Debug, PartialEq)]
+pub struct AsmOperand {
+    pub name: Option<String>,
+    pub constraint: String,
+    pub expr: ExprNode,
+}
+
+impl Dump for AsmOperand {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(self, name, "operand", prefix, last, stdout, name, constraint, expr);
+    }
+}
+
+pub type AsmOperands = Vec<AsmOperand>;
+
+impl Dump for AsmOperands {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_vec!(name, self, "op", prefix, last, stdout);
+    }
+}
+
+/// The body of a MSVC asm block: __asm { ... }
+#[derive(Clone, Debug, PartialEq)]
+pub struct AsmBlock {
+    pub tokens: Vec<Token>,
+}
+
+impl Dump for AsmBlock {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_str!(name, Token::spell_all(&self.tokens), prefix, last, stdout);
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Asm {
     pub attributes: Option<Attributes>,
+    pub qualifiers: AsmQualifier,
     pub code: String,
+    pub outputs: AsmOperands,
+    pub inputs: AsmOperands,
+    pub clobbers: Vec<String>,
+    pub labels: Vec<String>,
+    pub block: Option<AsmBlock>,
 }
 
 impl Dump for Asm {
     fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
-        dump_obj!(self, name, "asm", prefix, last, stdout, attributes);
+        dump_obj!(
+            self, name, "asm", prefix, last, stdout, attributes, qualifiers, code, outputs, inputs,
+            clobbers, labels, block
+        );
+    }
+}
+
+impl Asm {
+    pub(crate) fn is_asm(tok: &Token) -> bool {
+        matches!(tok, Token::Asm | Token::GnuAsm | Token::MSAsm)
     }
 }
 
@@ -34,16 +120,36 @@ impl<'a, L: TLexer> AsmParser<'a, L> {
     }
 
     pub(crate) fn parse(
-        self,
+        mut self,
         tok: Option<Token>,
         context: &mut Context,
     ) -> Result<(Option<Token>, Option<Asm>), ParserError> {
         let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
-        if tok != Token::Asm {
+        if !Asm::is_asm(&tok) {
             return Ok((Some(tok), None));
         }
 
-        let tok = self.lexer.next_useful();
+        let mut asm = Asm {
+            attributes: None,
+            qualifiers: AsmQualifier::empty(),
+            code: String::new(),
+            outputs: Vec::new(),
+            inputs: Vec::new(),
+            clobbers: Vec::new(),
+            labels: Vec::new(),
+            block: None,
+        };
+
+        let mut tok = self.lexer.next_useful();
+        while asm.qualifiers.add_tok(&tok) {
+            tok = self.lexer.next_useful();
+        }
+
+        if tok == Token::LeftBrace {
+            asm.block = Some(self.parse_block());
+            return Ok((None, Some(asm)));
+        }
+
         if tok != Token::LeftParen {
             return Err(ParserError::InvalidTokenInAsm {
                 sp: self.lexer.span(),
@@ -53,32 +159,185 @@ impl<'a, L: TLexer> AsmParser<'a, L> {
 
         let tok = self.lexer.next_useful();
 
-        if let Some(code) = tok.get_string() {
-            // TODO: add an asm lexer & parser
-            let slp = StringLiteralParser::new(self.lexer);
-            let (tok, code) = slp.parse(&code, context)?;
+        let code = if let Some(code) = tok.get_string() {
+            code
+        } else {
+            return Err(ParserError::InvalidTokenInAsm {
+                sp: self.lexer.span(),
+                tok: Token::None,
+            });
+        };
+
+        // TODO: add an asm lexer & parser
+        let slp = StringLiteralParser::new(self.lexer);
+        let (tok, code) = slp.parse(&code, context)?;
+        asm.code = code;
+
+        // Extended asm: outputs, inputs, clobbers and goto labels are separated by colons
+        let mut tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        let mut section = 0;
+        loop {
+            section += match tok {
+                Token::RightParen => break,
+                Token::Colon => 1,
+                Token::ColonColon => 2,
+                _ => 5,
+            };
+
+            tok = match section {
+                1 => self.parse_operands(&mut asm.outputs, context)?,
+                2 => self.parse_operands(&mut asm.inputs, context)?,
+                3 => self.parse_names(&mut asm.clobbers, context)?,
+                4 => self.parse_names(&mut asm.labels, context)?,
+                _ => {
+                    return Err(ParserError::InvalidTokenInAsm {
+                        sp: self.lexer.span(),
+                        tok,
+                    });
+                }
+            };
+        }
+
+        Ok((None, Some(asm)))
+    }
+
+    fn parse_block(&mut self) -> AsmBlock {
+        let mut tokens = Vec::new();
+        let mut level = 0;
+
+        loop {
+            let tok = self.lexer.next_useful();
+            match tok {
+                Token::LeftBrace => level += 1,
+                Token::RightBrace if level == 0 => break,
+                Token::RightBrace => level -= 1,
+                Token::Eof => break,
+                _ => {}
+            }
+            tokens.push(tok);
+        }
+
+        AsmBlock { tokens }
+    }
+
+    fn parse_operands(
+        &mut self,
+        operands: &mut AsmOperands,
+        context: &mut Context,
+    ) -> Result<Token, ParserError> {
+        loop {
+            let mut tok = self.lexer.next_useful();
+            if operands.is_empty()
+                && matches!(tok, Token::Colon | Token::ColonColon | Token::RightParen)
+            {
+                return Ok(tok);
+            }
+
+            let name = if tok == Token::LeftBrack {
+                let name = match self.lexer.next_useful() {
+                    Token::Identifier(name) => name,
+                    tok => {
+                        return Err(ParserError::InvalidTokenInAsm {
+                            sp: self.lexer.span(),
+                            tok,
+                        });
+                    }
+                };
+                let tk = self.lexer.next_useful();
+                if tk != Token::RightBrack {
+                    return Err(ParserError::InvalidTokenInAsm {
+                        sp: self.lexer.span(),
+                        tok: tk,
+                    });
+                }
+                tok = self.lexer.next_useful();
+                Some(name)
+            } else {
+                None
+            };
+
+            let (tok, constraint) = self.parse_string(tok, context)?;
 
             let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
-            if tok != Token::RightParen {
+            if tok != Token::LeftParen {
                 return Err(ParserError::InvalidTokenInAsm {
                     sp: self.lexer.span(),
                     tok,
                 });
             }
 
-            Ok((
-                None,
-                Some(Asm {
-                    attributes: None,
-                    code,
-                }),
-            ))
+            let mut ep = ExpressionParser::new(self.lexer, Token::RightParen);
+            let (tok, expr) = ep.parse(None, context)?;
+
+            let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+            let expr = match (tok, expr) {
+                (Token::RightParen, Some(expr)) => expr,
+                (tok, _) => {
+                    return Err(ParserError::InvalidTokenInAsm {
+                        sp: self.lexer.span(),
+                        tok,
+                    });
+                }
+            };
+
+            operands.push(AsmOperand {
+                name,
+                constraint,
+                expr,
+            });
+
+            let tok = self.lexer.next_useful();
+            if tok != Token::Comma {
+                return Ok(tok);
+            }
+        }
+    }
+
+    /// Parse the clobbers (strings) or the goto labels (identifiers)
+    fn parse_names(
+        &mut self,
+        names: &mut Vec<String>,
+        context: &mut Context,
+    ) -> Result<Token, ParserError> {
+        loop {
+            let tok = self.lexer.next_useful();
+            let tok = match tok {
+                Token::Colon | Token::ColonColon | Token::RightParen if names.is_empty() => {
+                    return Ok(tok);
+                }
+                Token::Identifier(name) => {
+                    names.push(name);
+                    self.lexer.next_useful()
+                }
+                tok => {
+                    let (tok, name) = self.parse_string(tok, context)?;
+                    names.push(name);
+                    tok.unwrap_or_else(|| self.lexer.next_useful())
+                }
+            };
+
+            if tok != Token::Comma {
+                return Ok(tok);
+            }
+        }
+    }
+
+    fn parse_string(
+        &mut self,
+        tok: Token,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, String), ParserError> {
+        let s = if let Some(s) = tok.clone().get_string() {
+            s
         } else {
-            Err(ParserError::InvalidTokenInAsm {
+            return Err(ParserError::InvalidTokenInAsm {
                 sp: self.lexer.span(),
-                tok: Token::None,
-            })
-        }
+                tok,
+            });
+        };
+
+        let slp = StringLiteralParser::new(self.lexer);
+        slp.parse(&s, context)
     }
 }
 
@@ -87,6 +346,10 @@ mod tests {
 
     use super::*;
     use crate::lexer::{preprocessor::context::DefaultContext, Lexer};
+    use crate::parser::declarations::{Declaration, DeclarationListParser};
+    use crate::parser::expressions::{VarDecl, Variable};
+    use crate::parser::names::Qualified;
+    use crate::parser::statements::{Statement, StatementParser};
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -123,8 +386,119 @@ asm(R"(
             u.unwrap(),
             Asm {
                 attributes: None,
+                qualifiers: AsmQualifier::empty(),
                 code: code.to_string(),
+                outputs: vec![],
+                inputs: vec![],
+                clobbers: vec![],
+                labels: vec![],
+                block: None,
             }
         );
     }
+
+    #[test]
+    fn test_asm_extended() {
+        let mut l = Lexer::<DefaultContext>::new(
+            br#"
+{
+    __asm__ __volatile__ ("addl %[b], %0" : "=r" (a) : [b] "r" (b), "0" (a) : "cc", "memory");
+    asm goto ("jmp %l0" :::: out);
+    __asm { mov eax, 1 }
+out:
+    return;
+}
+"#,
+        );
+        let p = StatementParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, stmt) = p.parse(None, &mut context).unwrap();
+
+        let stmts = match stmt.unwrap() {
+            Statement::Compound(c) => c.stmts,
+            _ => panic!("not a compound"),
+        };
+        let asms: Vec<_> = stmts
+            .iter()
+            .take(3)
+            .map(|s| match s {
+                Statement::Asm(asm) => asm.clone(),
+                _ => panic!("not an asm statement"),
+            })
+            .collect();
+
+        let var = |name: &str| {
+            ExprNode::Variable(Box::new(Variable {
+                name: mk_id!(name),
+                decl: VarDecl::Indirect(Default::default()),
+            }))
+        };
+
+        assert_eq!(
+            *asms[0],
+            Asm {
+                attributes: None,
+                qualifiers: AsmQualifier::VOLATILE,
+                code: "addl %[b], %0".to_string(),
+                outputs: vec![AsmOperand {
+                    name: None,
+                    constraint: "=r".to_string(),
+                    expr: var("a"),
+                }],
+                inputs: vec![
+                    AsmOperand {
+                        name: Some("b".to_string()),
+                        constraint: "r".to_string(),
+                        expr: var("b"),
+                    },
+                    AsmOperand {
+                        name: None,
+                        constraint: "0".to_string(),
+                        expr: var("a"),
+                    }
+                ],
+                clobbers: vec!["cc".to_string(), "memory".to_string()],
+                labels: vec![],
+                block: None,
+            }
+        );
+
+        assert_eq!(asms[1].qualifiers, AsmQualifier::GOTO);
+        assert!(asms[1].outputs.is_empty() && asms[1].inputs.is_empty());
+        assert_eq!(asms[1].labels, vec!["out".to_string()]);
+
+        assert_eq!(
+            asms[2].block,
+            Some(AsmBlock {
+                tokens: vec![
+                    Token::Identifier("mov".to_string()),
+                    Token::Identifier("eax".to_string()),
+                    Token::Comma,
+                    Token::LiteralInt(1),
+                ],
+            })
+        );
+
+        assert!(matches!(stmts[3], Statement::Label(_)));
+    }
+
+    #[test]
+    fn test_asm_nop() {
+        let mut l = Lexer::<DefaultContext>::new(b"asm(\"nop\"); int x;");
+        let p = DeclarationListParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decls) = p.parse(None, &mut context).unwrap();
+        let decls = decls.unwrap();
+
+        assert_eq!(decls.len(), 2);
+        match &decls[0] {
+            Declaration::Asm(asm) => {
+                assert_eq!(asm.code, "nop");
+                assert_eq!(asm.qualifiers, AsmQualifier::empty());
+                assert!(asm.outputs.is_empty() && asm.clobbers.is_empty());
+            }
+            _ => panic!("not an asm declaration"),
+        }
+        assert!(matches!(decls[1], Declaration::Type(_)));
+    }
 }
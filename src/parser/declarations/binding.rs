@@ -0,0 +1,321 @@
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::rc::Rc;
+use termcolor::StandardStreamLock;
+
+use super::TypeDeclarator;
+use crate::lexer::{TLexer, Token};
+use crate::parser::dump::Dump;
+use crate::parser::errors::ParserError;
+
+pub type BindingNames = Vec<String>;
+
+impl Dump for BindingNames {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_vec!(name, self, "name", prefix, last, stdout);
+    }
+}
+
+/// A structured binding declaration: const auto& [a, b] = e; == const auto& __e = e;
+/// with a and b referring to __e. The type, the attributes and the initializer
+/// are the ones of the declarator of __e
+#[derive(Clone, Debug, PartialEq)]
+pub struct StructuredBinding {
+    pub names: BindingNames,
+    pub decl: Rc<TypeDeclarator>,
+}
+
+impl Dump for StructuredBinding {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(
+            self,
+            name,
+            "structured-binding",
+            prefix,
+            last,
+            stdout,
+            names,
+            decl
+        );
+    }
+}
+
+pub(crate) struct StructuredBindingParser<'a, L: TLexer> {
+    lexer: &'a mut L,
+}
+
+impl<'a, L: TLexer> StructuredBindingParser<'a, L> {
+    pub(crate) fn new(lexer: &'a mut L) -> Self {
+        Self { lexer }
+    }
+
+    pub(crate) fn parse(
+        self,
+        tok: Option<Token>,
+    ) -> Result<(Option<Token>, Option<BindingNames>), ParserError> {
+        // [ identifier-list ]
+        let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        if tok != Token::LeftBrack {
+            return Ok((Some(tok), None));
+        }
+
+        let mut names = Vec::new();
+
+        loop {
+            let tok = self.lexer.next_useful();
+            if let Token::Identifier(id) = tok {
+                names.push(id);
+            } else {
+                return Err(ParserError::InvalidTokenInBinding {
+                    sp: self.lexer.span(),
+                    tok,
+                });
+            }
+
+            let tok = self.lexer.next_useful();
+            match tok {
+                Token::Comma => {}
+                Token::RightBrack => {
+                    break;
+                }
+                _ => {
+                    return Err(ParserError::InvalidTokenInBinding {
+                        sp: self.lexer.span(),
+                        tok,
+                    });
+                }
+            }
+        }
+
+        Ok((None, Some(names)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::lexer::preprocessor::context::DefaultContext;
+    use crate::lexer::Lexer;
+    use crate::mk_var;
+    use crate::parser::attributes::{Attribute, Attributes};
+    use crate::parser::context::{Context, SearchResult};
+    use crate::parser::declarations::{
+        types, DeclOrExpr, Declaration, DeclarationListParser, Declarators, MSModifier, Pointer,
+        PtrKind, Specifier, TypeDeclarator, TypeDeclaratorParser,
+    };
+    use crate::parser::expressions::ExprNode;
+    use crate::parser::initializer::Initializer;
+    use crate::parser::names::Qualified;
+    use crate::parser::statements::{Statement, StatementParser};
+    use crate::parser::types::{BaseType, CVQualifier, Type};
+    use pretty_assertions::assert_eq;
+
+    fn mk_binding(
+        cv: CVQualifier,
+        kind: Option<PtrKind>,
+        names: &[&str],
+        attributes: Option<Attributes>,
+        init: &str,
+    ) -> StructuredBinding {
+        StructuredBinding {
+            names: names.iter().map(|n| n.to_string()).collect(),
+            decl: Rc::new(TypeDeclarator {
+                typ: Type {
+                    base: BaseType::Auto,
+                    cv,
+                    pointers: kind.map(|kind| {
+                        vec![Pointer {
+                            kind,
+                            attributes: None,
+                            cv: CVQualifier::empty(),
+                            ms: MSModifier::empty(),
+                        }]
+                    }),
+                },
+                specifier: Specifier::empty(),
+                identifier: types::Identifier {
+                    identifier: None,
+                    attributes,
+                },
+                init: Some(Initializer::Equal(ExprNode::Variable(Box::new(mk_var!(
+                    init
+                ))))),
+                align: None,
+                bitfield_size: None,
+                explicit_spec: None,
+            }),
+        }
+    }
+
+    fn parse_binding(s: &[u8], context: &mut Context) -> (Option<Token>, StructuredBinding) {
+        let mut l = Lexer::<DefaultContext>::new(s);
+        let p = TypeDeclaratorParser::new(&mut l);
+        let (tok, decl) = p.parse_decl(None, None, context).unwrap();
+        match decl.unwrap() {
+            Declarators::Binding(binding) => (tok, binding),
+            _ => panic!("not a structured binding"),
+        }
+    }
+
+    #[test]
+    fn test_binding_value() {
+        let mut context = Context::default();
+        let (tok, binding) = parse_binding(b"auto [x, y] = pair;", &mut context);
+
+        assert_eq!(tok, Some(Token::SemiColon));
+        assert_eq!(
+            binding,
+            mk_binding(CVQualifier::empty(), None, &["x", "y"], None, "pair")
+        );
+    }
+
+    #[test]
+    fn test_binding_ref() {
+        let mut context = Context::default();
+        let (_, binding) = parse_binding(b"auto& [a, b, c] = tuple;", &mut context);
+
+        assert_eq!(
+            binding,
+            mk_binding(
+                CVQualifier::empty(),
+                Some(PtrKind::Reference),
+                &["a", "b", "c"],
+                None,
+                "tuple"
+            )
+        );
+    }
+
+    #[test]
+    fn test_binding_const_ref() {
+        let mut context = Context::default();
+        let (_, binding) = parse_binding(b"const auto& [k, v] = entry;", &mut context);
+
+        assert_eq!(
+            binding,
+            mk_binding(
+                CVQualifier::CONST,
+                Some(PtrKind::Reference),
+                &["k", "v"],
+                None,
+                "entry"
+            )
+        );
+    }
+
+    #[test]
+    fn test_binding_attributes() {
+        let mut context = Context::default();
+        let (_, binding) = parse_binding(b"auto&& [x] [[maybe_unused]] = s;", &mut context);
+
+        assert_eq!(
+            binding,
+            mk_binding(
+                CVQualifier::empty(),
+                Some(PtrKind::RValue),
+                &["x"],
+                Some(vec![Attribute {
+                    namespace: None,
+                    name: "maybe_unused".to_string(),
+                    arg: None,
+                    has_using: false,
+                }]),
+                "s"
+            )
+        );
+    }
+
+    #[test]
+    fn test_binding_not_a_declarator() {
+        let mut l = Lexer::<DefaultContext>::new(b"auto [x, y] = pair;");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+
+        assert!(p.parse(None, None, true, &mut context).is_err());
+    }
+
+    #[test]
+    fn test_binding_leading_attributes() {
+        let attr = |name: &str| Attribute {
+            namespace: None,
+            name: name.to_string(),
+            arg: None,
+            has_using: false,
+        };
+        let expected = mk_binding(
+            CVQualifier::empty(),
+            None,
+            &["a", "b"],
+            Some(vec![attr("maybe_unused"), attr("deprecated")]),
+            "p",
+        );
+
+        let mut l =
+            Lexer::<DefaultContext>::new(b"[[maybe_unused]] auto [a, b] [[deprecated]] = p;");
+        let p = DeclarationListParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decls) = p.parse(None, &mut context).unwrap();
+        assert_eq!(
+            decls.unwrap(),
+            vec![Declaration::StructuredBinding(expected.clone())]
+        );
+
+        let mut l =
+            Lexer::<DefaultContext>::new(b"{ [[maybe_unused]] auto [a, b] [[deprecated]] = p; }");
+        let p = StatementParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, stmt) = p.parse(None, &mut context).unwrap();
+        match stmt.unwrap() {
+            Statement::Compound(c) => assert_eq!(
+                c.stmts,
+                vec![Statement::StructuredBinding(Box::new(expected))]
+            ),
+            _ => panic!("not a compound"),
+        }
+    }
+
+    #[test]
+    fn test_binding_for_range() {
+        let mut l = Lexer::<DefaultContext>::new(b"for (const auto& [k, v] : map) { k; }");
+        let p = StatementParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, stmt) = p.parse(None, &mut context).unwrap();
+
+        let mut binding = mk_binding(
+            CVQualifier::CONST,
+            Some(PtrKind::Reference),
+            &["k", "v"],
+            None,
+            "map",
+        );
+        Rc::make_mut(&mut binding.decl).init = None;
+        match stmt.unwrap() {
+            Statement::ForRange(f) => {
+                assert_eq!(f.decl, DeclOrExpr::Binding(Box::new(binding)));
+                assert_eq!(f.expr, ExprNode::Variable(Box::new(mk_var!("map"))));
+            }
+            _ => panic!("not a for-range"),
+        }
+    }
+
+    #[test]
+    fn test_binding_context() {
+        let mut context = Context::default();
+        let (_, binding) = parse_binding(b"auto [x, y] = pair;", &mut context);
+        context.add_binding(&binding);
+
+        for name in &["x", "y"] {
+            if let Some(SearchResult::Var(var)) = context.search(Some(&mk_id!(name))) {
+                assert!(Rc::ptr_eq(&var, &binding.decl));
+            } else {
+                unreachable!();
+            }
+        }
+    }
+}
@@ -7,7 +7,8 @@ use bitflags::bitflags;
 use std::rc::Rc;
 use termcolor::StandardStreamLock;
 
-use super::member::{MemberParser, MemberRes, Members, Visibility};
+use super::function::FunctionParser;
+use super::member::{Member, MemberParser, MemberRes, Members, Visibility};
 use crate::check_semicolon;
 use crate::lexer::{TLexer, Token};
 use crate::parser::attributes::{Attributes, AttributesParser};
@@ -107,11 +108,13 @@ pub struct Derived {
     pub attributes: Option<Attributes>,
     pub name: Qualified,
     pub specifier: ClassSpecifier,
+    /// A pack expansion: B...
+    pub ellipsis: bool,
 }
 
 impl Dump for Derived {
     fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
-        dump_obj!(self, name, "", prefix, last, stdout, attributes, name, specifier);
+        dump_obj!(self, name, "", prefix, last, stdout, attributes, name, specifier, ellipsis);
     }
 }
 
@@ -186,12 +189,20 @@ impl<'a, L: TLexer> DerivedParser<'a, L> {
             return Ok((tok, None));
         };
 
+        let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        let (tok, ellipsis) = if tok == Token::Ellipsis {
+            (None, true)
+        } else {
+            (Some(tok), false)
+        };
+
         Ok((
             tok,
             Some(Derived {
                 attributes,
                 name,
                 specifier,
+                ellipsis,
             }),
         ))
     }
@@ -307,12 +318,19 @@ impl<'a, L: TLexer> ClassParser<'a, L> {
                 }
                 let cp = CompoundStmtParser::new(&mut saved);
                 let (_, body) = cp.parse(None, context)?;
+
+                // function-try-block: the handlers follow the body
+                let is_try = !saved.is_consumed();
+                let mut fp = FunctionParser::new(&mut saved);
+                let (_, handlers) = fp.parse_handlers(is_try, context)?;
+
                 let to_fix = context.pop_n(name.map_or(1, |n| n.len()));
                 if let Some(to_fix) = to_fix {
                     to_fix.fix(Rc::clone(&typ));
                 }
 
                 *fun.body.borrow_mut() = body;
+                *fun.handlers.borrow_mut() = handlers;
             }
 
             let to_fix = context.pop_n(name.as_ref().map_or(1, |n| n.len()));
@@ -392,6 +410,17 @@ impl<'a, L: TLexer> ClassBodyParser<'a, L> {
                         current.push(decl);
                         tk
                     }
+                    MemberRes::Decls(decls) => {
+                        let tk = if decls.iter().all(Member::has_semicolon) {
+                            check_semicolon!(self, tk);
+                            None
+                        } else {
+                            tk
+                        };
+
+                        current.extend(decls);
+                        tk
+                    }
                 }
             } else {
                 tk
@@ -415,10 +444,12 @@ mod tests {
 
     use super::*;
     use crate::lexer::{preprocessor::context::DefaultContext, Lexer};
+    use crate::parser::attributes::Attribute;
     use crate::parser::declarations::{self, *};
     use crate::parser::expressions::{self, *};
     use crate::parser::initializer::Initializer;
-    use crate::parser::names::*;
+    use crate::parser::literals::{IntLiteral, Integer};
+    use crate::parser::names::{self, *};
     use crate::parser::statements::*;
     use crate::parser::types::*;
     use pretty_assertions::assert_eq;
@@ -464,7 +495,9 @@ public:
                 attributes: None,
             },
             init: None,
+            align: None,
             bitfield_size: None,
+            explicit_spec: None,
         });
 
         let expected = Class {
@@ -477,11 +510,13 @@ public:
                     attributes: None,
                     name: mk_id!("B"),
                     specifier: ClassSpecifier::PUBLIC,
+                    ellipsis: false,
                 },
                 Derived {
                     attributes: None,
                     name: mk_id!("C"),
                     specifier: ClassSpecifier::PROTECTED,
+                    ellipsis: false,
                 },
             ]),
             body: Some(ClassBody {
@@ -498,7 +533,9 @@ public:
                             attributes: None,
                         },
                         init: None,
+                        align: None,
                         bitfield_size: None,
+                        explicit_spec: None,
                     })),
                     Member::Type(Rc::new(TypeDeclarator {
                         typ: Type {
@@ -532,6 +569,7 @@ public:
                                         })),
                                     }))],
                                 })),
+                                handlers: RefCell::new(None),
                             })),
                             cv: CVQualifier::empty(),
                             pointers: None,
@@ -542,7 +580,9 @@ public:
                             attributes: None,
                         },
                         init: None,
+                        align: None,
                         bitfield_size: None,
+                        explicit_spec: None,
                     })),
                 ],
                 protected: vec![Member::Type(Rc::new(TypeDeclarator {
@@ -562,7 +602,9 @@ public:
                         attributes: None,
                     },
                     init: Some(Initializer::Equal(ExprNode::Nullptr(Box::new(Nullptr {})))),
+                    align: None,
                     bitfield_size: None,
+                    explicit_spec: None,
                 }))],
                 private: vec![
                     Member::Type(Rc::clone(&x)),
@@ -585,6 +627,7 @@ public:
                                 requires: None,
                                 ctor_init: None,
                                 body: RefCell::new(None),
+                                handlers: RefCell::new(None),
                             })),
                             cv: CVQualifier::empty(),
                             pointers: None,
@@ -595,7 +638,9 @@ public:
                             attributes: None,
                         },
                         init: None,
+                        align: None,
                         bitfield_size: None,
+                        explicit_spec: None,
                     })),
                 ],
             }),
@@ -634,7 +679,9 @@ struct A {
                 attributes: None,
             },
             init: None,
+            align: None,
             bitfield_size: None,
+            explicit_spec: None,
         });
 
         let expected = Class {
@@ -673,6 +720,7 @@ struct A {
                                         }))),
                                     }))],
                                 })),
+                                handlers: RefCell::new(None),
                             })),
                             cv: CVQualifier::empty(),
                             pointers: None,
@@ -683,7 +731,9 @@ struct A {
                             attributes: None,
                         },
                         init: None,
+                        align: None,
                         bitfield_size: None,
+                        explicit_spec: None,
                     })),
                     Member::Type(Rc::clone(&x)),
                 ],
@@ -694,4 +744,339 @@ struct A {
 
         assert_eq!(c, expected);
     }
+
+    #[test]
+    fn test_class_gnu_attributes() {
+        let mut l =
+            Lexer::<DefaultContext>::new(b"struct __attribute__((packed)) S { char c; int i; };");
+        let p = ClassParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, c, _) = p.parse(None, &mut context).unwrap();
+        let c = c.unwrap();
+
+        let mk_member = |prim: Primitive, name: &str| {
+            Member::Type(Rc::new(TypeDeclarator {
+                typ: Type {
+                    base: BaseType::Primitive(prim),
+                    cv: CVQualifier::empty(),
+                    pointers: None,
+                },
+                specifier: Specifier::empty(),
+                identifier: declarations::Identifier {
+                    identifier: Some(mk_id!(name)),
+                    attributes: None,
+                },
+                init: None,
+                align: None,
+                bitfield_size: None,
+                explicit_spec: None,
+            }))
+        };
+
+        let expected = Class {
+            kind: super::Kind::Struct,
+            attributes: Some(vec![Attribute {
+                namespace: Some("gnu".to_string()),
+                name: "packed".to_string(),
+                arg: None,
+                has_using: false,
+            }]),
+            name: Some(mk_id!("S")),
+            r#final: false,
+            bases: None,
+            body: Some(ClassBody {
+                public: vec![
+                    mk_member(Primitive::Char, "c"),
+                    mk_member(Primitive::Int, "i"),
+                ],
+                protected: vec![],
+                private: vec![],
+            }),
+        };
+
+        assert_eq!(c, expected);
+    }
+
+    #[test]
+    fn test_class_attributed_methods() {
+        let mut l = Lexer::<DefaultContext>::new(
+            b"struct A { [[nodiscard]] int f() { return 0; } [[deprecated]] virtual void g() = 0; };",
+        );
+        let p = ClassParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, c, _) = p.parse(None, &mut context).unwrap();
+        let members = c.unwrap().body.unwrap().public;
+
+        let attr = |m: &Member| -> (String, bool) {
+            let t = match m {
+                Member::Type(t) => t,
+                _ => panic!("not a type member"),
+            };
+            let has_body = match &t.typ.base {
+                BaseType::Function(f) => f.body.borrow().is_some(),
+                _ => panic!("not a function"),
+            };
+            (
+                t.identifier.attributes.as_ref().unwrap()[0].name.clone(),
+                has_body,
+            )
+        };
+
+        assert_eq!(attr(&members[0]), ("nodiscard".to_string(), true));
+        assert_eq!(attr(&members[1]), ("deprecated".to_string(), false));
+    }
+
+    #[test]
+    fn test_class_stmt_attribute_on_member() {
+        let mut l = Lexer::<DefaultContext>::new(b"struct S { [[likely]] int x; };");
+        let p = ClassParser::new(&mut l);
+        let mut context = Context::default();
+
+        assert!(matches!(
+            p.parse(None, &mut context),
+            Err(ParserError::InvalidAttrInDecl { name, .. }) if name == "likely"
+        ));
+    }
+
+    #[test]
+    fn test_class_bitfields() {
+        let mut l = Lexer::<DefaultContext>::new(
+            b"struct S { unsigned flag : 1; int : 3; unsigned : 0; unsigned long z : 2; }",
+        );
+        let p = ClassParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, c, _) = p.parse(None, &mut context).unwrap();
+        let c = c.unwrap();
+
+        let sizes: Vec<_> = c
+            .body
+            .unwrap()
+            .public
+            .iter()
+            .map(|m| {
+                if let Member::Type(t) = m {
+                    (
+                        t.identifier.identifier.as_ref().map(|id| id.to_string()),
+                        t.bitfield_size.clone(),
+                    )
+                } else {
+                    panic!("Not a type declarator");
+                }
+            })
+            .collect();
+        let size = |n| {
+            Some(ExprNode::Integer(Box::new(Integer {
+                value: IntLiteral::Int(n),
+            })))
+        };
+
+        assert_eq!(
+            sizes,
+            vec![
+                (Some("flag".to_string()), size(1)),
+                (None, size(3)),
+                (None, size(0)),
+                (Some("z".to_string()), size(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_class_pack_expansion() {
+        let mut l = Lexer::<DefaultContext>::new(
+            b"struct S : B..., public virtual C... { using B::operator()...; using C::f, C::g...; }",
+        );
+        let p = ClassParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, c, _) = p.parse(None, &mut context).unwrap();
+        let c = c.unwrap();
+
+        assert_eq!(
+            c.bases.unwrap(),
+            vec![
+                Derived {
+                    attributes: None,
+                    name: mk_id!("B"),
+                    specifier: ClassSpecifier::empty(),
+                    ellipsis: true,
+                },
+                Derived {
+                    attributes: None,
+                    name: mk_id!("C"),
+                    specifier: ClassSpecifier::PUBLIC | ClassSpecifier::VIRTUAL,
+                    ellipsis: true,
+                },
+            ]
+        );
+
+        let usings: Vec<_> = c
+            .body
+            .unwrap()
+            .public
+            .iter()
+            .map(|m| {
+                if let Member::UsingDecl(u) = m {
+                    (u.names.len(), u.ellipsis)
+                } else {
+                    panic!("Not a using declaration");
+                }
+            })
+            .collect();
+        assert_eq!(usings, vec![(1, true), (2, true)]);
+
+        let mut l = Lexer::<DefaultContext>::new(b"template<class... B> struct S : B... { };");
+        let p = DeclarationListParser::new(&mut l);
+        let (_, decls) = p.parse(None, &mut context).unwrap();
+        let decls = decls.unwrap();
+
+        assert_eq!(decls.len(), 1);
+        assert!(matches!(decls[0], Declaration::Template(_)));
+    }
+
+    #[test]
+    fn test_class_using_enum() {
+        let mut l = Lexer::<DefaultContext>::new(
+            b"struct S { using enum Color; using enum A::Fruit; Color c = red; }",
+        );
+        let p = ClassParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, c, _) = p.parse(None, &mut context).unwrap();
+        let c = c.unwrap();
+        let members = c.body.unwrap().public;
+
+        assert_eq!(members.len(), 3);
+        assert_eq!(
+            members[0],
+            Member::UsingEnum(UsingEnum {
+                name: mk_id!("Color"),
+            })
+        );
+        assert_eq!(
+            members[1],
+            Member::UsingEnum(UsingEnum {
+                name: mk_id!("A", "Fruit"),
+            })
+        );
+        assert!(matches!(members[2], Member::Type(_)));
+    }
+
+    #[test]
+    fn test_class_defaulted_members() {
+        let mut l = Lexer::<DefaultContext>::new(
+            b"
+struct A {
+    auto operator<=>(const A&) const = default;
+    auto operator<=>(int) const = delete;
+    ~A() = default;
+};
+struct B {
+    ~B() = delete;
+};
+",
+        );
+        let p = DeclarationListParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decls) = p.parse(None, &mut context).unwrap();
+        let decls = decls.unwrap();
+
+        let statuses = |decl: &Declaration| -> Vec<(Option<Qualified>, FunStatus)> {
+            let c = match decl {
+                Declaration::Type(t) => match &t.typ.base {
+                    BaseType::Class(c) => c.clone(),
+                    _ => panic!("not a class"),
+                },
+                _ => panic!("not a type declaration"),
+            };
+            c.body
+                .unwrap()
+                .public
+                .iter()
+                .map(|m| match m {
+                    Member::Type(t) => match &t.typ.base {
+                        BaseType::Function(f) => {
+                            (t.identifier.identifier.clone(), f.status.clone())
+                        }
+                        _ => panic!("not a function"),
+                    },
+                    _ => panic!("not a type member"),
+                })
+                .collect()
+        };
+
+        let spaceship = Qualified {
+            names: vec![names::Name::Operator(Box::new(names::Operator::Op(
+                expressions::Operator::ThreeWayComp,
+            )))],
+        };
+        let dtor = |name: &str| Qualified {
+            names: vec![names::Name::Destructor(Destructor {
+                name: name.to_string(),
+            })],
+        };
+        assert_eq!(
+            statuses(&decls[0]),
+            vec![
+                (Some(spaceship.clone()), FunStatus::Default),
+                (Some(spaceship), FunStatus::Delete),
+                (Some(dtor("A")), FunStatus::Default),
+            ]
+        );
+        assert_eq!(
+            statuses(&decls[1]),
+            vec![(Some(dtor("B")), FunStatus::Delete)]
+        );
+    }
+
+    #[test]
+    fn test_class_declarator_list() {
+        let mut l = Lexer::<DefaultContext>::new(
+            b"
+struct A {
+    int a : 3, b : 4;
+    int c, *d;
+};
+",
+        );
+        let p = DeclarationListParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decls) = p.parse(None, &mut context).unwrap();
+        let decls = decls.unwrap();
+
+        let c = match &decls[0] {
+            Declaration::Type(t) => match &t.typ.base {
+                BaseType::Class(c) => c.clone(),
+                _ => panic!("not a class"),
+            },
+            _ => panic!("not a type declaration"),
+        };
+        let members: Vec<_> = c
+            .body
+            .unwrap()
+            .public
+            .iter()
+            .map(|m| match m {
+                Member::Type(t) => (
+                    t.identifier.identifier.clone(),
+                    t.bitfield_size.clone(),
+                    t.typ.pointers.is_some(),
+                ),
+                _ => panic!("not a type member"),
+            })
+            .collect();
+
+        let size = |n| {
+            Some(ExprNode::Integer(Box::new(Integer {
+                value: IntLiteral::Int(n),
+            })))
+        };
+        assert_eq!(
+            members,
+            vec![
+                (Some(mk_id!("a")), size(3), false),
+                (Some(mk_id!("b")), size(4), false),
+                (Some(mk_id!("c")), None, false),
+                (Some(mk_id!("d")), None, true),
+            ]
+        );
+    }
 }
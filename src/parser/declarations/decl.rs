@@ -6,15 +6,17 @@
 use std::rc::Rc;
 use termcolor::StandardStreamLock;
 
-use super::types::{DeclHint, TypeDeclarator, TypeDeclaratorParser};
+use super::types::{DeclHint, Declarators, TypeDeclarator, TypeDeclaratorParser};
 use super::{
-    Asm, AsmParser, Extern, ExternParser, Namespace, NamespaceAlias, NamespaceParser, StaticAssert,
-    StaticAssertParser, UsingAlias, UsingDecl, UsingEnum, UsingNS, UsingParser,
+    Asm, AsmParser, DeductionGuide, DeductionGuideParser, ExportBlock, Extern, ExternParser,
+    ImportDecl, ModuleDecl, ModuleParser, Namespace, NamespaceAlias, NamespaceParser, StaticAssert,
+    StaticAssertParser, StructuredBinding, TemplateDecl, TemplateParser, UsingAlias, UsingDecl,
+    UsingEnum, UsingNS, UsingParser,
 };
 use crate::check_semicolon;
 
 use crate::lexer::{TLexer, Token};
-use crate::parser::attributes::{Attributes, AttributesParser};
+use crate::parser::attributes::{check_decl_attributes, Attributes, AttributesParser};
 use crate::parser::dump::Dump;
 use crate::parser::errors::ParserError;
 use crate::parser::Context;
@@ -22,6 +24,7 @@ use crate::parser::Context;
 #[derive(Clone, Debug, PartialEq)]
 pub enum Declaration {
     Type(Rc<TypeDeclarator>),
+    StructuredBinding(StructuredBinding),
     Extern(Extern),
     Namespace(Namespace),
     NamespaceAlias(NamespaceAlias),
@@ -32,6 +35,11 @@ pub enum Declaration {
     UsingEnum(UsingEnum),
     UsingNS(UsingNS),
     UsingAlias(UsingAlias),
+    Template(TemplateDecl),
+    DeductionGuide(DeductionGuide),
+    Module(ModuleDecl),
+    Import(ImportDecl),
+    Export(ExportBlock),
     Empty,
 }
 
@@ -45,6 +53,7 @@ impl Dump for Declaration {
 
         match self {
             Self::Type(x) => dump!(x),
+            Self::StructuredBinding(x) => dump!(x),
             Self::Extern(x) => dump!(x),
             Self::Namespace(x) => dump!(x),
             Self::NamespaceAlias(x) => dump!(x),
@@ -55,6 +64,11 @@ impl Dump for Declaration {
             Self::UsingEnum(x) => dump!(x),
             Self::UsingNS(x) => dump!(x),
             Self::UsingAlias(x) => dump!(x),
+            Self::Template(x) => dump!(x),
+            Self::DeductionGuide(x) => dump!(x),
+            Self::Module(x) => dump!(x),
+            Self::Import(x) => dump!(x),
+            Self::Export(x) => dump!(x),
             Self::Empty => dump_str!(name, "empty", Cyan, prefix, last, stdout),
         }
     }
@@ -74,6 +88,11 @@ impl Declaration {
             Self::Type(d) => d.has_semicolon(),
             Self::Extern(e) => !e.multiple,
             Self::Namespace(_) => false,
+            Self::Template(t) => t.decl.has_semicolon(),
+            Self::Asm(a) => a.block.is_none(),
+            Self::Export(e) => {
+                !e.multiple && matches!(e.decls.last(), Some(d) if d.has_semicolon())
+            }
             _ => true,
         }
     }
@@ -94,31 +113,79 @@ impl<'a, L: TLexer> DeclarationParser<'a, L> {
         hint: Option<DeclHint>, // TODO: remove hint
         context: &mut Context,
     ) -> Result<(Option<Token>, Option<Declaration>), ParserError> {
+        let (tok, decls) = self.parse_decls(tok, hint, false, context)?;
+        Ok((tok, decls.and_then(|decls| decls.into_iter().next())))
+    }
+
+    /// Parse a declaration where a simple declaration can declare several entities:
+    /// int x, *y; gives one type declaration per declarator
+    pub(crate) fn parse_list(
+        self,
+        tok: Option<Token>,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<Declarations>), ParserError> {
+        self.parse_decls(tok, None, true, context)
+    }
+
+    fn parse_decls(
+        self,
+        tok: Option<Token>,
+        hint: Option<DeclHint>,
+        list: bool,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<Declarations>), ParserError> {
         let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
         if tok == Token::SemiColon {
-            return Ok((None, Some(Declaration::Empty)));
+            // the semicolon is consumed by the caller
+            return Ok((Some(tok), Some(vec![Declaration::Empty])));
         }
         let tok = Some(tok);
 
+        let tp = TemplateParser::new(self.lexer);
+        let (tok, tpl) = tp.parse(tok, context)?;
+
+        if let Some(tpl) = tpl {
+            let decl = match *tpl.decl {
+                // alias template: template<...> using A = ...;
+                Declaration::UsingAlias(mut alias) => {
+                    alias.template = Some(tpl.params);
+                    Declaration::UsingAlias(alias)
+                }
+                decl => Declaration::Template(TemplateDecl {
+                    params: tpl.params,
+                    requires: tpl.requires,
+                    decl: Box::new(decl),
+                }),
+            };
+            return Ok((tok, Some(vec![decl])));
+        }
+
         let ep = ExternParser::new(self.lexer);
         let (tok, decl) = ep.parse(tok, context)?;
 
-        if decl.is_some() {
-            return Ok((tok, decl));
+        if let Some(decl) = decl {
+            return Ok((tok, Some(vec![decl])));
+        }
+
+        let mp = ModuleParser::new(self.lexer);
+        let (tok, decl) = mp.parse(tok, context)?;
+
+        if let Some(decl) = decl {
+            return Ok((tok, Some(vec![decl])));
         }
 
         let np = NamespaceParser::new(self.lexer);
         let (tok, decl) = np.parse(tok, context)?;
 
-        if decl.is_some() {
-            return Ok((tok, decl));
+        if let Some(decl) = decl {
+            return Ok((tok, Some(vec![decl])));
         }
 
         let sap = StaticAssertParser::new(self.lexer);
         let (tok, sa) = sap.parse(tok, context)?;
 
         if let Some(sa) = sa {
-            return Ok((tok, Some(Declaration::StaticAssert(sa))));
+            return Ok((tok, Some(vec![Declaration::StaticAssert(sa)])));
         }
 
         let ap = AttributesParser::new(self.lexer);
@@ -126,7 +193,10 @@ impl<'a, L: TLexer> DeclarationParser<'a, L> {
 
         let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
         if tok == Token::SemiColon {
-            return Ok((None, Some(Declaration::Attributes(attrs.unwrap()))));
+            return Ok((
+                Some(tok),
+                Some(vec![Declaration::Attributes(attrs.unwrap())]),
+            ));
         }
         let tok = Some(tok);
 
@@ -135,7 +205,7 @@ impl<'a, L: TLexer> DeclarationParser<'a, L> {
 
         if let Some(mut asm) = asm {
             asm.attributes = attrs;
-            return Ok((tok, Some(Declaration::Asm(asm))));
+            return Ok((tok, Some(vec![Declaration::Asm(asm)])));
         }
 
         let up = UsingParser::new(self.lexer);
@@ -145,20 +215,52 @@ impl<'a, L: TLexer> DeclarationParser<'a, L> {
             if let Declaration::UsingNS(ref mut u) = using {
                 std::mem::swap(&mut u.attributes, &mut attrs);
             }
-            return Ok((tok, Some(using)));
+            return Ok((tok, Some(vec![using])));
         }
 
-        let tdp = TypeDeclaratorParser::new(self.lexer);
-        let (tok, decl) = tdp.parse(tok, hint, true, context)?;
+        // The leading attributes appertain to each declared entity
+        let tdp = TypeDeclaratorParser::new_with_attributes(self.lexer, attrs.clone());
+        let (tok, decls) = if list {
+            tdp.parse_list(tok, hint, context)?
+        } else {
+            tdp.parse_decl(tok, hint, context)?
+        };
+
+        let decls = match decls {
+            Some(Declarators::Types(decls)) => Some(decls),
+            Some(Declarators::Binding(binding)) => {
+                check_decl_attributes(attrs.as_ref(), self.lexer.span())?;
+                context.add_binding(&binding);
+                return Ok((tok, Some(vec![Declaration::StructuredBinding(binding)])));
+            }
+            None => None,
+        };
+
+        // deduction guide: S(T) -> S<T>;
+        if let (Some(Token::Lower), Some([decl])) = (&tok, decls.as_deref()) {
+            let dgp = DeductionGuideParser::new(self.lexer);
+            let (tok, guide) = dgp.parse(decl, context)?;
+
+            if let Some(guide) = guide {
+                return Ok((tok, Some(vec![Declaration::DeductionGuide(guide)])));
+            }
+        }
 
-        let decl = if let Some(decl) = decl {
-            context.add_type_decl(Rc::clone(&decl));
-            Some(Declaration::Type(decl))
+        let decls = if let Some(decls) = decls {
+            check_decl_attributes(attrs.as_ref(), self.lexer.span())?;
+            let decls = decls
+                .into_iter()
+                .map(|decl| {
+                    context.add_type_decl(Rc::clone(&decl));
+                    Declaration::Type(decl)
+                })
+                .collect();
+            Some(decls)
         } else {
             None
         };
 
-        Ok((tok, decl))
+        Ok((tok, decls))
     }
 }
 
@@ -181,16 +283,16 @@ impl<'a, L: TLexer> DeclarationListParser<'a, L> {
 
         loop {
             let dp = DeclarationParser::new(self.lexer);
-            let (tk, decl) = dp.parse(tok, None, context)?;
+            let (tk, decls) = dp.parse_list(tok, context)?;
 
-            tok = if let Some(decl) = decl {
-                let tk = if decl.has_semicolon() {
+            tok = if let Some(decls) = decls {
+                let tk = if decls.last().unwrap().has_semicolon() {
                     check_semicolon!(self, tk);
                     None
                 } else {
                     tk
                 };
-                list.push(decl);
+                list.extend(decls);
                 tk
             } else {
                 return Ok((tk, Some(list)));
@@ -217,4 +319,128 @@ mod tests {
 
     #[test]
     fn test_decl() {}*/
+
+    use super::*;
+    use crate::lexer::{preprocessor::context::DefaultContext, Lexer};
+    use crate::parser::attributes::{Attribute, AttributeArg, InitFini};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_decl_empty() {
+        let mut l = Lexer::<DefaultContext>::new(b";; int a; ;;; int b;; namespace A {};");
+        let p = DeclarationListParser::new(&mut l);
+        let mut context = Context::default();
+        let (tok, decls) = p.parse(None, &mut context).unwrap();
+        let decls = decls.unwrap();
+
+        let tok = tok.unwrap_or_else(|| l.next_useful());
+        assert_eq!(tok, Token::Eof);
+
+        let kinds: Vec<_> = decls
+            .iter()
+            .map(|d| match d {
+                Declaration::Empty => "empty",
+                Declaration::Type(_) => "type",
+                Declaration::Namespace(_) => "namespace",
+                _ => "other",
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                "empty",
+                "empty",
+                "type",
+                "empty",
+                "empty",
+                "empty",
+                "type",
+                "empty",
+                "namespace",
+                "empty"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decl_gnu_constructor() {
+        let mut l = Lexer::<DefaultContext>::new(
+            b"[[gnu::constructor(101)]] void init(); __attribute__((destructor)) void fini();",
+        );
+        let p = DeclarationListParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decls) = p.parse(None, &mut context).unwrap();
+        let decls = decls.unwrap();
+
+        let get = |i: usize| match &decls[i] {
+            Declaration::Type(decl) => decl.identifier.attributes.as_ref().unwrap()[0].clone(),
+            _ => panic!("not a type declaration"),
+        };
+
+        let init = get(0);
+        assert_eq!(
+            init,
+            Attribute {
+                namespace: Some("gnu".to_string()),
+                name: "constructor".to_string(),
+                arg: Some(AttributeArg {
+                    tokens: vec![Token::LiteralInt(101)],
+                }),
+                has_using: false,
+            }
+        );
+        assert_eq!(
+            init.get_init_fini(),
+            Some(InitFini {
+                is_ctor: true,
+                priority: Some(101),
+            })
+        );
+        assert_eq!(
+            get(1).get_init_fini(),
+            Some(InitFini {
+                is_ctor: false,
+                priority: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_decl_likely() {
+        let mut l = Lexer::<DefaultContext>::new(b"[[likely]] int x;");
+        let p = DeclarationListParser::new(&mut l);
+        let mut context = Context::default();
+
+        assert!(matches!(
+            p.parse(None, &mut context),
+            Err(ParserError::InvalidAttrInDecl { .. })
+        ));
+    }
+
+    #[test]
+    fn test_decl_list_deprecated() {
+        let mut l = Lexer::<DefaultContext>::new(b"int x [[deprecated]], y; int z;");
+        let p = DeclarationListParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decls) = p.parse(None, &mut context).unwrap();
+        let decls = decls.unwrap();
+
+        let decls: Vec<_> = decls
+            .iter()
+            .map(|d| match d {
+                Declaration::Type(decl) => decl,
+                _ => panic!("not a type declaration"),
+            })
+            .collect();
+        let names: Vec<_> = decls
+            .iter()
+            .map(|d| d.identifier.identifier.as_ref().unwrap().to_string())
+            .collect();
+        assert_eq!(names, vec!["x", "y", "z"]);
+
+        let x = decls[0].identifier.attributes.as_ref().unwrap();
+        assert!(x[0].get_deprecated().is_some());
+        assert!(decls[1].identifier.attributes.is_none());
+        assert_eq!(decls[0].typ, decls[1].typ);
+    }
 }
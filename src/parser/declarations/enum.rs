@@ -8,7 +8,7 @@ use termcolor::StandardStreamLock;
 use crate::lexer::{TLexer, Token};
 use crate::parser::attributes::{Attributes, AttributesParser};
 use crate::parser::context::{Context, ScopeKind, TypeToFix};
-use crate::parser::declarations::DeclSpecifierParser;
+use crate::parser::declarations::{DeclSpecifierParser, DeclSpecifiers};
 use crate::parser::dump::Dump;
 use crate::parser::errors::ParserError;
 use crate::parser::expressions::{ExprNode, ExpressionParser};
@@ -34,9 +34,9 @@ impl Kind {
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Entry {
-    pub(crate) name: String,
-    pub(crate) attributes: Option<Attributes>,
-    pub(crate) init: Option<ExprNode>,
+    pub name: String,
+    pub attributes: Option<Attributes>,
+    pub init: Option<ExprNode>,
 }
 
 impl Dump for Entry {
@@ -55,11 +55,11 @@ impl Dump for Entries {
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Enum {
-    pub(crate) kind: Kind,
-    pub(crate) attributes: Option<Attributes>,
-    pub(crate) name: Option<Qualified>,
-    pub(crate) base: Option<Type>,
-    pub(crate) entries: Option<Entries>,
+    pub kind: Kind,
+    pub attributes: Option<Attributes>,
+    pub name: Option<Qualified>,
+    pub base: Option<Type>,
+    pub entries: Option<Entries>,
 }
 
 impl Dump for Enum {
@@ -90,7 +90,15 @@ impl<'a, L: TLexer> BaseTypeParser<'a, L> {
         }
 
         let dsp = DeclSpecifierParser::new(self.lexer);
-        let (tok, (_, ty, _, _)) = dsp.parse(None, None, context)?;
+        let (tok, DeclSpecifiers { typ: ty, .. }) = dsp.parse(None, None, context)?;
+
+        if ty.is_none() {
+            let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+            return Err(ParserError::InvalidTokenInEnum {
+                sp: self.lexer.span(),
+                tok,
+            });
+        }
 
         Ok((tok, ty))
     }
@@ -543,4 +551,71 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_enum_scoped_base_init() {
+        let mut l = Lexer::<DefaultContext>::new(
+            b"typedef unsigned char uint8_t; enum struct Color : uint8_t {red = 1, green = red + 1, blue}",
+        );
+        let mut context = Context::default();
+        let tdp = crate::parser::declarations::TypeDeclaratorParser::new(&mut l);
+        let (tok, typedef) = tdp.parse(None, None, false, &mut context).unwrap();
+        context.add_type_decl(typedef.unwrap());
+        assert_eq!(tok, Some(Token::SemiColon));
+
+        let p = EnumParser::new(&mut l);
+        let (_, e, _) = p.parse(None, &mut context).unwrap();
+        let e = e.unwrap();
+
+        assert_eq!(e.kind, Kind::Struct);
+        assert_eq!(e.name, Some(mk_id!("Color")));
+        if let Some(Type {
+            base: BaseType::UD(ud),
+            ..
+        }) = &e.base
+        {
+            assert_eq!(ud.name, mk_id!("uint8_t"));
+        } else {
+            panic!("Invalid base: {:?}", e.base);
+        }
+
+        let entries = e.entries.unwrap();
+        let names: Vec<_> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["red", "green", "blue"]);
+        assert!(matches!(&entries[1].init, Some(ExprNode::BinaryOp(_))));
+        assert!(entries[2].init.is_none());
+    }
+
+    #[test]
+    fn test_enum_class_base_no_body() {
+        let mut l = Lexer::<DefaultContext>::new(b"enum class Color : int;");
+        let p = EnumParser::new(&mut l);
+        let mut context = Context::default();
+        let (tok, e, _) = p.parse(None, &mut context).unwrap();
+
+        assert_eq!(tok, Some(Token::SemiColon));
+        assert_eq!(
+            e.unwrap(),
+            Enum {
+                kind: Kind::Class,
+                attributes: None,
+                name: Some(mk_id!("Color")),
+                base: Some(Type {
+                    base: BaseType::Primitive(Primitive::Int),
+                    cv: CVQualifier::empty(),
+                    pointers: None,
+                }),
+                entries: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_enum_missing_base() {
+        let mut l = Lexer::<DefaultContext>::new(b"enum class Color : {red}");
+        let p = EnumParser::new(&mut l);
+        let mut context = Context::default();
+
+        assert!(p.parse(None, &mut context).is_err());
+    }
 }
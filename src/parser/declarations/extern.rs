@@ -148,7 +148,9 @@ extern "C" {
                                         attributes: None
                                     },
                                     init: None,
+                                    align: None,
                                     bitfield_size: None,
+                                    explicit_spec: None,
                                 }),
                             }],
                             cv: CVQualifier::empty(),
@@ -160,7 +162,8 @@ extern "C" {
                             status: FunStatus::None,
                             requires: None,
                             ctor_init: None,
-                            body: RefCell::new(None)
+                            body: RefCell::new(None),
+                            handlers: RefCell::new(None)
                         })),
                         cv: CVQualifier::empty(),
                         pointers: None,
@@ -171,7 +174,9 @@ extern "C" {
                         attributes: None
                     },
                     init: None,
+                    align: None,
                     bitfield_size: None,
+                    explicit_spec: None,
                 }))],
                 multiple: true,
             })
@@ -215,7 +220,9 @@ extern double sqrt(double);
                                     attributes: None
                                 },
                                 init: None,
+                                align: None,
                                 bitfield_size: None,
+                                explicit_spec: None,
                             }),
                         }],
                         cv: CVQualifier::empty(),
@@ -227,7 +234,8 @@ extern double sqrt(double);
                         status: FunStatus::None,
                         requires: None,
                         ctor_init: None,
-                        body: RefCell::new(None)
+                        body: RefCell::new(None),
+                        handlers: RefCell::new(None)
                     })),
                     cv: CVQualifier::empty(),
                     pointers: None,
@@ -238,7 +246,9 @@ extern double sqrt(double);
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             }))
         );
     }
@@ -16,10 +16,12 @@ use crate::lexer::{TLexer, Token};
 use crate::parser::attributes::{Attributes, AttributesParser};
 use crate::parser::dump::Dump;
 use crate::parser::errors::ParserError;
-use crate::parser::expressions::{ExprNode, ExpressionParser, Parameters, ParametersParser};
+use crate::parser::expressions::{
+    ConstraintParser, ExprNode, ExpressionParser, Parameters, ParametersParser,
+};
 use crate::parser::initializer::{Initializer, InitializerParser};
 use crate::parser::names::{Name, OperatorParser, Qualified, QualifiedParser};
-use crate::parser::statements::{Compound, CompoundStmtParser};
+use crate::parser::statements::{Compound, CompoundStmtParser, HandlerParser, Handlers};
 use crate::parser::{Context, ScopeKind, TypeToFix};
 
 #[derive(Clone, Debug, PartialEq)]
@@ -184,11 +186,10 @@ impl<'a, L: TLexer> CtorInitializersParser<'a, L> {
         }
 
         let mut inits = Vec::new();
-        let mut tok = Some(tok);
 
         loop {
             let qp = QualifiedParser::new(self.lexer);
-            let (tk, name) = qp.parse(tok, None, context)?;
+            let (tk, name) = qp.parse(None, None, context)?;
 
             let name = if let Some(name) = name {
                 name
@@ -215,7 +216,6 @@ impl<'a, L: TLexer> CtorInitializersParser<'a, L> {
             if tk != Token::Comma {
                 return Ok((Some(tk), Some(inits)));
             }
-            tok = Some(tk);
         }
     }
 }
@@ -234,6 +234,7 @@ pub struct Function {
     pub requires: Option<ExprNode>,
     pub ctor_init: Option<CtorInitializers>,
     pub body: RefCell<Option<Compound>>,
+    pub handlers: RefCell<Option<Handlers>>,
 }
 
 impl Dump for Function {
@@ -256,7 +257,8 @@ impl Dump for Function {
             status,
             requires,
             ctor_init,
-            body
+            body,
+            handlers
         );
     }
 }
@@ -266,11 +268,11 @@ pub struct ParameterListParser<'a, L: TLexer> {
 }
 
 impl<'a, L: TLexer> ParameterListParser<'a, L> {
-    pub(super) fn new(lexer: &'a mut L) -> Self {
+    pub(crate) fn new(lexer: &'a mut L) -> Self {
         Self { lexer }
     }
 
-    pub(super) fn parse(
+    pub(crate) fn parse(
         self,
         tok: Option<Token>,
         skip_lparen: bool,
@@ -291,7 +293,7 @@ impl<'a, L: TLexer> ParameterListParser<'a, L> {
             let ap = AttributesParser::new(self.lexer);
             let (tk, attributes) = ap.parse(tok, context)?;
 
-            let dp = TypeDeclaratorParser::new(self.lexer);
+            let dp = TypeDeclaratorParser::new_param(self.lexer);
             let (tk, decl) = dp.parse(tk, None, true, context)?;
             let decl = if let Some(decl) = decl {
                 decl
@@ -330,7 +332,7 @@ impl<'a, L: TLexer> FunctionParser<'a, L> {
     }
 
     pub(super) fn parse(
-        self,
+        mut self,
         tok: Option<Token>,
         skip_lparen: bool,
         name: Option<&Qualified>,
@@ -376,10 +378,24 @@ impl<'a, L: TLexer> FunctionParser<'a, L> {
 
         let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
 
+        // trailing return type: the leading type (auto) is deduced from it
+        // and the type-id after the arrow has an abstract declarator (e.g. int(*)(int))
         let (tok, trailing) = if tok == Token::Arrow {
             let tdp = TypeDeclaratorParser::new(self.lexer);
             let (tok, decl) = tdp.parse(None, None, false, context)?;
-            (tok, decl.map(|d| Rc::try_unwrap(d).unwrap().typ))
+            match decl {
+                Some(decl) if decl.identifier.identifier.is_none() => {
+                    let typ = Rc::try_unwrap(decl).map_or_else(|d| d.typ.clone(), |d| d.typ);
+                    (tok, Some(typ))
+                }
+                _ => {
+                    let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+                    return Err(ParserError::InvalidTokenInFuncDecl {
+                        sp: self.lexer.span(),
+                        tok,
+                    });
+                }
+            }
         } else {
             (Some(tok), None)
         };
@@ -391,8 +407,8 @@ impl<'a, L: TLexer> FunctionParser<'a, L> {
         }
 
         let (tok, requires) = if tok == Token::Requires {
-            let mut ep = ExpressionParser::new(self.lexer, Token::Eof);
-            let (tok, e) = ep.parse(None, context)?;
+            let cp = ConstraintParser::new(self.lexer);
+            let (tok, e) = cp.parse(None, context)?;
             let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
             (tok, e)
         } else {
@@ -416,17 +432,35 @@ impl<'a, L: TLexer> FunctionParser<'a, L> {
             (tok, FunStatus::None)
         };
 
+        // function-try-block: try [ctor-initializer] compound-statement handler-seq
+        let (tok, is_try) = if tok == Token::Try {
+            (self.lexer.next_useful(), true)
+        } else {
+            (tok, false)
+        };
+
         let cip = CtorInitializersParser::new(self.lexer);
         let (tok, ctor_init) = cip.parse(Some(tok), context)?;
 
         let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        if is_try && tok != Token::LeftBrace {
+            return Err(ParserError::InvalidTokenInFuncDecl {
+                sp: self.lexer.span(),
+                tok,
+            });
+        }
 
-        let (tok, body, to_fix, saved) = if tok == Token::LeftBrace {
+        let (tok, body, handlers, to_fix, saved) = if tok == Token::LeftBrace {
             if context.in_class() {
                 // we're in a class/struct so we must postpone the body parsing
                 // to avoid to have issues due to the use of unknown members.
-                let (_, saved) = self.lexer.save_until(Token::RightBrace, 1);
-                (None, Some(Compound::default()), None, Some(saved))
+                let (_, mut saved) = self.lexer.save_until(Token::RightBrace, 1);
+                let tok = if is_try {
+                    Some(self.save_handlers(&mut saved)?)
+                } else {
+                    None
+                };
+                (tok, Some(Compound::default()), None, None, Some(saved))
             } else {
                 context.set_current(name, ScopeKind::Function);
                 for param in params.iter() {
@@ -434,11 +468,16 @@ impl<'a, L: TLexer> FunctionParser<'a, L> {
                 }
                 let cp = CompoundStmtParser::new(self.lexer);
                 let (tok, body) = cp.parse(None, context)?;
+                let (tok, handlers) = if is_try {
+                    self.parse_handlers(true, context)?
+                } else {
+                    (tok, None)
+                };
                 let to_fix = context.pop_n(name.map_or(1, |n| n.len()));
-                (tok, body, to_fix, None)
+                (tok, body, handlers, to_fix, None)
             }
         } else {
-            (Some(tok), None, None, None)
+            (Some(tok), None, None, None, None)
         };
 
         let fun = Function {
@@ -454,10 +493,61 @@ impl<'a, L: TLexer> FunctionParser<'a, L> {
             requires,
             ctor_init,
             body: RefCell::new(body),
+            handlers: RefCell::new(handlers),
         };
 
         Ok((tok, Some(fun), to_fix, saved))
     }
+
+    /// The handlers of a function-try-block in a class are saved with the body
+    /// since they can use some unparsed members too
+    fn save_handlers(&mut self, saved: &mut SavedLexer) -> Result<Token, ParserError> {
+        let mut tok = self.lexer.next_useful();
+        if tok != Token::Catch {
+            return Err(ParserError::InvalidTokenInFuncDecl {
+                sp: self.lexer.span(),
+                tok,
+            });
+        }
+
+        while tok == Token::Catch {
+            // catch ( exception-declaration ) compound-statement
+            saved.push(tok);
+            let (_, handler) = self.lexer.save_until(Token::RightBrace, 0);
+            saved.append(handler);
+            tok = self.lexer.next_useful();
+        }
+
+        Ok(tok)
+    }
+
+    pub(super) fn parse_handlers(
+        &mut self,
+        is_try: bool,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<Handlers>), ParserError> {
+        if !is_try {
+            return Ok((None, None));
+        }
+
+        let mut handlers = Vec::new();
+        let mut tok = None;
+        loop {
+            let hp = HandlerParser::new(self.lexer);
+            let (tk, handler) = hp.parse(tok, context)?;
+            if let Some(handler) = handler {
+                handlers.push(handler);
+                tok = tk;
+            } else if handlers.is_empty() {
+                return Err(ParserError::InvalidTokenInFuncDecl {
+                    sp: self.lexer.span(),
+                    tok: tk.unwrap(),
+                });
+            } else {
+                return Ok((tk, Some(handlers)));
+            }
+        }
+    }
 }
 
 pub struct ExceptionParser<'a, L: TLexer> {
@@ -486,7 +576,15 @@ impl<'a, L: TLexer> ExceptionParser<'a, L> {
                 if tok == Token::LeftParen {
                     let mut ep = ExpressionParser::new(self.lexer, Token::RightParen);
                     let (tok, exp) = ep.parse(None, context)?;
-                    Ok((tok, Some(Exception::Noexcept(exp))))
+
+                    let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+                    if tok != Token::RightParen {
+                        return Err(ParserError::InvalidTokenInFuncDecl {
+                            sp: self.lexer.span(),
+                            tok,
+                        });
+                    }
+                    Ok((None, Some(Exception::Noexcept(exp))))
                 } else {
                     Ok((Some(tok), Some(Exception::Noexcept(None))))
                 }
@@ -574,7 +672,9 @@ impl<'a, L: TLexer> ConvOperatorDeclaratorParser<'a, L> {
                         attributes,
                     },
                     init: None,
+                    align: None,
                     bitfield_size: None,
+                    explicit_spec: None,
                 }),
                 to_fix,
                 saved,
@@ -0,0 +1,199 @@
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use termcolor::StandardStreamLock;
+
+use super::{Parameter, TypeDeclarator};
+use crate::lexer::lexer::{TLexer, Token};
+use crate::parser::context::Context;
+use crate::parser::dump::Dump;
+use crate::parser::errors::ParserError;
+use crate::parser::expressions::{parse_template_args, ExprNode, Parameters};
+use crate::parser::names::Qualified;
+use crate::parser::types::BaseType;
+
+/// Class template argument deduction guide: explicit(cond) S(T) -> S<T>;
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeductionGuide {
+    pub explicit: Option<Option<ExprNode>>,
+    pub name: Qualified,
+    pub params: Vec<Parameter>,
+    pub target: Qualified,
+    pub args: Parameters,
+}
+
+impl Dump for DeductionGuide {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(
+            self,
+            name,
+            "deduction-guide",
+            prefix,
+            last,
+            stdout,
+            explicit,
+            name,
+            params,
+            target,
+            args
+        );
+    }
+}
+
+pub(crate) struct DeductionGuideParser<'a, L: TLexer> {
+    lexer: &'a mut L,
+}
+
+impl<'a, L: TLexer> DeductionGuideParser<'a, L> {
+    pub(super) fn new(lexer: &'a mut L) -> Self {
+        Self { lexer }
+    }
+
+    /// The part before the template arguments has been parsed as a constructor-like
+    /// function declaration with a trailing return type and the '<' has been consumed
+    pub(super) fn parse(
+        self,
+        decl: &TypeDeclarator,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<DeductionGuide>), ParserError> {
+        let (name, params, target) = match &decl.typ.base {
+            BaseType::Function(fun) if decl.identifier.identifier.is_none() => {
+                match (
+                    fun.return_type.as_ref().map(|t| &t.base),
+                    fun.trailing.as_ref().map(|t| &t.base),
+                ) {
+                    (Some(BaseType::UD(name)), Some(BaseType::UD(target))) => {
+                        (&name.name, &fun.params, &target.name)
+                    }
+                    _ => return Ok((Some(Token::Lower), None)),
+                }
+            }
+            _ => return Ok((Some(Token::Lower), None)),
+        };
+
+        let args = parse_template_args(self.lexer, context)?;
+
+        Ok((
+            None,
+            Some(DeductionGuide {
+                explicit: decl.explicit_spec.clone(),
+                name: name.clone(),
+                params: params.clone(),
+                target: target.clone(),
+                args,
+            }),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lexer::{preprocessor::context::DefaultContext, Lexer};
+    use crate::parser::declarations::{
+        Declaration, DeclarationListParser, MSModifier, Pointer, PtrKind, TemplateDecl,
+    };
+    use crate::parser::literals::Bool;
+    use crate::parser::types::{CVQualifier, Primitive, Type};
+    use pretty_assertions::assert_eq;
+
+    fn parse(s: &[u8]) -> Vec<Declaration> {
+        let mut l = Lexer::<DefaultContext>::new(s);
+        let p = DeclarationListParser::new(&mut l);
+        let mut context = Context::default();
+        let (tok, decls) = p.parse(None, &mut context).unwrap();
+
+        let tok = tok.unwrap_or_else(|| l.next_useful());
+        assert_eq!(tok, Token::Eof);
+
+        decls.unwrap()
+    }
+
+    fn int() -> ExprNode {
+        ExprNode::Type(Box::new(Type {
+            base: BaseType::Primitive(Primitive::Int),
+            cv: CVQualifier::empty(),
+            pointers: None,
+        }))
+    }
+
+    #[test]
+    fn test_deduction_guide() {
+        let decls = parse(b"S(int) -> S<int>;");
+
+        let guide = match &decls[0] {
+            Declaration::DeductionGuide(guide) => guide,
+            _ => panic!("not a deduction guide"),
+        };
+
+        assert_eq!(guide.explicit, None);
+        assert_eq!(guide.name, mk_id!("S"));
+        assert_eq!(guide.params.len(), 1);
+        assert_eq!(guide.target, mk_id!("S"));
+        assert_eq!(guide.args, vec![int()]);
+    }
+
+    #[test]
+    fn test_deduction_guide_explicit() {
+        let decls = parse(
+            b"
+explicit S(const char*) -> S<int, 2>;
+template<class T> explicit(true) S(T*, T*) -> S<T>;
+",
+        );
+
+        let guide = match &decls[0] {
+            Declaration::DeductionGuide(guide) => guide,
+            _ => panic!("not a deduction guide"),
+        };
+        assert_eq!(guide.explicit, Some(None));
+        assert_eq!(guide.args.len(), 2);
+
+        let guide = match &decls[1] {
+            Declaration::Template(TemplateDecl { decl, .. }) => match decl.as_ref() {
+                Declaration::DeductionGuide(guide) => guide,
+                _ => panic!("not a deduction guide"),
+            },
+            _ => panic!("not a template"),
+        };
+        assert_eq!(
+            guide.explicit,
+            Some(Some(ExprNode::Bool(Box::new(Bool { value: true }))))
+        );
+        assert_eq!(guide.params.len(), 2);
+        assert_eq!(guide.target, mk_id!("S"));
+    }
+
+    #[test]
+    fn test_deduction_guide_ptr_args() {
+        let decls = parse(b"S(const char*) -> S<char*, const int*>;");
+
+        let guide = match &decls[0] {
+            Declaration::DeductionGuide(guide) => guide,
+            _ => panic!("not a deduction guide"),
+        };
+
+        let ptr = |base, cv| {
+            ExprNode::Type(Box::new(Type {
+                base: BaseType::Primitive(base),
+                cv,
+                pointers: Some(vec![Pointer {
+                    kind: PtrKind::Pointer,
+                    attributes: None,
+                    cv: CVQualifier::empty(),
+                    ms: MSModifier::empty(),
+                }]),
+            }))
+        };
+        assert_eq!(
+            guide.args,
+            vec![
+                ptr(Primitive::Char, CVQualifier::empty()),
+                ptr(Primitive::Int, CVQualifier::CONST),
+            ]
+        );
+    }
+}
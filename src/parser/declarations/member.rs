@@ -6,9 +6,10 @@
 use std::rc::Rc;
 use termcolor::StandardStreamLock;
 
-use super::bitfield::BitFieldDeclaratorParser;
+use super::types::Declarators;
 use super::{StaticAssert, StaticAssertParser, UsingAlias, UsingDecl, UsingEnum, UsingParser};
 use crate::lexer::{TLexer, Token};
+use crate::parser::attributes::{check_decl_attributes, AttributesParser};
 use crate::parser::declarations::{Declaration, TypeDeclarator, TypeDeclaratorParser};
 use crate::parser::dump::Dump;
 use crate::parser::errors::ParserError;
@@ -72,6 +73,8 @@ pub(super) enum Visibility {
 pub(super) enum MemberRes {
     Vis(Visibility),
     Decl(Member),
+    /// A member declaration with several declarators: int a : 4, b : 4;
+    Decls(Members),
 }
 
 pub struct MemberParser<'a, L: TLexer> {
@@ -90,7 +93,7 @@ impl<'a, L: TLexer> MemberParser<'a, L> {
     ) -> Result<(Option<Token>, Option<MemberRes>), ParserError> {
         let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
         if tok == Token::SemiColon {
-            return Ok((None, Some(MemberRes::Decl(Member::Empty))));
+            return Ok((Some(tok), Some(MemberRes::Decl(Member::Empty))));
         }
         let tok = Some(tok);
 
@@ -127,28 +130,38 @@ impl<'a, L: TLexer> MemberParser<'a, L> {
             return Ok((tok, Some(MemberRes::Decl(using))));
         }
 
-        let tdp = TypeDeclaratorParser::new(self.lexer);
-        let (tok, typ) = tdp.parse(tok, None, true, context)?;
-
-        let mut typ = if let Some(typ) = typ {
-            typ
-        } else {
-            return Ok((tok, None));
-        };
-
-        let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
-        let tok = if tok == Token::Colon {
-            // we've a bitfield
-            let bfdp = BitFieldDeclaratorParser::new(self.lexer);
-            let tok = bfdp.parse(None, Rc::get_mut(&mut typ).unwrap(), context)?;
-            tok
-        } else {
-            Some(tok)
+        // [[no_unique_address]] T x;: the attributes appertain to the declared entity
+        let ap = AttributesParser::new(self.lexer);
+        let (tok, attrs) = ap.parse(tok, context)?;
+        check_decl_attributes(attrs.as_ref(), self.lexer.span())?;
+
+        let tdp = TypeDeclaratorParser::new_member(self.lexer, attrs);
+        let (tok, decls) = tdp.parse_list(tok, None, context)?;
+
+        let mut decls = match decls {
+            Some(Declarators::Types(decls)) => decls,
+            Some(Declarators::Binding(_)) => {
+                return Err(ParserError::InvalidTokenInClass {
+                    sp: self.lexer.span(),
+                    tok: tok.unwrap_or(Token::Eof),
+                });
+            }
+            None => {
+                return Ok((tok, None));
+            }
         };
 
-        context.add_type_decl(Rc::clone(&typ));
+        for typ in decls.iter() {
+            context.add_type_decl(Rc::clone(typ));
+        }
 
-        Ok((tok, Some(MemberRes::Decl(Member::Type(typ)))))
+        if decls.len() == 1 {
+            let typ = decls.remove(0);
+            Ok((tok, Some(MemberRes::Decl(Member::Type(typ)))))
+        } else {
+            let members = decls.into_iter().map(Member::Type).collect();
+            Ok((tok, Some(MemberRes::Decls(members))))
+        }
     }
 }
 
@@ -193,7 +206,8 @@ mod tests {
 
     use super::*;
     use crate::lexer::{preprocessor::context::DefaultContext, Lexer};
-    use crate::parser::declarations::{Identifier, Specifier};
+    use crate::parser::attributes::Attribute;
+    use crate::parser::declarations::{Identifier, RefQualifier, Specifier};
     use crate::parser::expressions::*;
     use crate::parser::initializer::Initializer;
     use crate::parser::literals::*;
@@ -244,9 +258,11 @@ mod tests {
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: Some(ExprNode::Integer(Box::new(Integer {
                     value: IntLiteral::Int(4)
                 }))),
+                explicit_spec: None,
             }))
         );
     }
@@ -280,9 +296,11 @@ mod tests {
                 init: Some(Initializer::Equal(ExprNode::Integer(Box::new(Integer {
                     value: IntLiteral::Int(1)
                 })))),
+                align: None,
                 bitfield_size: Some(ExprNode::Integer(Box::new(Integer {
                     value: IntLiteral::Int(4)
                 }))),
+                explicit_spec: None,
             }))
         );
     }
@@ -318,10 +336,63 @@ mod tests {
                         value: IntLiteral::Int(1)
                     }
                 )),])),
+                align: None,
                 bitfield_size: Some(ExprNode::Integer(Box::new(Integer {
                     value: IntLiteral::Int(4)
                 }))),
+                explicit_spec: None,
             }))
         );
     }
+
+    #[test]
+    fn test_member_no_unique_address() {
+        let mut l = Lexer::<DefaultContext>::new(b"[[no_unique_address]] Empty e{};");
+        let p = MemberParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, m) = p.parse(None, &mut context).unwrap();
+        let t = if let Some(MemberRes::Decl(Member::Type(t))) = m {
+            t
+        } else {
+            panic!("Not a type declarator");
+        };
+
+        assert_eq!(t.identifier.identifier, Some(mk_id!("e")));
+        assert_eq!(
+            t.identifier.attributes,
+            Some(vec![Attribute {
+                namespace: None,
+                name: "no_unique_address".to_string(),
+                arg: None,
+                has_using: false,
+            }])
+        );
+        assert_eq!(t.init, Some(Initializer::Brace(vec![])));
+    }
+
+    #[test]
+    fn test_member_qualified_requires() {
+        let mut l = Lexer::<DefaultContext>::new(b"void f() const & requires C<T>;");
+        let p = MemberParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, m) = p.parse(None, &mut context).unwrap();
+        let t = if let Some(MemberRes::Decl(Member::Type(t))) = m {
+            t
+        } else {
+            panic!("Not a type declarator");
+        };
+
+        let fun = if let BaseType::Function(fun) = &t.typ.base {
+            fun
+        } else {
+            panic!("Not a function: {:?}", t);
+        };
+
+        assert_eq!(fun.cv, CVQualifier::CONST);
+        assert_eq!(fun.refq, RefQualifier::LValue);
+        assert!(matches!(
+            &fun.requires,
+            Some(ExprNode::TemplateId(id)) if id.name == mk_id!("C")
+        ));
+    }
 }
@@ -30,6 +30,9 @@ pub use self::member::*;
 pub mod bitfield;
 pub use self::bitfield::*;
 
+pub mod binding;
+pub use self::binding::*;
+
 mod using;
 pub use self::using::*;
 
@@ -45,5 +48,14 @@ pub use self::namespace::*;
 pub mod r#extern;
 pub use self::r#extern::*;
 
+pub mod module;
+pub use self::module::*;
+
 mod class;
 pub use self::class::*;
+
+pub mod template;
+pub use self::template::*;
+
+pub mod guide;
+pub use self::guide::*;
@@ -0,0 +1,442 @@
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use termcolor::StandardStreamLock;
+
+use super::{Declaration, DeclarationListParser, DeclarationParser, Declarations};
+use crate::lexer::lexer::{TLexer, Token};
+use crate::parser::attributes::{Attributes, AttributesParser};
+use crate::parser::dump::Dump;
+use crate::parser::errors::ParserError;
+use crate::parser::Context;
+
+/// module; (global module fragment), module :private; or export module A.B:part;
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModuleDecl {
+    pub export: bool,
+    pub name: Option<String>,
+    pub partition: Option<String>,
+    pub attributes: Option<Attributes>,
+}
+
+impl Dump for ModuleDecl {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(self, name, "module", prefix, last, stdout, export, name, partition, attributes);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImportName {
+    Module(String),
+    Partition(String),
+    /// import <header>;
+    Header(String),
+    /// import "header";
+    QuotedHeader(String),
+}
+
+impl Dump for ImportName {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        let val = match self {
+            Self::Module(m) => m.clone(),
+            Self::Partition(p) => format!(":{}", p),
+            Self::Header(h) => format!("<{}>", h),
+            Self::QuotedHeader(h) => format!("\"{}\"", h),
+        };
+        dump_str!(name, val, Cyan, prefix, last, stdout);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportDecl {
+    pub export: bool,
+    pub name: ImportName,
+    pub attributes: Option<Attributes>,
+}
+
+impl Dump for ImportDecl {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(self, name, "import", prefix, last, stdout, export, name, attributes);
+    }
+}
+
+/// export { ... } or export followed by a single declaration
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportBlock {
+    pub decls: Declarations,
+    pub multiple: bool,
+}
+
+impl Dump for ExportBlock {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(self, name, "export", prefix, last, stdout, decls, multiple);
+    }
+}
+
+pub(crate) struct ModuleParser<'a, L: TLexer> {
+    lexer: &'a mut L,
+}
+
+impl<'a, L: TLexer> ModuleParser<'a, L> {
+    pub(super) fn new(lexer: &'a mut L) -> Self {
+        Self { lexer }
+    }
+
+    pub(super) fn parse(
+        self,
+        tok: Option<Token>,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<Declaration>), ParserError> {
+        let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        let (export, tok) = if tok == Token::Export {
+            (true, self.lexer.next_useful())
+        } else {
+            (false, tok)
+        };
+
+        match tok {
+            Token::Module => self.parse_module(export, context),
+            Token::Import => self.parse_import(export, context),
+            _ if export => self.parse_export(tok, context),
+            _ => Ok((Some(tok), None)),
+        }
+    }
+
+    fn parse_module(
+        mut self,
+        export: bool,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<Declaration>), ParserError> {
+        let tok = self.lexer.next_useful();
+        let (name, tok) = self.parse_name(tok)?;
+
+        let (partition, tok) = if tok == Token::Colon {
+            let tok = self.lexer.next_useful();
+            if tok == Token::Private {
+                (Some("private".to_string()), self.lexer.next_useful())
+            } else {
+                self.parse_name(tok)?
+            }
+        } else {
+            (None, tok)
+        };
+
+        let (tok, attributes) = self.parse_attributes(tok, context)?;
+
+        Ok((
+            Some(tok),
+            Some(Declaration::Module(ModuleDecl {
+                export,
+                name,
+                partition,
+                attributes,
+            })),
+        ))
+    }
+
+    fn parse_import(
+        mut self,
+        export: bool,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<Declaration>), ParserError> {
+        let tok = self.lexer.next_useful();
+        let (name, tok) = match tok {
+            Token::HeaderName(header) => (ImportName::Header(header), self.lexer.next_useful()),
+            Token::LiteralString(header) => {
+                (ImportName::QuotedHeader(header), self.lexer.next_useful())
+            }
+            Token::Colon => {
+                let tok = self.lexer.next_useful();
+                let (name, tok) = self.parse_name(tok)?;
+                if let Some(name) = name {
+                    (ImportName::Partition(name), tok)
+                } else {
+                    return Err(ParserError::InvalidTokenInModule {
+                        sp: self.lexer.span(),
+                        tok,
+                    });
+                }
+            }
+            _ => {
+                let (name, tok) = self.parse_name(tok)?;
+                if let Some(name) = name {
+                    (ImportName::Module(name), tok)
+                } else {
+                    return Err(ParserError::InvalidTokenInModule {
+                        sp: self.lexer.span(),
+                        tok,
+                    });
+                }
+            }
+        };
+
+        let (tok, attributes) = self.parse_attributes(tok, context)?;
+
+        Ok((
+            Some(tok),
+            Some(Declaration::Import(ImportDecl {
+                export,
+                name,
+                attributes,
+            })),
+        ))
+    }
+
+    fn parse_export(
+        self,
+        tok: Token,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<Declaration>), ParserError> {
+        if tok == Token::LeftBrace {
+            let dlp = DeclarationListParser::new(self.lexer);
+            let (tok, decls) = dlp.parse(None, context)?;
+
+            let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+            if tok != Token::RightBrace {
+                return Err(ParserError::InvalidTokenInModule {
+                    sp: self.lexer.span(),
+                    tok,
+                });
+            }
+
+            return Ok((
+                None,
+                Some(Declaration::Export(ExportBlock {
+                    decls: decls.unwrap(),
+                    multiple: true,
+                })),
+            ));
+        }
+
+        let dp = DeclarationParser::new(self.lexer);
+        let (tok, decls) = dp.parse_list(Some(tok), context)?;
+
+        if let Some(decls) = decls {
+            Ok((
+                tok,
+                Some(Declaration::Export(ExportBlock {
+                    decls,
+                    multiple: false,
+                })),
+            ))
+        } else {
+            Err(ParserError::InvalidTokenInModule {
+                sp: self.lexer.span(),
+                tok: tok.unwrap_or(Token::None),
+            })
+        }
+    }
+
+    /// Parse a dotted module name: A.B.C
+    fn parse_name(&mut self, tok: Token) -> Result<(Option<String>, Token), ParserError> {
+        let mut name = if let Token::Identifier(id) = tok {
+            id
+        } else {
+            return Ok((None, tok));
+        };
+
+        loop {
+            let tok = self.lexer.next_useful();
+            if tok != Token::Dot {
+                return Ok((Some(name), tok));
+            }
+
+            match self.lexer.next_useful() {
+                Token::Identifier(id) => {
+                    name.push('.');
+                    name.push_str(&id);
+                }
+                tok => {
+                    return Err(ParserError::InvalidTokenInModule {
+                        sp: self.lexer.span(),
+                        tok,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Parse the optional attributes before the semicolon which isn't consumed
+    fn parse_attributes(
+        &mut self,
+        tok: Token,
+        context: &mut Context,
+    ) -> Result<(Token, Option<Attributes>), ParserError> {
+        let ap = AttributesParser::new(self.lexer);
+        let (tok, attributes) = ap.parse(Some(tok), context)?;
+
+        let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        if tok != Token::SemiColon {
+            return Err(ParserError::InvalidTokenInModule {
+                sp: self.lexer.span(),
+                tok,
+            });
+        }
+
+        Ok((tok, attributes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lexer::{preprocessor::context::DefaultContext, Lexer};
+    use crate::parser::attributes::Attribute;
+    use crate::parser::names::Qualified;
+    use pretty_assertions::assert_eq;
+
+    fn parse(s: &[u8]) -> Declarations {
+        let mut l = Lexer::<DefaultContext>::new(s);
+        let p = DeclarationListParser::new(&mut l);
+        let mut context = Context::default();
+        let (tok, decls) = p.parse(None, &mut context).unwrap();
+
+        let tok = tok.unwrap_or_else(|| l.next_useful());
+        assert_eq!(tok, Token::Eof);
+
+        decls.unwrap()
+    }
+
+    fn module(export: bool, name: Option<&str>, partition: Option<&str>) -> Declaration {
+        Declaration::Module(ModuleDecl {
+            export,
+            name: name.map(|s| s.to_string()),
+            partition: partition.map(|s| s.to_string()),
+            attributes: None,
+        })
+    }
+
+    fn import(export: bool, name: ImportName) -> Declaration {
+        Declaration::Import(ImportDecl {
+            export,
+            name,
+            attributes: None,
+        })
+    }
+
+    #[test]
+    fn test_module_decl() {
+        let decls = parse(
+            b"
+module;
+export module A.B:part;
+module :private;
+",
+        );
+
+        assert_eq!(
+            decls,
+            vec![
+                module(false, None, None),
+                module(true, Some("A.B"), Some("part")),
+                module(false, None, Some("private")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_module_attributes() {
+        let decls = parse(b"export module foo [[deprecated]];");
+
+        assert_eq!(
+            decls,
+            vec![Declaration::Module(ModuleDecl {
+                export: true,
+                name: Some("foo".to_string()),
+                partition: None,
+                attributes: Some(vec![Attribute {
+                    namespace: None,
+                    name: "deprecated".to_string(),
+                    arg: None,
+                    has_using: false,
+                }]),
+            })]
+        );
+    }
+
+    #[test]
+    fn test_module_import() {
+        let decls = parse(
+            br#"
+import std.core;
+import :part;
+import <sys/types.h>;
+import "foo.h";
+export import bar;
+"#,
+        );
+
+        assert_eq!(
+            decls,
+            vec![
+                import(false, ImportName::Module("std.core".to_string())),
+                import(false, ImportName::Partition("part".to_string())),
+                import(false, ImportName::Header("sys/types.h".to_string())),
+                import(false, ImportName::QuotedHeader("foo.h".to_string())),
+                import(true, ImportName::Module("bar".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_module_export() {
+        let decls = parse(
+            b"
+export module A;
+export { int f(); int g(); }
+export int h();
+export namespace N {}
+int i;
+",
+        );
+
+        let kinds: Vec<_> = decls
+            .iter()
+            .map(|d| match d {
+                Declaration::Module(_) => "module".to_string(),
+                Declaration::Export(e) => format!("export{}", e.decls.len()),
+                Declaration::Type(_) => "type".to_string(),
+                _ => "other".to_string(),
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec!["module", "export2", "export1", "export1", "type"]
+        );
+
+        assert!(matches!(&decls[1], Declaration::Export(e) if e.multiple));
+        assert!(matches!(&decls[2], Declaration::Export(e) if !e.multiple));
+        assert!(
+            matches!(&decls[3], Declaration::Export(e) if matches!(e.decls[0], Declaration::Namespace(_)))
+        );
+    }
+
+    #[test]
+    fn test_module_export_block() {
+        let decls = parse(
+            b"
+export module A;
+export {
+    using T = int;
+    T f(T x);
+}
+",
+        );
+
+        assert_eq!(decls.len(), 2);
+        let block = match &decls[1] {
+            Declaration::Export(block) => block,
+            _ => panic!("not an export block"),
+        };
+
+        assert!(block.multiple);
+        assert_eq!(block.decls.len(), 2);
+        assert!(matches!(&block.decls[0], Declaration::UsingAlias(_)));
+        assert!(
+            matches!(&block.decls[1], Declaration::Type(t) if t.identifier.identifier == Some(mk_id!("f")))
+        );
+    }
+}
@@ -10,6 +10,7 @@ use super::{
     DeclHint, Declaration, DeclarationListParser, Declarations, Specifier, TypeDeclaratorParser,
 };
 use crate::lexer::{TLexer, Token};
+use crate::parser::attributes::{Attributes, AttributesParser};
 use crate::parser::dump::Dump;
 use crate::parser::errors::ParserError;
 use crate::parser::names::{Qualified, QualifiedParser};
@@ -44,12 +45,23 @@ impl Dump for NsNames {
 #[derive(Clone, Debug, PartialEq)]
 pub struct Namespace {
     pub name: NsNames,
+    pub attributes: Option<Attributes>,
     pub body: Declarations,
 }
 
 impl Dump for Namespace {
     fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
-        dump_obj!(self, name, "namespace", prefix, last, stdout, name, body);
+        dump_obj!(
+            self,
+            name,
+            "namespace",
+            prefix,
+            last,
+            stdout,
+            name,
+            attributes,
+            body
+        );
     }
 }
 
@@ -83,8 +95,12 @@ impl<'a, L: TLexer> NsNamesParser<'a, L> {
         Self { lexer }
     }
 
-    fn parse(self, _context: &mut Context) -> Result<(Option<Token>, NsNames), ParserError> {
-        let mut tok = self.lexer.next_useful();
+    fn parse(
+        self,
+        tok: Option<Token>,
+        _context: &mut Context,
+    ) -> Result<(Option<Token>, NsNames), ParserError> {
+        let mut tok = tok.unwrap_or_else(|| self.lexer.next_useful());
         let mut names = Vec::new();
         let mut inline = false;
 
@@ -148,8 +164,20 @@ impl<'a, L: TLexer> NamespaceParser<'a, L> {
             false
         };
 
+        // optional: attributes
+        let ap = AttributesParser::new(self.lexer);
+        let (tok, mut attributes) = ap.parse(None, context)?;
+
         let np = NsNamesParser::new(self.lexer);
-        let (tok, mut name) = np.parse(context)?;
+        let (tok, mut name) = np.parse(tok, context)?;
+
+        // attributes after the name are not standard but accepted by some compilers
+        let ap = AttributesParser::new(self.lexer);
+        let (tok, attrs) = ap.parse(tok, context)?;
+        if let Some(attrs) = attrs {
+            attributes.get_or_insert_with(Vec::new).extend(attrs);
+        }
+
         let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
 
         if inline {
@@ -168,6 +196,7 @@ impl<'a, L: TLexer> NamespaceParser<'a, L> {
                 if tok == Token::RightBrace {
                     let ns = Namespace {
                         name,
+                        attributes,
                         body: body.unwrap(),
                     };
 
@@ -210,6 +239,8 @@ mod tests {
 
     use super::*;
     use crate::lexer::{preprocessor::context::DefaultContext, Lexer};
+    use crate::parser::attributes::{Attribute, AttributeArg};
+    use crate::parser::context::SearchResult;
     use crate::parser::declarations::{types, *};
     use crate::parser::names::*;
     use crate::parser::types::*;
@@ -220,7 +251,7 @@ mod tests {
         let mut l = Lexer::<DefaultContext>::new(b"A");
         let p = NsNamesParser::new(&mut l);
         let mut context = Context::default();
-        let (_, ns) = p.parse(&mut context).unwrap();
+        let (_, ns) = p.parse(None, &mut context).unwrap();
 
         assert_eq!(
             ns,
@@ -236,7 +267,7 @@ mod tests {
         let mut l = Lexer::<DefaultContext>::new(b"A::inline B::C::inline D::E");
         let p = NsNamesParser::new(&mut l);
         let mut context = Context::default();
-        let (_, ns) = p.parse(&mut context).unwrap();
+        let (_, ns) = p.parse(None, &mut context).unwrap();
 
         assert_eq!(
             ns,
@@ -289,12 +320,14 @@ namespace A {
                     inline: false,
                     name: "A".to_string(),
                 },],
+                attributes: None,
                 body: vec![
                     Declaration::Namespace(Namespace {
                         name: vec![NsName {
                             inline: false,
                             name: "B".to_string(),
                         },],
+                        attributes: None,
                         body: vec![Declaration::Type(Rc::new(TypeDeclarator {
                             typ: Type {
                                 base: BaseType::Function(Box::new(Function {
@@ -313,7 +346,8 @@ namespace A {
                                     status: FunStatus::None,
                                     requires: None,
                                     ctor_init: None,
-                                    body: RefCell::new(None)
+                                    body: RefCell::new(None),
+                                    handlers: RefCell::new(None)
                                 })),
                                 cv: CVQualifier::empty(),
                                 pointers: None,
@@ -324,7 +358,9 @@ namespace A {
                                 attributes: None
                             },
                             init: None,
+                            align: None,
                             bitfield_size: None,
+                            explicit_spec: None,
                         }))],
                     },),
                     Declaration::Type(Rc::new(TypeDeclarator {
@@ -345,7 +381,8 @@ namespace A {
                                 status: FunStatus::None,
                                 requires: None,
                                 ctor_init: None,
-                                body: RefCell::new(None)
+                                body: RefCell::new(None),
+                                handlers: RefCell::new(None)
                             })),
                             cv: CVQualifier::empty(),
                             pointers: None,
@@ -356,7 +393,9 @@ namespace A {
                             attributes: None
                         },
                         init: None,
+                        align: None,
                         bitfield_size: None,
+                        explicit_spec: None,
                     }))
                 ],
             })
@@ -379,4 +418,159 @@ namespace A {
             })
         );
     }
+
+    #[test]
+    fn test_namespace_attributes() {
+        let mut l = Lexer::<DefaultContext>::new(
+            br#"
+namespace [[deprecated]] A [[gnu::visibility("hidden")]] {
+    [[maybe_unused]];
+    ;
+}
+        "#,
+        );
+        let p = DeclarationParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, ns) = p.parse(None, None, &mut context).unwrap();
+        let ns = ns.unwrap();
+
+        assert_eq!(
+            ns,
+            Declaration::Namespace(Namespace {
+                name: vec![NsName {
+                    inline: false,
+                    name: "A".to_string(),
+                },],
+                attributes: Some(vec![
+                    Attribute {
+                        namespace: None,
+                        name: "deprecated".to_string(),
+                        arg: None,
+                        has_using: false,
+                    },
+                    Attribute {
+                        namespace: Some("gnu".to_string()),
+                        name: "visibility".to_string(),
+                        arg: Some(AttributeArg {
+                            tokens: vec![Token::LiteralString("hidden".to_string())],
+                        }),
+                        has_using: false,
+                    },
+                ]),
+                body: vec![
+                    Declaration::Attributes(vec![Attribute {
+                        namespace: None,
+                        name: "maybe_unused".to_string(),
+                        arg: None,
+                        has_using: false,
+                    }]),
+                    Declaration::Empty,
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_namespace_nested_two() {
+        let mut l = Lexer::<DefaultContext>::new(b"namespace A::B {}");
+        let p = DeclarationParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, ns) = p.parse(None, None, &mut context).unwrap();
+        let ns = ns.unwrap();
+
+        assert_eq!(
+            ns,
+            Declaration::Namespace(Namespace {
+                name: vec![
+                    NsName {
+                        inline: false,
+                        name: "A".to_string(),
+                    },
+                    NsName {
+                        inline: false,
+                        name: "B".to_string(),
+                    },
+                ],
+                attributes: None,
+                body: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn test_namespace_nested_three() {
+        let mut l = Lexer::<DefaultContext>::new(b"namespace A::B::C { int x; }");
+        let p = DeclarationParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, ns) = p.parse(None, None, &mut context).unwrap();
+        let ns = ns.unwrap();
+
+        let x = if let Declaration::Namespace(Namespace { name, body, .. }) = &ns {
+            assert_eq!(
+                name.iter().map(|n| n.name.as_str()).collect::<Vec<_>>(),
+                vec!["A", "B", "C"]
+            );
+            if let Declaration::Type(x) = &body[0] {
+                Rc::clone(x)
+            } else {
+                unreachable!();
+            }
+        } else {
+            unreachable!();
+        };
+
+        // the nested scopes are the same as with namespace A { namespace B { ... } }
+        if let Some(SearchResult::Var(var)) = context.search(Some(&mk_id!("A", "B", "C", "x"))) {
+            assert!(Rc::ptr_eq(&var, &x));
+        } else {
+            unreachable!();
+        }
+    }
+
+    #[test]
+    fn test_namespace_nested_inline() {
+        let mut l = Lexer::<DefaultContext>::new(b"namespace A::inline B {}");
+        let p = DeclarationParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, ns) = p.parse(None, None, &mut context).unwrap();
+        let ns = ns.unwrap();
+
+        assert_eq!(
+            ns,
+            Declaration::Namespace(Namespace {
+                name: vec![
+                    NsName {
+                        inline: false,
+                        name: "A".to_string(),
+                    },
+                    NsName {
+                        inline: true,
+                        name: "B".to_string(),
+                    },
+                ],
+                attributes: None,
+                body: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn test_namespace_nested_inline_middle() {
+        let mut l = Lexer::<DefaultContext>::new(b"namespace a::inline b::c {}");
+        let p = DeclarationParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, ns) = p.parse(None, None, &mut context).unwrap();
+
+        let ns = if let Some(Declaration::Namespace(ns)) = ns {
+            ns
+        } else {
+            panic!("Not a namespace: {:?}", ns);
+        };
+        let names: Vec<_> = ns
+            .name
+            .iter()
+            .map(|n| (n.name.as_str(), n.inline))
+            .collect();
+        assert_eq!(names, vec![("a", false), ("b", true), ("c", false)]);
+    }
 }
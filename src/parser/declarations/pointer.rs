@@ -8,7 +8,7 @@ use termcolor::StandardStreamLock;
 
 use super::super::types::CVQualifier;
 use super::specifier::Specifier;
-use super::types::{NoPtrDeclaratorParser, TypeDeclarator};
+use super::types::{DeclaratorParser, TypeDeclarator};
 use crate::lexer::{TLexer, Token};
 use crate::parser::attributes::{Attributes, AttributesParser};
 use crate::parser::dump::Dump;
@@ -217,18 +217,19 @@ impl<'a, L: TLexer> ParenPointerDeclaratorParser<'a, L> {
         // The previous token was a parenthesis
         // so we can have some params (function type, e.g. int * (int, int)))
         // or a function/array pointer
-        let pdp = PointerDeclaratorParser::new(self.lexer);
-        let (tok, pointers) = pdp.parse(None, None, context)?;
+        let tok = self.lexer.next_useful();
 
-        if pointers.is_some() {
-            let npdp = NoPtrDeclaratorParser::new(self.lexer);
+        if PtrKind::is_ptr(&tok) {
+            // The declarator is parsed with a placeholder type (BaseType::None)
+            // which will be replaced by the type built by the outer declarator
+            let dp = DeclaratorParser::new(self.lexer);
             let typ = Type {
                 base: BaseType::None,
                 cv: CVQualifier::empty(),
-                pointers,
+                pointers: None,
             };
-            let (tok, decl, _, _) =
-                npdp.parse(tok, typ, Specifier::empty(), false, false, context)?;
+            let (tok, decl, _, _, _) =
+                dp.parse(Some(tok), typ, Specifier::empty(), false, false, context)?;
 
             let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
             if tok != Token::RightParen {
@@ -241,7 +242,7 @@ impl<'a, L: TLexer> ParenPointerDeclaratorParser<'a, L> {
             Ok((None, (decl, false)))
         } else {
             // we've function params
-            Ok((tok, (None, true)))
+            Ok((Some(tok), (None, true)))
         }
     }
 }
@@ -30,6 +30,7 @@ bitflags! {
         const STDCALL = 0b1_0000_0000_0000_0000;
         const THISCALL = 0b10_0000_0000_0000_0000;
         const VECTORCALL = 0b100_0000_0000_0000_0000;
+        const NORETURN = 0b1000_0000_0000_0000_0000;
     }
 }
 
@@ -75,7 +76,9 @@ impl ToString for Specifier {
             THISCALL,
             "__thiscall",
             VECTORCALL,
-            "__vectorcall"
+            "__vectorcall",
+            NORETURN,
+            "_Noreturn"
         )
     }
 }
@@ -129,7 +132,7 @@ impl Specifier {
                 *self |= Specifier::STATIC;
                 true
             }
-            Token::ThreadLocal => {
+            Token::ThreadLocal | Token::GnuThread => {
                 *self |= Specifier::THREAD_LOCAL;
                 true
             }
@@ -165,6 +168,10 @@ impl Specifier {
                 *self |= Specifier::VECTORCALL;
                 true
             }
+            Token::CNoreturn => {
+                *self |= Specifier::NORETURN;
+                true
+            }
             _ => false,
         }
     }
@@ -184,6 +191,7 @@ impl Specifier {
             | Token::Register
             | Token::Static
             | Token::ThreadLocal
+            | Token::GnuThread
             | Token::Extern
             | Token::Mutable
             | Token::Cdecl
@@ -191,7 +199,8 @@ impl Specifier {
             | Token::Fastcall
             | Token::Stdcall
             | Token::Thiscall
-            | Token::Vectorcall => true,
+            | Token::Vectorcall
+            | Token::CNoreturn => true,
             _ => false,
         }
     }
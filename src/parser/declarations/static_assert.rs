@@ -5,6 +5,7 @@
 
 use termcolor::StandardStreamLock;
 
+use crate::args::Language;
 use crate::lexer::{TLexer, Token};
 use crate::parser::dump::Dump;
 use crate::parser::errors::ParserError;
@@ -79,6 +80,13 @@ impl<'a, L: TLexer> StaticAssertParser<'a, L> {
         let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
         match tok {
             Token::RightParen => {
+                if *context.get_language() == Language::C {
+                    // C11/C17: the message is required, static_assert being only a macro
+                    // for _Static_assert (the message is optional in C23 and C++17)
+                    return Err(ParserError::InvalidArgInStaticAssert {
+                        sp: self.lexer.span(),
+                    });
+                }
                 return Ok((
                     None,
                     Some(StaticAssert {
@@ -86,7 +94,7 @@ impl<'a, L: TLexer> StaticAssertParser<'a, L> {
                         string: None,
                         cpp,
                     }),
-                ))
+                ));
             }
             Token::Comma => {}
             _ => {
@@ -181,4 +189,37 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_static_assert_message() {
+        let parse = |s: &[u8], lang: Language| {
+            let mut l = Lexer::<DefaultContext>::new(s);
+            let p = StaticAssertParser::new(&mut l);
+            let mut context = Context::default();
+            context.set_language(lang);
+            p.parse(None, &mut context).map(|(_, u)| u.unwrap())
+        };
+
+        let u = parse(b"_Static_assert(1, \"x\")", Language::C).unwrap();
+        assert_eq!(u.string, Some("x".to_string()));
+        assert!(!u.cpp);
+
+        assert!(parse(b"_Static_assert(1)", Language::C).is_err());
+        assert!(parse(b"static_assert(1)", Language::C).is_err());
+
+        let u = parse(b"_Static_assert(1)", Language::C23).unwrap();
+        assert_eq!(u.string, None);
+
+        let u = parse(b"static_assert(1)", Language::C23).unwrap();
+        assert_eq!(u.string, None);
+
+        let u = parse(b"_Static_assert(1)", Language::CPP).unwrap();
+        assert_eq!(u.string, None);
+
+        // the default language is C++
+        let mut l = Lexer::<DefaultContext>::new(b"_Static_assert(1)");
+        let p = StaticAssertParser::new(&mut l);
+        let mut context = Context::default();
+        assert!(p.parse(None, &mut context).is_ok());
+    }
 }
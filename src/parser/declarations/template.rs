@@ -0,0 +1,617 @@
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::rc::Rc;
+use termcolor::StandardStreamLock;
+
+use super::types::{TypeDeclarator, TypeDeclaratorParser};
+use super::{Declaration, DeclarationParser};
+use crate::lexer::{TLexer, Token};
+use crate::parser::context::Context;
+use crate::parser::dump::Dump;
+use crate::parser::errors::ParserError;
+use crate::parser::expressions::{ConstraintParser, ExprNode, ExpressionParser};
+use crate::parser::initializer::Initializer;
+use crate::parser::names::{Identifier, Name, Qualified, QualifiedParser};
+use crate::parser::types::Type;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TypeParameterKey {
+    Class,
+    Typename,
+}
+
+impl TypeParameterKey {
+    fn from_tok(tok: &Token) -> Option<Self> {
+        match tok {
+            Token::Class => Some(Self::Class),
+            Token::Typename => Some(Self::Typename),
+            _ => None,
+        }
+    }
+
+    fn to_str(self) -> &'static str {
+        match self {
+            Self::Class => "class",
+            Self::Typename => "typename",
+        }
+    }
+}
+
+impl Dump for TypeParameterKey {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_str!(name, self.to_str(), Cyan, prefix, last, stdout);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeParameter {
+    pub key: TypeParameterKey,
+    pub name: Option<String>,
+    pub pack: bool,
+    pub default: Option<Type>,
+}
+
+impl Dump for TypeParameter {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(self, name, "type", prefix, last, stdout, key, name, pack, default);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemplateTemplateParameter {
+    pub params: TemplateParameters,
+    pub key: TypeParameterKey,
+    pub name: Option<String>,
+    pub pack: bool,
+    pub default: Option<Qualified>,
+}
+
+impl Dump for TemplateTemplateParameter {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(self, name, "template", prefix, last, stdout, params, key, name, pack, default);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NonTypeParameter {
+    pub decl: Rc<TypeDeclarator>,
+    pub pack: bool,
+}
+
+impl Dump for NonTypeParameter {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(self, name, "non-type", prefix, last, stdout, decl, pack);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TemplateParameter {
+    Type(TypeParameter),
+    NonType(NonTypeParameter),
+    Template(TemplateTemplateParameter),
+}
+
+impl Dump for TemplateParameter {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        match self {
+            Self::Type(x) => x.dump(name, prefix, last, stdout),
+            Self::NonType(x) => x.dump(name, prefix, last, stdout),
+            Self::Template(x) => x.dump(name, prefix, last, stdout),
+        }
+    }
+}
+
+impl TemplateParameter {
+    pub(crate) fn get_name(&self) -> Option<&str> {
+        match self {
+            Self::Type(x) => x.name.as_deref(),
+            Self::NonType(x) => x
+                .decl
+                .identifier
+                .identifier
+                .as_ref()
+                .and_then(|id| id.names.last())
+                .map(|name| name.as_ref()),
+            Self::Template(x) => x.name.as_deref(),
+        }
+    }
+}
+
+pub type TemplateParameters = Vec<TemplateParameter>;
+
+impl Dump for TemplateParameters {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_vec!(name, self, "par", prefix, last, stdout);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemplateDecl {
+    pub params: TemplateParameters,
+    pub requires: Option<ExprNode>,
+    pub decl: Box<Declaration>,
+}
+
+impl Dump for TemplateDecl {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(self, name, "template", prefix, last, stdout, params, requires, decl);
+    }
+}
+
+struct TemplateParameterParser<'a, L: TLexer> {
+    lexer: &'a mut L,
+}
+
+impl<'a, L: TLexer> TemplateParameterParser<'a, L> {
+    fn new(lexer: &'a mut L) -> Self {
+        Self { lexer }
+    }
+
+    fn parse_tail(&mut self) -> (Option<Token>, bool, Option<String>, bool) {
+        // [...] [name] [= default]
+        let mut tok = self.lexer.next_useful();
+        let pack = if tok == Token::Ellipsis {
+            tok = self.lexer.next_useful();
+            true
+        } else {
+            false
+        };
+
+        let name = if let Token::Identifier(id) = tok {
+            tok = self.lexer.next_useful();
+            Some(id)
+        } else {
+            None
+        };
+
+        if tok == Token::Equal {
+            (None, pack, name, true)
+        } else {
+            (Some(tok), pack, name, false)
+        }
+    }
+
+    fn parse(
+        mut self,
+        tok: Token,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<TemplateParameter>), ParserError> {
+        if tok == Token::Template {
+            let tok = self.lexer.next_useful();
+            if tok != Token::Lower {
+                return Err(ParserError::InvalidTokenInTemplate {
+                    sp: self.lexer.span(),
+                    tok,
+                });
+            }
+
+            let tpp = TemplateParametersParser::new(self.lexer);
+            let params = tpp.parse(context)?;
+
+            // template<...> class T or template<...> typename T (C++17)
+            let tok = self.lexer.next_useful();
+            let key = if let Some(key) = TypeParameterKey::from_tok(&tok) {
+                key
+            } else {
+                return Err(ParserError::InvalidTokenInTemplate {
+                    sp: self.lexer.span(),
+                    tok,
+                });
+            };
+
+            let (tok, pack, name, has_default) = self.parse_tail();
+            let (tok, default) = if has_default {
+                let qp = QualifiedParser::new(self.lexer);
+                qp.parse(None, None, context)?
+            } else {
+                (tok, None)
+            };
+
+            return Ok((
+                tok,
+                Some(TemplateParameter::Template(TemplateTemplateParameter {
+                    params,
+                    key,
+                    name,
+                    pack,
+                    default,
+                })),
+            ));
+        }
+
+        if let Some(key) = TypeParameterKey::from_tok(&tok) {
+            let (tok, pack, name, has_default) = self.parse_tail();
+            let (tok, default) = if has_default {
+                let tdp = TypeDeclaratorParser::new(self.lexer);
+                let (tok, typ) = tdp.parse(None, None, false, context)?;
+                (tok, typ.map(|t| t.typ.clone()))
+            } else {
+                (tok, None)
+            };
+
+            return Ok((
+                tok,
+                Some(TemplateParameter::Type(TypeParameter {
+                    key,
+                    name,
+                    pack,
+                    default,
+                })),
+            ));
+        }
+
+        // non-type parameter
+        let tdp = TypeDeclaratorParser::new(self.lexer);
+        let (tok, decl) = tdp.parse(Some(tok), None, false, context)?;
+        let mut decl = if let Some(decl) = decl {
+            decl
+        } else {
+            return Ok((tok, None));
+        };
+
+        // int... Ns or char... (e.g. for a templated literal operator)
+        let mut tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        let pack = if tok == Token::Ellipsis {
+            tok = self.lexer.next_useful();
+            if let Token::Identifier(id) = tok {
+                if decl.identifier.identifier.is_none() {
+                    Rc::make_mut(&mut decl).identifier.identifier = Some(Qualified {
+                        names: vec![Name::Identifier(Identifier { val: id })],
+                    });
+                    tok = self.lexer.next_useful();
+                } else {
+                    return Err(ParserError::InvalidTokenInTemplate {
+                        sp: self.lexer.span(),
+                        tok: Token::Identifier(id),
+                    });
+                }
+            }
+            true
+        } else {
+            false
+        };
+
+        if tok == Token::Equal {
+            let mut ep = ExpressionParser::new(self.lexer, Token::Greater);
+            let (tok, expr) = ep.parse(None, context)?;
+            Rc::make_mut(&mut decl).init = expr.map(Initializer::Equal);
+
+            Ok((
+                tok,
+                Some(TemplateParameter::NonType(NonTypeParameter { decl, pack })),
+            ))
+        } else {
+            Ok((
+                Some(tok),
+                Some(TemplateParameter::NonType(NonTypeParameter { decl, pack })),
+            ))
+        }
+    }
+}
+
+pub(crate) struct TemplateParametersParser<'a, L: TLexer> {
+    lexer: &'a mut L,
+}
+
+impl<'a, L: TLexer> TemplateParametersParser<'a, L> {
+    pub(crate) fn new(lexer: &'a mut L) -> Self {
+        Self { lexer }
+    }
+
+    /// Parse the parameters after the '<' up to the closing '>'
+    pub(crate) fn parse(self, context: &mut Context) -> Result<TemplateParameters, ParserError> {
+        let mut params = Vec::new();
+        let mut tok = self.lexer.next_useful();
+
+        if tok == Token::Greater {
+            return Ok(params);
+        }
+
+        loop {
+            let tpp = TemplateParameterParser::new(self.lexer);
+            let (tk, param) = tpp.parse(tok, context)?;
+
+            if let Some(param) = param {
+                params.push(param);
+            } else {
+                let tok = tk.unwrap_or_else(|| self.lexer.next_useful());
+                return Err(ParserError::InvalidTokenInTemplate {
+                    sp: self.lexer.span(),
+                    tok,
+                });
+            }
+
+            let tk = tk.unwrap_or_else(|| self.lexer.next_useful());
+            match tk {
+                Token::Comma => {}
+                Token::Greater => {
+                    return Ok(params);
+                }
+                _ => {
+                    return Err(ParserError::InvalidTokenInTemplate {
+                        sp: self.lexer.span(),
+                        tok: tk,
+                    });
+                }
+            }
+            tok = self.lexer.next_useful();
+        }
+    }
+}
+
+pub(crate) struct TemplateParser<'a, L: TLexer> {
+    lexer: &'a mut L,
+}
+
+impl<'a, L: TLexer> TemplateParser<'a, L> {
+    pub(crate) fn new(lexer: &'a mut L) -> Self {
+        Self { lexer }
+    }
+
+    pub(crate) fn parse(
+        mut self,
+        tok: Option<Token>,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<TemplateDecl>), ParserError> {
+        let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        if tok != Token::Template {
+            return Ok((Some(tok), None));
+        }
+
+        let tok = self.lexer.next_useful();
+        if tok != Token::Lower {
+            return Err(ParserError::InvalidTokenInTemplate {
+                sp: self.lexer.span(),
+                tok,
+            });
+        }
+
+        let tpp = TemplateParametersParser::new(self.lexer);
+        let params = tpp.parse(context)?;
+
+        // the parameters are visible in the requires-clause and in the templated declaration
+        let saved = context.add_template_params(&params);
+        let res = self.parse_requires(context).and_then(|(tok, requires)| {
+            let dp = DeclarationParser::new(self.lexer);
+            let (tok, decl) = dp.parse(tok, None, context)?;
+            Ok((tok, requires, decl))
+        });
+        context.remove_template_params(saved);
+
+        let (tok, requires, decl) = res?;
+        let decl = if let Some(decl) = decl {
+            decl
+        } else {
+            let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+            return Err(ParserError::InvalidTokenInTemplate {
+                sp: self.lexer.span(),
+                tok,
+            });
+        };
+
+        Ok((
+            tok,
+            Some(TemplateDecl {
+                params,
+                requires,
+                decl: Box::new(decl),
+            }),
+        ))
+    }
+
+    fn parse_requires(
+        &mut self,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<ExprNode>), ParserError> {
+        let tok = self.lexer.next_useful();
+        if tok == Token::Requires {
+            let cp = ConstraintParser::new(self.lexer);
+            cp.parse(None, context)
+        } else {
+            Ok((Some(tok), None))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lexer::{preprocessor::context::DefaultContext, Lexer};
+    use crate::parser::expressions::Operator;
+    use crate::parser::names::operator;
+    use crate::parser::types::{BaseType, Primitive};
+    use pretty_assertions::assert_eq;
+
+    fn parse_params(s: &[u8]) -> TemplateParameters {
+        let mut l = Lexer::<DefaultContext>::new(s);
+        let p = TemplateParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, tpl) = p.parse(None, &mut context).unwrap();
+
+        tpl.unwrap().params
+    }
+
+    fn template_key(param: &TemplateParameter) -> TypeParameterKey {
+        match param {
+            TemplateParameter::Template(t) => t.key,
+            _ => panic!("Not a template template parameter: {:?}", param),
+        }
+    }
+
+    #[test]
+    fn test_template_template_typename() {
+        let params = parse_params(
+            b"template<template<typename> typename T, typename U = int, int N = 3> struct A { U x[N]; };",
+        );
+
+        assert_eq!(params.len(), 3);
+        assert_eq!(
+            params[0],
+            TemplateParameter::Template(TemplateTemplateParameter {
+                params: vec![TemplateParameter::Type(TypeParameter {
+                    key: TypeParameterKey::Typename,
+                    name: None,
+                    pack: false,
+                    default: None,
+                })],
+                key: TypeParameterKey::Typename,
+                name: Some("T".to_string()),
+                pack: false,
+                default: None,
+            })
+        );
+        assert_eq!(
+            params[1],
+            TemplateParameter::Type(TypeParameter {
+                key: TypeParameterKey::Typename,
+                name: Some("U".to_string()),
+                pack: false,
+                default: Some(Type {
+                    base: BaseType::Primitive(Primitive::Int),
+                    cv: crate::parser::types::CVQualifier::empty(),
+                    pointers: None,
+                }),
+            })
+        );
+        assert!(matches!(&params[2], TemplateParameter::NonType(d) if d.decl.init.is_some()));
+    }
+
+    #[test]
+    fn test_template_template_class() {
+        let params = parse_params(b"template<template<class...> class... Ts> void f();");
+        assert_eq!(template_key(&params[0]), TypeParameterKey::Class);
+
+        let params = parse_params(b"template<template<class...> typename... Ts> void f();");
+        assert_eq!(template_key(&params[0]), TypeParameterKey::Typename);
+        if let TemplateParameter::Template(t) = &params[0] {
+            assert!(t.pack);
+            assert_eq!(t.name, Some("Ts".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_template_alias() {
+        let mut l = Lexer::<DefaultContext>::new(b"template<typename T> using P = T*;");
+        let p = DeclarationParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, &mut context).unwrap();
+
+        let alias = if let Some(Declaration::UsingAlias(alias)) = decl {
+            alias
+        } else {
+            panic!("Not an alias: {:?}", decl);
+        };
+        assert_eq!(alias.name, "P");
+        assert_eq!(alias.template.unwrap().len(), 1);
+        assert!(alias.typ.typ.pointers.is_some());
+    }
+
+    #[test]
+    fn test_template_requires_clause() {
+        let mut l = Lexer::<DefaultContext>::new(
+            b"template<typename T> requires Integral<T> || (sizeof(T) == 4) void f(T x);",
+        );
+        let p = DeclarationParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, &mut context).unwrap();
+
+        let tpl = if let Some(Declaration::Template(tpl)) = decl {
+            tpl
+        } else {
+            panic!("Not a template: {:?}", decl);
+        };
+        let requires = if let Some(ExprNode::BinaryOp(op)) = tpl.requires {
+            op
+        } else {
+            panic!("Invalid requires-clause: {:?}", tpl.requires);
+        };
+        assert_eq!(requires.op, Operator::Or);
+        if let ExprNode::TemplateId(id) = &requires.arg1 {
+            assert_eq!(id.name, mk_id!("Integral"));
+            assert!(matches!(id.args[0], ExprNode::Type(_)));
+        } else {
+            panic!("Not a concept-id: {:?}", requires.arg1);
+        }
+        assert!(matches!(*tpl.decl, Declaration::Type(_)));
+    }
+
+    #[test]
+    fn test_template_trailing_requires_clause() {
+        let mut l = Lexer::<DefaultContext>::new(
+            b"template<typename T> void f(T x) requires C<T> && requires (T y) { y + 1; } {}",
+        );
+        let p = DeclarationParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, &mut context).unwrap();
+
+        let tpl = if let Some(Declaration::Template(tpl)) = decl {
+            tpl
+        } else {
+            panic!("Not a template: {:?}", decl);
+        };
+        assert!(tpl.requires.is_none());
+
+        let fun = if let Declaration::Type(t) = &*tpl.decl {
+            if let BaseType::Function(fun) = &t.typ.base {
+                fun.clone()
+            } else {
+                panic!("Not a function: {:?}", t);
+            }
+        } else {
+            panic!("Not a declaration: {:?}", tpl.decl);
+        };
+        assert!(fun.body.borrow().is_some());
+
+        let requires = if let Some(ExprNode::BinaryOp(op)) = &fun.requires {
+            op
+        } else {
+            panic!("Invalid requires-clause: {:?}", fun.requires);
+        };
+        assert_eq!(requires.op, Operator::And);
+        if let ExprNode::RequiresExpr(req) = &requires.arg2 {
+            assert_eq!(req.params.as_ref().unwrap().len(), 1);
+            assert_eq!(req.requirements.len(), 1);
+        } else {
+            panic!("Not a requires-expression: {:?}", requires.arg2);
+        }
+    }
+
+    #[test]
+    fn test_template_udl_operator() {
+        let mut l = Lexer::<DefaultContext>::new(b"template<char...> auto operator\"\"_x();");
+        let p = DeclarationParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, &mut context).unwrap();
+
+        let tpl = if let Some(Declaration::Template(tpl)) = decl {
+            tpl
+        } else {
+            panic!("Not a template: {:?}", decl);
+        };
+        assert!(matches!(
+            &tpl.params[0],
+            TemplateParameter::NonType(d) if d.pack && d.decl.typ.base == BaseType::Primitive(Primitive::Char)
+        ));
+
+        let decl = if let Declaration::Type(t) = &*tpl.decl {
+            t.clone()
+        } else {
+            panic!("Not a declaration: {:?}", tpl.decl);
+        };
+        assert!(matches!(decl.typ.base, BaseType::Function(_)));
+        assert_eq!(
+            decl.identifier.identifier.as_ref().unwrap().names,
+            vec![Name::Operator(Box::new(operator::Operator::UD(
+                "_x".to_string()
+            )))]
+        );
+
+        let params = parse_params(b"template<int... Ns> void g();");
+        assert_eq!(params[0].get_name(), Some("Ns"));
+        assert!(matches!(&params[0], TemplateParameter::NonType(d) if d.pack));
+    }
+}
@@ -7,11 +7,14 @@ use std::rc::Rc;
 use termcolor::StandardStreamLock;
 
 use super::array::ArrayParser;
+use super::binding::{BindingNames, StructuredBinding, StructuredBindingParser};
+use super::bitfield::BitFieldDeclaratorParser;
 use super::class::ClassParser;
 use super::function::{ConvOperatorDeclaratorParser, FunctionParser};
 use super::pointer::{ParenPointerDeclaratorParser, PointerDeclaratorParser};
 use super::r#enum::EnumParser;
 use super::specifier::Specifier;
+use crate::errors::Span;
 use crate::lexer::extra::SavedLexer;
 use crate::lexer::{TLexer, Token};
 use crate::parser::attributes::{Attributes, AttributesParser};
@@ -35,14 +38,44 @@ impl Dump for Identifier {
     }
 }
 
-// TODO: handle structured bindings: https://en.cppreference.com/w/cpp/language/structured_binding
+#[derive(Clone, Debug, PartialEq)]
+pub enum Alignas {
+    Expr(ExprNode),
+    Type(Type),
+}
+
+impl Dump for Alignas {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        let prefix = dump_start!(name, "alignas", prefix, last, stdout);
+        match self {
+            Self::Expr(x) => {
+                x.dump("expr", &prefix, true, stdout);
+            }
+            Self::Type(x) => {
+                x.dump("type", &prefix, true, stdout);
+            }
+        }
+    }
+}
+
+pub type Alignments = Vec<Alignas>;
+
+impl Dump for Alignments {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_vec!(name, self, "align", prefix, last, stdout);
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct TypeDeclarator {
     pub typ: Type,
     pub specifier: Specifier,
     pub identifier: Identifier,
     pub init: Option<Initializer>,
+    pub align: Option<Alignments>,
     pub bitfield_size: Option<ExprNode>,
+    /// explicit or explicit(expr): the condition isn't evaluated
+    pub explicit_spec: Option<Option<ExprNode>>,
 }
 
 impl Dump for TypeDeclarator {
@@ -58,11 +91,29 @@ impl Dump for TypeDeclarator {
             specifier,
             identifier,
             init,
-            bitfield_size
+            align,
+            bitfield_size,
+            explicit_spec
         );
     }
 }
 
+/// The declarators of a simple declaration with an init-declarator-list
+pub type TypeDeclarators = Vec<Rc<TypeDeclarator>>;
+
+impl Dump for TypeDeclarators {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_vec!(name, self, "decl", prefix, last, stdout);
+    }
+}
+
+/// What a simple declaration declares: some entities or some structured bindings
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Declarators {
+    Types(TypeDeclarators),
+    Binding(StructuredBinding),
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) struct TypeDeclNames<'a> {
     pub(crate) var: Option<&'a Qualified>,
@@ -87,6 +138,32 @@ impl TypeDeclarator {
         }
     }
 
+    /// The leading attributes appertain to the declared entity
+    pub(crate) fn add_leading_attributes(&mut self, attrs: &Attributes) {
+        let mut attrs = attrs.clone();
+        if let Some(old) = self.identifier.attributes.take() {
+            attrs.extend(old);
+        }
+        self.identifier.attributes = Some(attrs);
+    }
+
+    /// Get the effective alignment when all the alignas have an integer literal as argument:
+    /// the strictest one applies and alignas(0) is ignored
+    pub fn get_alignment(&self) -> Option<u64> {
+        let mut max = None;
+        for al in self.align.as_ref()? {
+            if let Alignas::Expr(ExprNode::Integer(i)) = al {
+                let n: u64 = (&i.value).into();
+                if n != 0 {
+                    max = Some(max.map_or(n, |m: u64| m.max(n)));
+                }
+            } else {
+                return None;
+            }
+        }
+        max
+    }
+
     pub(crate) fn is_type_part(tok: &Token) -> bool {
         *tok == Token::Class || *tok == Token::Enum || *tok == Token::Struct
     }
@@ -139,6 +216,17 @@ pub(crate) enum DeclHint {
     Type(BaseType),
 }
 
+/// The result of a decl-specifier-seq
+pub(crate) struct DeclSpecifiers {
+    pub(crate) spec: Specifier,
+    pub(crate) typ: Option<Type>,
+    /// The name of a conversion operator or of a destructor
+    pub(crate) op: Option<Qualified>,
+    pub(crate) to_fix: Option<TypeToFix>,
+    pub(crate) align: Option<Alignments>,
+    pub(crate) explicit: Option<Option<ExprNode>>,
+}
+
 pub struct DeclSpecifierParser<'a, L: TLexer> {
     lexer: &'a mut L,
 }
@@ -153,18 +241,7 @@ impl<'a, L: TLexer> DeclSpecifierParser<'a, L> {
         tok: Option<Token>,
         hint: Option<DeclHint>,
         context: &mut Context,
-    ) -> Result<
-        (
-            Option<Token>,
-            (
-                Specifier,
-                Option<Type>,
-                Option<Qualified>,
-                Option<TypeToFix>,
-            ),
-        ),
-        ParserError,
-    > {
+    ) -> Result<(Option<Token>, DeclSpecifiers), ParserError> {
         let (mut typ, mut spec, mut ty_modif) = if let Some(hint) = hint {
             match hint {
                 DeclHint::Name(id) => (
@@ -187,9 +264,44 @@ impl<'a, L: TLexer> DeclSpecifierParser<'a, L> {
 
         let mut cv = CVQualifier::empty();
         let mut to_fix = None;
+        let mut align: Option<Alignments> = None;
+        let mut explicit = None;
 
         let mut tok = tok.unwrap_or_else(|| self.lexer.next_useful());
         loop {
+            // alignas: all of them are kept (see TypeDeclarator::get_alignment)
+            if tok == Token::Alignas || tok == Token::CAlignas {
+                let ap = AlignasParser::new(self.lexer);
+                let (tk, al) = ap.parse(Some(tok), context)?;
+                align.get_or_insert_with(Vec::new).push(al.unwrap());
+                tok = tk.unwrap_or_else(|| self.lexer.next_useful());
+                continue;
+            }
+
+            // explicit or explicit ( constant-expression ):
+            // the condition isn't evaluated, so explicit(false) is still marked as explicit
+            if tok == Token::Explicit {
+                tok = self.lexer.next_useful();
+                if tok == Token::LeftParen {
+                    let mut ep = ExpressionParser::new(self.lexer, Token::RightParen);
+                    let (tk, cond) = ep.parse(None, context)?;
+                    let tk = tk.unwrap_or_else(|| self.lexer.next_useful());
+                    if tk != Token::RightParen || cond.is_none() {
+                        return Err(ParserError::InvalidTokenInFuncDecl {
+                            sp: self.lexer.span(),
+                            tok: tk,
+                        });
+                    }
+                    spec |= Specifier::EXPLICIT;
+                    explicit = Some(cond);
+                    tok = self.lexer.next_useful();
+                } else {
+                    spec |= Specifier::EXPLICIT;
+                    explicit = Some(None);
+                }
+                continue;
+            }
+
             // const, volatile
             if cv.from_tok(&tok) {
                 tok = self.lexer.next_useful();
@@ -231,14 +343,41 @@ impl<'a, L: TLexer> DeclSpecifierParser<'a, L> {
                     continue;
                 }
 
-                // identifier
+                // destructor: ~A() (only in a class, elsewhere ~x is an expression)
                 let tk = tk.unwrap_or_else(|| self.lexer.next_useful());
+                if tk == Token::Tilde && context.in_class() {
+                    let qp = QualifiedParser::new(self.lexer);
+                    let (tk, name) = qp.parse(Some(tk), None, context)?;
+                    return Ok((
+                        tk,
+                        DeclSpecifiers {
+                            spec,
+                            typ: None,
+                            op: name,
+                            to_fix,
+                            align,
+                            explicit,
+                        },
+                    ));
+                }
+
+                // identifier
                 if let Token::Identifier(id) = tk {
                     let qp = QualifiedParser::new(self.lexer);
                     let (tk, name) = qp.parse(None, Some(id), context)?;
                     let name = name.unwrap();
-                    if name.is_conv_op() {
-                        return Ok((tk, (spec, None, Some(name), to_fix)));
+                    if name.is_conv_op() || name.is_dtor() {
+                        return Ok((
+                            tk,
+                            DeclSpecifiers {
+                                spec,
+                                typ: None,
+                                op: Some(name),
+                                to_fix,
+                                align,
+                                explicit,
+                            },
+                        ));
                     }
 
                     let ud_typ = if let Some(res) = context.search(Some(&name)) {
@@ -261,44 +400,163 @@ impl<'a, L: TLexer> DeclSpecifierParser<'a, L> {
                     continue;
                 }
 
-                if tk == Token::Auto {
+                if tk == Token::Auto || tk == Token::GnuAutoType {
                     typ = Some(BaseType::Auto);
                     tok = self.lexer.next_useful();
                     continue;
                 }
 
+                if tk == Token::Decltype {
+                    let dp = DecltypeParser::new(self.lexer);
+                    let (tk, dt) = dp.parse(Some(tk), context)?;
+                    typ = dt;
+                    tok = tk.unwrap_or_else(|| self.lexer.next_useful());
+                    continue;
+                }
+
                 tok = tk;
             }
 
-            let spec_ty = if let Some(base) = typ {
-                (
+            let typ = if let Some(base) = typ {
+                Some(Type {
+                    base,
+                    cv,
+                    pointers: None,
+                })
+            } else if ty_modif.is_empty() {
+                None
+            } else {
+                Some(Type {
+                    base: BaseType::Primitive(ty_modif.to_primitive()),
+                    cv,
+                    pointers: None,
+                })
+            };
+
+            return Ok((
+                Some(tok),
+                DeclSpecifiers {
                     spec,
-                    Some(Type {
-                        base,
-                        cv,
-                        pointers: None,
-                    }),
-                    None,
+                    typ,
+                    op: None,
                     to_fix,
-                )
-            } else if cv.is_empty() && ty_modif.is_empty() {
-                (spec, None, None, to_fix)
+                    align,
+                    explicit,
+                },
+            ));
+        }
+    }
+}
+
+pub struct DecltypeParser<'a, L: TLexer> {
+    lexer: &'a mut L,
+}
+
+impl<'a, L: TLexer> DecltypeParser<'a, L> {
+    pub(crate) fn new(lexer: &'a mut L) -> Self {
+        Self { lexer }
+    }
+
+    pub(crate) fn parse(
+        self,
+        tok: Option<Token>,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<BaseType>), ParserError> {
+        // decltype ( expression )
+        // decltype ( auto )
+        let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        if tok != Token::Decltype {
+            return Ok((Some(tok), None));
+        }
+
+        let tok = self.lexer.next_useful();
+        if tok != Token::LeftParen {
+            return Err(ParserError::InvalidTokenInDecltype {
+                sp: self.lexer.span(),
+                tok,
+            });
+        }
+
+        let tok = self.lexer.next_useful();
+        let (tok, typ) = if tok == Token::Auto {
+            (self.lexer.next_useful(), BaseType::DecltypeAuto)
+        } else {
+            let mut ep = ExpressionParser::new(self.lexer, Token::RightParen);
+            let (tok, expr) = ep.parse(Some(tok), context)?;
+            let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+
+            if let Some(expr) = expr {
+                (tok, BaseType::Decltype(expr))
             } else {
-                let typ = if ty_modif.is_empty() {
-                    None
-                } else {
-                    Some(Type {
-                        base: BaseType::Primitive(ty_modif.to_primitive()),
-                        cv,
-                        pointers: None,
-                    })
-                };
+                return Err(ParserError::InvalidTokenInDecltype {
+                    sp: self.lexer.span(),
+                    tok,
+                });
+            }
+        };
 
-                (spec, typ, None, to_fix)
-            };
+        if tok != Token::RightParen {
+            return Err(ParserError::InvalidTokenInDecltype {
+                sp: self.lexer.span(),
+                tok,
+            });
+        }
+
+        Ok((None, Some(typ)))
+    }
+}
+
+pub struct AlignasParser<'a, L: TLexer> {
+    lexer: &'a mut L,
+}
+
+impl<'a, L: TLexer> AlignasParser<'a, L> {
+    pub(crate) fn new(lexer: &'a mut L) -> Self {
+        Self { lexer }
+    }
+
+    pub(crate) fn parse(
+        self,
+        tok: Option<Token>,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<Alignas>), ParserError> {
+        // alignas ( expression )
+        // alignas ( type-id )
+        let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        if tok != Token::Alignas && tok != Token::CAlignas {
+            return Ok((Some(tok), None));
+        }
+
+        let tok = self.lexer.next_useful();
+        if tok != Token::LeftParen {
+            return Err(ParserError::InvalidTokenInAlignas {
+                sp: self.lexer.span(),
+                tok,
+            });
+        }
 
-            return Ok((Some(tok), spec_ty));
+        let doep = DeclOrExprParser::new(self.lexer);
+        let (tok, doe) = doep.parse(None, context)?;
+        let align = match doe {
+            Some(DeclOrExpr::Decl(d)) => Alignas::Type(Rc::try_unwrap(d).unwrap().typ),
+            Some(DeclOrExpr::Expr(e)) => Alignas::Expr(e),
+            Some(DeclOrExpr::Decls(_)) | Some(DeclOrExpr::Binding(_)) | None => {
+                return Err(ParserError::InvalidTokenInAlignas {
+                    sp: self.lexer.span(),
+                    tok: tok.unwrap_or_else(|| self.lexer.next_useful()),
+                });
+            }
+        };
+
+        let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        if tok != Token::RightParen {
+            return Err(ParserError::InvalidTokenInAlignas {
+                sp: self.lexer.span(),
+                tok,
+            });
         }
+
+        Ok((None, Some(align)))
     }
 }
 
@@ -373,7 +631,9 @@ impl<'a, L: TLexer> NoPtrDeclaratorParser<'a, L> {
                     specifier,
                     identifier,
                     init: None,
+                    align: None,
                     bitfield_size: None,
+                    explicit_spec: None,
                 }),
                 to_fix,
                 saved,
@@ -410,7 +670,9 @@ impl<'a, L: TLexer> NoPtrDeclaratorParser<'a, L> {
                 specifier,
                 identifier,
                 init,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             }),
             None,
             None,
@@ -418,154 +680,429 @@ impl<'a, L: TLexer> NoPtrDeclaratorParser<'a, L> {
     }
 }
 
-pub struct TypeDeclaratorParser<'a, L: TLexer> {
+pub(crate) fn check_array_qualifiers(typ: &Type, sp: Span) -> Result<(), ParserError> {
+    match &typ.base {
+        BaseType::Array(array) if array.has_qualifiers() => {
+            Err(ParserError::InvalidArrayQualifiers { sp })
+        }
+        _ => Ok(()),
+    }
+}
+
+// Replace the placeholder type (BaseType::None) used when parsing
+// a parenthesized declarator by the type built around it:
+// int (*f[2]) [3] == A * f[2] with A = int[3]
+fn fill_hole(typ: &mut Type, filler: Type) {
+    match &mut typ.base {
+        BaseType::None => {
+            let Type { base, cv, pointers } = filler;
+            typ.base = base;
+            typ.cv = cv;
+            typ.pointers = match (pointers, typ.pointers.take()) {
+                (Some(mut ptrs), Some(others)) => {
+                    ptrs.extend(others);
+                    Some(ptrs)
+                }
+                (ptrs, None) => ptrs,
+                (None, others) => others,
+            };
+        }
+        BaseType::Array(array) => {
+            fill_hole(array.base.as_mut().unwrap(), filler);
+        }
+        BaseType::Function(fun) => {
+            fill_hole(fun.return_type.as_mut().unwrap(), filler);
+        }
+        _ => {
+            unreachable!();
+        }
+    }
+}
+
+pub struct DeclaratorParser<'a, L: TLexer> {
     lexer: &'a mut L,
 }
 
-impl<'a, L: TLexer> TypeDeclaratorParser<'a, L> {
+impl<'a, L: TLexer> DeclaratorParser<'a, L> {
     pub(crate) fn new(lexer: &'a mut L) -> Self {
         Self { lexer }
     }
 
+    #[allow(clippy::type_complexity)]
     pub(crate) fn parse(
         self,
         tok: Option<Token>,
-        hint: Option<DeclHint>,
+        typ: Type,
+        specifier: Specifier,
         init: bool,
+        param: bool,
         context: &mut Context,
-    ) -> Result<(Option<Token>, Option<Rc<TypeDeclarator>>), ParserError> {
-        let dsp = DeclSpecifierParser::new(self.lexer);
-        let (tok, (spec, typ, op, to_fix)) = dsp.parse(tok, hint, context)?;
+    ) -> Result<
+        (
+            Option<Token>,
+            Option<TypeDeclarator>,
+            Option<TypeToFix>,
+            Option<SavedLexer>,
+            Option<BindingNames>,
+        ),
+        ParserError,
+    > {
+        // declarator:
+        //   ptr-operator* noptr-declarator
+        // noptr-declarator:
+        //   declarator-id attributes? (params|array)*
+        //   ( declarator ) (params|array)*
+        let mut typ = typ;
 
-        let typ = if let Some(typ) = typ {
-            typ
+        // Pointer: *, &, &&
+        let pdp = PointerDeclaratorParser::new(self.lexer);
+        let (tok, ptrs) = pdp.parse(tok, None, context)?;
+        typ.pointers = ptrs;
+
+        // structured binding: auto& [a, b] = ...
+        let (tok, binding) = if typ.base == BaseType::Auto {
+            let sbp = StructuredBindingParser::new(self.lexer);
+            sbp.parse(tok)?
         } else {
-            // conversion operator
-            let codp = ConvOperatorDeclaratorParser::new(self.lexer);
-            let (tok, conv, to_fix, saved) = codp.parse(spec, op, tok, context)?;
-            let conv = if let Some(conv) = conv {
-                let conv = Rc::new(conv);
-                if let Some(to_fix) = to_fix {
-                    to_fix.fix(Rc::clone(&conv));
-                }
-                if let Some(saved) = saved {
-                    context.add_method(Rc::clone(&conv), saved);
-                }
-                Some(conv)
+            (tok, None)
+        };
+
+        if let Some(names) = binding {
+            // the attributes after the identifier-list appertain to the binding
+            let ap = AttributesParser::new(self.lexer);
+            let (tok, attributes) = ap.parse(tok, context)?;
+
+            let (tok, init) = if init {
+                let ip = InitializerParser::new(self.lexer);
+                ip.parse(tok, context)?
             } else {
-                None
+                (tok, None)
             };
 
-            return Ok((tok, conv));
-        };
-
-        let mut typ = typ;
+            return Ok((
+                tok,
+                Some(TypeDeclarator {
+                    typ,
+                    specifier,
+                    identifier: Identifier {
+                        identifier: None,
+                        attributes,
+                    },
+                    init,
+                    align: None,
+                    bitfield_size: None,
+                    explicit_spec: None,
+                }),
+                None,
+                None,
+                Some(names),
+            ));
+        }
 
         let ppdp = ParenPointerDeclaratorParser::new(self.lexer);
         let (tok, (paren_decl, is_func_param)) = ppdp.parse(tok, context)?;
 
-        let tok = if !is_func_param {
-            // Pointer: *, &, &&
-            let pdp = PointerDeclaratorParser::new(self.lexer);
-            let (tok, ptrs) = pdp.parse(tok, None, context)?;
-
-            typ.pointers = ptrs;
-            tok
-        } else {
-            tok
-        };
-
         // int (*f[1]) (int, int) == A *f[1] avec A = int ()(int, int)
         // int (*f[2]) [3] == A * f[2] avec A = int[3]
         // int (*f) (int) == A * f avec A = int () (int)
 
         let npdp = NoPtrDeclaratorParser::new(self.lexer);
-        let (tok, decl, tf, saved) = npdp.parse(tok, typ, spec, is_func_param, init, context)?;
+        let (tok, decl, to_fix, saved) =
+            npdp.parse(tok, typ, specifier, is_func_param, init, context)?;
         let mut decl = decl.unwrap();
-        let to_fix = if to_fix.is_none() { tf } else { to_fix };
+
+        // the qualifiers are only allowed in the outermost array of a parameter:
+        // void f(int a[static 10]) but not void f(int (*a)[static 10])
+        if !param || paren_decl.is_some() {
+            check_array_qualifiers(&decl.typ, self.lexer.span())?;
+        }
 
         if let Some(paren_decl) = paren_decl {
             let TypeDeclarator {
-                typ,
-                specifier: _,
+                mut typ,
                 identifier,
-                init: _,
-                bitfield_size: _,
+                ..
             } = paren_decl;
-            let Type {
-                base,
-                cv: _,
-                pointers,
-            } = typ;
 
+            fill_hole(&mut typ, decl.typ);
+            decl.typ = typ;
             decl.identifier = identifier;
-
-            match base {
-                BaseType::None => {
-                    decl.typ.pointers = pointers;
-                }
-                BaseType::Array(mut array) => {
-                    // int (*f[2]) [3] == A * f[2] with A = int[3]
-                    // base == BaseType::Array(Array { base: Some(Type { base: None, cv, *}), dim: 2 })
-                    // decl.typ.base == BaseType::Array(Array { base: Some(Type { int, cv, }), dim = 3})
-                    if let Some(base) = array.base.as_mut() {
-                        std::mem::swap(&mut base.base, &mut decl.typ.base);
-                    }
-                    decl.typ.base = BaseType::Array(array);
-                }
-                _ => {
-                    unreachable!();
-                }
-            }
-        }
-
-        let decl = Rc::new(decl);
-        if let Some(to_fix) = to_fix {
-            to_fix.fix(Rc::clone(&decl));
-        }
-
-        if let Some(saved) = saved {
-            context.add_method(Rc::clone(&decl), saved);
         }
 
-        Ok((tok, Some(decl)))
-    }
-
-    pub(crate) fn is_decl_part(tok: &Token) -> bool {
-        match tok {
-            Token::Star | Token::And | Token::AndAnd => true,
-            _ => CVQualifier::is_cv(tok) || Specifier::is_specifier(tok),
-        }
+        Ok((tok, Some(decl), to_fix, saved, None))
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
-pub enum DeclOrExpr {
-    Decl(Rc<TypeDeclarator>),
-    Expr(ExprNode),
+pub struct TypeDeclaratorParser<'a, L: TLexer> {
+    lexer: &'a mut L,
+    attributes: Option<Attributes>,
+    param: bool,
+    member: bool,
 }
 
-impl Dump for DeclOrExpr {
-    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
-        let prefix = dump_start!(name, "decl-or-expr", prefix, last, stdout);
-        match self {
-            Self::Decl(x) => {
-                x.dump("decl", &prefix, true, stdout);
-            }
-            Self::Expr(x) => {
-                x.dump("expr", &prefix, true, stdout);
-            }
+impl<'a, L: TLexer> TypeDeclaratorParser<'a, L> {
+    pub(crate) fn new(lexer: &'a mut L) -> Self {
+        Self {
+            lexer,
+            attributes: None,
+            param: false,
+            member: false,
         }
     }
-}
 
-pub(crate) struct DeclOrExprParser<'a, L: TLexer> {
-    lexer: &'a mut L,
-}
+    /// The leading attributes of the declaration are added to each declarator
+    /// before it's shared (e.g. with the context for a method body to parse later)
+    pub(crate) fn new_with_attributes(lexer: &'a mut L, attributes: Option<Attributes>) -> Self {
+        Self {
+            lexer,
+            attributes,
+            param: false,
+            member: false,
+        }
+    }
 
-impl<'a, L: TLexer> DeclOrExprParser<'a, L> {
-    pub(crate) fn new(lexer: &'a mut L) -> Self {
-        Self { lexer }
+    /// A parameter declaration: the array qualifiers are allowed (e.g. int a[static 10])
+    pub(crate) fn new_param(lexer: &'a mut L) -> Self {
+        Self {
+            lexer,
+            attributes: None,
+            param: true,
+            member: false,
+        }
+    }
+
+    /// A member declaration: the declarators can be bitfields (e.g. int a : 4, b : 2)
+    pub(crate) fn new_member(lexer: &'a mut L, attributes: Option<Attributes>) -> Self {
+        Self {
+            lexer,
+            attributes,
+            param: false,
+            member: true,
+        }
+    }
+
+    pub(crate) fn parse(
+        mut self,
+        tok: Option<Token>,
+        hint: Option<DeclHint>,
+        init: bool,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<Rc<TypeDeclarator>>), ParserError> {
+        let (tok, decls) = self.parse_declarators(tok, hint, init, false, context)?;
+        match decls {
+            Some(Declarators::Types(decls)) => Ok((tok, decls.into_iter().next())),
+            Some(Declarators::Binding(_)) => Err(ParserError::InvalidTokenInBinding {
+                sp: self.lexer.span(),
+                tok: Token::LeftBrack,
+            }),
+            None => Ok((tok, None)),
+        }
+    }
+
+    /// Parse a declaration which can be a structured binding one
+    pub(crate) fn parse_decl(
+        mut self,
+        tok: Option<Token>,
+        hint: Option<DeclHint>,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<Declarators>), ParserError> {
+        self.parse_declarators(tok, hint, true, false, context)
+    }
+
+    /// Parse a declaration with an init-declarator-list (e.g. int x = 1, *y, z[2];):
+    /// the decl-specifiers are shared by all the declarators
+    pub(crate) fn parse_list(
+        mut self,
+        tok: Option<Token>,
+        hint: Option<DeclHint>,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<Declarators>), ParserError> {
+        self.parse_declarators(tok, hint, true, true, context)
+    }
+
+    fn parse_declarators(
+        &mut self,
+        tok: Option<Token>,
+        hint: Option<DeclHint>,
+        init: bool,
+        list: bool,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<Declarators>), ParserError> {
+        let dsp = DeclSpecifierParser::new(self.lexer);
+        let (
+            tok,
+            DeclSpecifiers {
+                spec,
+                typ,
+                op,
+                to_fix,
+                align,
+                explicit,
+            },
+        ) = dsp.parse(tok, hint, context)?;
+
+        let typ = if let Some(typ) = typ {
+            typ
+        } else {
+            // conversion operator or destructor
+            let codp = ConvOperatorDeclaratorParser::new(self.lexer);
+            let (tok, conv, to_fix, saved) = codp.parse(spec, op, tok, context)?;
+            let conv = if let Some(mut conv) = conv {
+                conv.explicit_spec = explicit;
+                if let Some(attrs) = self.attributes.as_ref() {
+                    conv.add_leading_attributes(attrs);
+                }
+                let conv = Rc::new(conv);
+                if let Some(to_fix) = to_fix {
+                    to_fix.fix(Rc::clone(&conv));
+                }
+                if let Some(saved) = saved {
+                    context.add_method(Rc::clone(&conv), saved);
+                }
+                Some(conv)
+            } else {
+                None
+            };
+
+            return Ok((tok, conv.map(|conv| Declarators::Types(vec![conv]))));
+        };
+
+        let mut decls = Vec::new();
+        let mut tok = tok;
+        let mut to_fix = to_fix;
+
+        loop {
+            let dp = DeclaratorParser::new(self.lexer);
+            let (tk, decl, tf, saved, names) =
+                dp.parse(tok, typ.clone(), spec, init, self.param, context)?;
+            let mut decl = decl.unwrap();
+            decl.align = align.clone();
+            decl.explicit_spec = explicit.clone();
+            if let Some(attrs) = self.attributes.as_ref() {
+                decl.add_leading_attributes(attrs);
+            }
+            let to_fix = to_fix.take().or(tf);
+
+            let tk = if self.member {
+                let tk = tk.unwrap_or_else(|| self.lexer.next_useful());
+                if tk == Token::Colon {
+                    // we've a bitfield
+                    let bfdp = BitFieldDeclaratorParser::new(self.lexer);
+                    bfdp.parse(None, &mut decl, context)?
+                } else {
+                    Some(tk)
+                }
+            } else {
+                tk
+            };
+
+            let decl = Rc::new(decl);
+            if let Some(to_fix) = to_fix {
+                to_fix.fix(Rc::clone(&decl));
+            }
+
+            if let Some(saved) = saved {
+                context.add_method(Rc::clone(&decl), saved);
+            }
+
+            if let Some(names) = names {
+                let binding = StructuredBinding { names, decl };
+                return Ok((tk, Some(Declarators::Binding(binding))));
+            }
+
+            if !list {
+                decls.push(decl);
+                return Ok((tk, Some(Declarators::Types(decls))));
+            }
+
+            let tk = tk.unwrap_or_else(|| self.lexer.next_useful());
+            let more = tk == Token::Comma && decl.has_semicolon();
+            decls.push(decl);
+
+            if !more {
+                return Ok((Some(tk), Some(Declarators::Types(decls))));
+            }
+            tok = None;
+        }
+    }
+
+    pub(crate) fn is_decl_part(tok: &Token) -> bool {
+        match tok {
+            Token::Star | Token::And | Token::AndAnd => true,
+            _ => CVQualifier::is_cv(tok) || Specifier::is_specifier(tok),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DeclOrExpr {
+    Decl(Rc<TypeDeclarator>),
+    /// A declaration with several declarators: int a, b = 2;
+    Decls(TypeDeclarators),
+    Binding(Box<StructuredBinding>),
+    Expr(ExprNode),
+}
+
+impl DeclOrExpr {
+    fn from_declarators(decls: Declarators) -> Self {
+        match decls {
+            Declarators::Types(mut decls) if decls.len() == 1 => Self::Decl(decls.remove(0)),
+            Declarators::Types(decls) => Self::Decls(decls),
+            Declarators::Binding(binding) => Self::Binding(Box::new(binding)),
+        }
+    }
+}
+
+impl Dump for DeclOrExpr {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        let prefix = dump_start!(name, "decl-or-expr", prefix, last, stdout);
+        match self {
+            Self::Decl(x) => {
+                x.dump("decl", &prefix, true, stdout);
+            }
+            Self::Decls(x) => {
+                x.dump("decls", &prefix, true, stdout);
+            }
+            Self::Binding(x) => {
+                x.dump("binding", &prefix, true, stdout);
+            }
+            Self::Expr(x) => {
+                x.dump("expr", &prefix, true, stdout);
+            }
+        }
+    }
+}
+
+/// Parse a declaration with a single declarator or with a list of declarators
+fn parse_decl_or_list<L: TLexer>(
+    lexer: &mut L,
+    list: bool,
+    tok: Option<Token>,
+    hint: Option<DeclHint>,
+    context: &mut Context,
+) -> Result<(Option<Token>, Option<DeclOrExpr>), ParserError> {
+    let tdp = TypeDeclaratorParser::new(lexer);
+    let (tok, decls) = if list {
+        tdp.parse_list(tok, hint, context)?
+    } else {
+        tdp.parse_decl(tok, hint, context)?
+    };
+    Ok((tok, decls.map(DeclOrExpr::from_declarators)))
+}
+
+pub(crate) struct DeclOrExprParser<'a, L: TLexer> {
+    lexer: &'a mut L,
+    list: bool,
+}
+
+impl<'a, L: TLexer> DeclOrExprParser<'a, L> {
+    pub(crate) fn new(lexer: &'a mut L) -> Self {
+        Self { lexer, list: false }
+    }
+
+    /// The declaration can have several declarators (e.g. in a block or in a for-init)
+    pub(crate) fn new_list(lexer: &'a mut L) -> Self {
+        Self { lexer, list: true }
     }
 
     pub(crate) fn parse(
@@ -576,50 +1113,7 @@ impl<'a, L: TLexer> DeclOrExprParser<'a, L> {
         let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
         let (typ, var, tok) = match tok {
             Token::Identifier(id) => {
-                let qp = QualifiedParser::new(self.lexer);
-                let (tok, name) = qp.parse(None, Some(id), context)?;
-
-                if let Some(res) = context.search(name.as_ref()) {
-                    match res {
-                        SearchResult::Var(var) => (
-                            None,
-                            Some(ExprNode::Variable(Box::new(Variable {
-                                name: name.unwrap(),
-                                decl: VarDecl::Direct(var),
-                            }))),
-                            tok,
-                        ),
-                        SearchResult::IncompleteVar(var) => (
-                            None,
-                            Some(ExprNode::Variable(Box::new(Variable {
-                                name: name.unwrap(),
-                                decl: VarDecl::Indirect(var),
-                            }))),
-                            tok,
-                        ),
-                        SearchResult::Type(typ) => (
-                            Some(BaseType::UD(Box::new(UserDefined {
-                                name: name.unwrap(),
-                                typ: UDType::Direct(typ),
-                            }))),
-                            None,
-                            tok,
-                        ),
-                        SearchResult::IncompleteType(typ) => (
-                            Some(BaseType::UD(Box::new(UserDefined {
-                                name: name.unwrap(),
-                                typ: UDType::Indirect(typ),
-                            }))),
-                            None,
-                            tok,
-                        ),
-                    }
-                } else {
-                    return Err(ParserError::UnknownId {
-                        sp: self.lexer.span(),
-                        name: name.unwrap().to_string(),
-                    });
-                }
+                return self.parse_id(None, id, context);
             }
             _ => {
                 if Modifier::is_primitive_part(&tok) {
@@ -634,11 +1128,13 @@ impl<'a, L: TLexer> DeclOrExprParser<'a, L> {
                             Some(tok),
                         )
                     } else {
-                        let tdp = TypeDeclaratorParser::new(self.lexer);
-                        let (tok, typ) =
-                            tdp.parse(Some(tok), Some(DeclHint::Modifier(modif)), true, context)?;
-
-                        return Ok((tok, Some(DeclOrExpr::Decl(typ.unwrap()))));
+                        return parse_decl_or_list(
+                            self.lexer,
+                            self.list,
+                            Some(tok),
+                            Some(DeclHint::Modifier(modif)),
+                            context,
+                        );
                     }
                 } else {
                     (None, None, Some(tok))
@@ -646,6 +1142,77 @@ impl<'a, L: TLexer> DeclOrExprParser<'a, L> {
             }
         };
 
+        self.parse_with(typ, var, tok, context)
+    }
+
+    /// Parse a declaration or an expression starting with the identifier id
+    /// (tok is the token following id when it has already been read)
+    pub(crate) fn parse_id(
+        self,
+        tok: Option<Token>,
+        id: String,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<DeclOrExpr>), ParserError> {
+        let qp = QualifiedParser::new(self.lexer);
+        let (tok, name) = qp.parse(tok, Some(id), context)?;
+
+        let (typ, var, tok) = if let Some(res) = context.search(name.as_ref()) {
+            match res {
+                SearchResult::Var(var) => (
+                    None,
+                    Some(ExprNode::Variable(Box::new(Variable {
+                        name: name.unwrap(),
+                        decl: VarDecl::Direct(var),
+                    }))),
+                    tok,
+                ),
+                SearchResult::IncompleteVar(var) => (
+                    None,
+                    Some(ExprNode::Variable(Box::new(Variable {
+                        name: name.unwrap(),
+                        decl: VarDecl::Indirect(var),
+                    }))),
+                    tok,
+                ),
+                SearchResult::Type(typ) => (
+                    Some(BaseType::UD(Box::new(UserDefined {
+                        name: name.unwrap(),
+                        typ: UDType::Direct(typ),
+                    }))),
+                    None,
+                    tok,
+                ),
+                SearchResult::IncompleteType(typ) => (
+                    Some(BaseType::UD(Box::new(UserDefined {
+                        name: name.unwrap(),
+                        typ: UDType::Indirect(typ),
+                    }))),
+                    None,
+                    tok,
+                ),
+            }
+        } else {
+            // not a known type name (see Context::add_type_name) so an expression
+            (
+                None,
+                Some(ExprNode::Variable(Box::new(Variable {
+                    name: name.unwrap(),
+                    decl: VarDecl::Indirect(TypeToFix::default()),
+                }))),
+                tok,
+            )
+        };
+
+        self.parse_with(typ, var, tok, context)
+    }
+
+    fn parse_with(
+        self,
+        typ: Option<BaseType>,
+        var: Option<ExprNode>,
+        tok: Option<Token>,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<DeclOrExpr>), ParserError> {
         if let Some(typ) = typ {
             let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
             if tok == Token::LeftParen {
@@ -654,11 +1221,11 @@ impl<'a, L: TLexer> DeclOrExprParser<'a, L> {
                 // if it's ok then it's a declaration else it's an expression.
                 let (_, mut saved) = self.lexer.save_until(Token::SemiColon, 1);
 
-                let tdp = TypeDeclaratorParser::new(&mut saved);
-                let res = tdp.parse(
+                let res = parse_decl_or_list(
+                    &mut saved,
+                    self.list,
                     Some(Token::LeftParen),
                     Some(DeclHint::Type(typ.clone())),
-                    true,
                     context,
                 );
 
@@ -683,13 +1250,15 @@ impl<'a, L: TLexer> DeclOrExprParser<'a, L> {
                     }
                 }
 
-                let (_, typ) = res.unwrap();
-                return Ok((None, Some(DeclOrExpr::Decl(typ.unwrap()))));
+                return res;
             } else {
-                let tdp = TypeDeclaratorParser::new(self.lexer);
-                let (tok, typ) = tdp.parse(Some(tok), Some(DeclHint::Type(typ)), true, context)?;
-
-                Ok((tok, Some(DeclOrExpr::Decl(typ.unwrap()))))
+                parse_decl_or_list(
+                    self.lexer,
+                    self.list,
+                    Some(tok),
+                    Some(DeclHint::Type(typ)),
+                    context,
+                )
             }
         } else if let Some(var) = var {
             let mut ep = ExpressionParser::new(self.lexer, Token::Eof);
@@ -698,11 +1267,9 @@ impl<'a, L: TLexer> DeclOrExprParser<'a, L> {
 
             Ok((tok, Some(DeclOrExpr::Expr(expr.unwrap()))))
         } else {
-            let tp = TypeDeclaratorParser::new(self.lexer);
-            let (tok, typ) = tp.parse(tok, None, true, context)?;
-
-            if let Some(typ) = typ {
-                return Ok((tok, Some(DeclOrExpr::Decl(typ))));
+            let (tok, decls) = parse_decl_or_list(self.lexer, self.list, tok, None, context)?;
+            if decls.is_some() {
+                return Ok((tok, decls));
             }
 
             let mut ep = ExpressionParser::new(self.lexer, Token::Eof);
@@ -725,10 +1292,11 @@ mod tests {
 
     use super::super::function::*;
     use super::*;
+    use crate::args::Language;
     use crate::lexer::{preprocessor::context::DefaultContext, Lexer};
     use crate::mk_var;
     use crate::parser::array::*;
-    use crate::parser::attributes::Attribute;
+    use crate::parser::attributes::{Attribute, AttributeArg};
     use crate::parser::declarations::class::{self, *};
     use crate::parser::declarations::member::*;
     use crate::parser::declarations::pointer::*;
@@ -736,6 +1304,7 @@ mod tests {
     use crate::parser::expressions::{self, *};
     use crate::parser::literals::{self, *};
     use crate::parser::names::{self, operator, ConvBaseType, ConvType, Name};
+    use crate::parser::statements::Statement;
     use crate::parser::types::{Primitive, UserDefined};
     use pretty_assertions::assert_eq;
 
@@ -752,7 +1321,9 @@ mod tests {
                 attributes: None,
             },
             init: None,
+            align: None,
             bitfield_size: None,
+            explicit_spec: None,
         });
 
         context.add_type_decl(Rc::clone(&t));
@@ -795,7 +1366,7 @@ mod tests {
             let mut l = Lexer::<DefaultContext>::new(buf.as_bytes());
             let p = DeclSpecifierParser::new(&mut l);
             let mut context = Context::default();
-            let (_, (_, ty, _, _)) = p.parse(None, None, &mut context).unwrap();
+            let (_, DeclSpecifiers { typ: ty, .. }) = p.parse(None, None, &mut context).unwrap();
 
             let ty = match ty.as_ref().unwrap().base() {
                 BaseType::Primitive(ty) => ty,
@@ -818,7 +1389,7 @@ mod tests {
             let mut l = Lexer::<DefaultContext>::new(buf.as_bytes());
             let p = DeclSpecifierParser::new(&mut l);
             let mut context = Context::default();
-            let (_, (_, ty, _, _)) = p.parse(None, None, &mut context).unwrap();
+            let (_, DeclSpecifiers { typ: ty, .. }) = p.parse(None, None, &mut context).unwrap();
             let ty = &ty.as_ref().unwrap();
 
             assert!(ty.is_const(), "{}", buf);
@@ -844,7 +1415,7 @@ mod tests {
             let mut l = Lexer::<DefaultContext>::new(buf.as_bytes());
             let p = DeclSpecifierParser::new(&mut l);
             let mut context = Context::default();
-            let (_, (_, ty, _, _)) = p.parse(None, None, &mut context).unwrap();
+            let (_, DeclSpecifiers { typ: ty, .. }) = p.parse(None, None, &mut context).unwrap();
             let ty = &ty.as_ref().unwrap();
 
             assert!(ty.is_volatile(), "{}", buf);
@@ -885,11 +1456,33 @@ mod tests {
                     attributes: None
                 },
                 init: Some(Initializer::Equal(ExprNode::Nullptr(Box::new(Nullptr {})))),
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
 
+    #[test]
+    fn test_null_pointer_constants() {
+        let get_init = |src: &[u8]| {
+            let mut l = Lexer::<DefaultContext>::new(src);
+            let p = TypeDeclaratorParser::new(&mut l);
+            let mut context = Context::default();
+            let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+            decl.unwrap().init.clone()
+        };
+
+        assert_eq!(
+            get_init(b"void* p = nullptr;"),
+            Some(Initializer::Equal(ExprNode::Nullptr(Box::new(Nullptr {}))))
+        );
+        assert_eq!(
+            get_init(b"void* q = __null;"),
+            Some(Initializer::Equal(ExprNode::GnuNull(Box::new(GnuNull {}))))
+        );
+    }
+
     #[test]
     fn test_simple_pointer_paren() {
         let mut l = Lexer::<DefaultContext>::new(b"int (*x) = nullptr");
@@ -917,7 +1510,9 @@ mod tests {
                     attributes: None
                 },
                 init: Some(Initializer::Equal(ExprNode::Nullptr(Box::new(Nullptr {})))),
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -952,7 +1547,9 @@ mod tests {
                     attributes: None
                 },
                 init: Some(Initializer::Equal(ExprNode::Nullptr(Box::new(Nullptr {})))),
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -983,7 +1580,9 @@ mod tests {
                         value: IntLiteral::Int(314)
                     }
                 )),])),
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -1017,7 +1616,9 @@ mod tests {
                 init: Some(Initializer::Equal(ExprNode::Variable(Box::new(mk_var!(
                     "NULL"
                 ))))),
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -1057,7 +1658,9 @@ mod tests {
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -1103,7 +1706,9 @@ mod tests {
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -1152,7 +1757,9 @@ mod tests {
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -1184,7 +1791,9 @@ mod tests {
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -1216,7 +1825,9 @@ mod tests {
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -1248,7 +1859,9 @@ mod tests {
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -1288,7 +1901,9 @@ mod tests {
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -1318,6 +1933,8 @@ mod tests {
                                         value: IntLiteral::Int(3)
                                     }))),
                                     attributes: None,
+                                    is_static: false,
+                                    cv: CVQualifier::empty(),
                                 }],
                             })),
                             cv: CVQualifier::empty(),
@@ -1333,6 +1950,8 @@ mod tests {
                                 value: IntLiteral::Int(2)
                             }))),
                             attributes: None,
+                            is_static: false,
+                            cv: CVQualifier::empty(),
                         }],
                     })),
                     cv: CVQualifier::empty(),
@@ -1344,7 +1963,9 @@ mod tests {
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -1382,7 +2003,8 @@ mod tests {
                         status: FunStatus::None,
                         requires: None,
                         ctor_init: None,
-                        body: RefCell::new(None)
+                        body: RefCell::new(None),
+                        handlers: RefCell::new(None)
                     })),
                     cv: CVQualifier::empty(),
                     pointers: None,
@@ -1393,7 +2015,9 @@ mod tests {
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -1430,7 +2054,9 @@ mod tests {
                                     attributes: None
                                 },
                                 init: None,
+                                align: None,
                                 bitfield_size: None,
+                                explicit_spec: None,
                             }),
                         }],
                         cv: CVQualifier::empty(),
@@ -1442,7 +2068,8 @@ mod tests {
                         status: FunStatus::None,
                         requires: None,
                         ctor_init: None,
-                        body: RefCell::new(None)
+                        body: RefCell::new(None),
+                        handlers: RefCell::new(None)
                     })),
                     cv: CVQualifier::empty(),
                     pointers: None,
@@ -1453,7 +2080,9 @@ mod tests {
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -1490,7 +2119,9 @@ mod tests {
                                     attributes: None
                                 },
                                 init: None,
+                                align: None,
                                 bitfield_size: None,
+                                explicit_spec: None,
                             }),
                         }],
                         cv: CVQualifier::empty(),
@@ -1502,7 +2133,8 @@ mod tests {
                         status: FunStatus::None,
                         requires: None,
                         ctor_init: None,
-                        body: RefCell::new(None)
+                        body: RefCell::new(None),
+                        handlers: RefCell::new(None)
                     })),
                     cv: CVQualifier::empty(),
                     pointers: Some(vec![
@@ -1526,7 +2158,9 @@ mod tests {
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -1576,7 +2210,9 @@ mod tests {
                                     attributes: None
                                 },
                                 init: None,
+                                align: None,
                                 bitfield_size: None,
+                                explicit_spec: None,
                             }),
                         }],
                         cv: CVQualifier::empty(),
@@ -1588,7 +2224,8 @@ mod tests {
                         status: FunStatus::None,
                         requires: None,
                         ctor_init: None,
-                        body: RefCell::new(None)
+                        body: RefCell::new(None),
+                        handlers: RefCell::new(None)
                     })),
                     cv: CVQualifier::empty(),
                     pointers: None,
@@ -1599,7 +2236,9 @@ mod tests {
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -1637,7 +2276,9 @@ mod tests {
                                         attributes: None
                                     },
                                     init: None,
+                                    align: None,
                                     bitfield_size: None,
+                                    explicit_spec: None,
                                 }),
                             },
                             Parameter {
@@ -1659,7 +2300,9 @@ mod tests {
                                         attributes: None
                                     },
                                     init: None,
+                                    align: None,
                                     bitfield_size: None,
+                                    explicit_spec: None,
                                 }),
                             }
                         ],
@@ -1672,7 +2315,8 @@ mod tests {
                         status: FunStatus::None,
                         requires: None,
                         ctor_init: None,
-                        body: RefCell::new(None)
+                        body: RefCell::new(None),
+                        handlers: RefCell::new(None)
                     })),
                     cv: CVQualifier::empty(),
                     pointers: None,
@@ -1683,7 +2327,9 @@ mod tests {
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -1724,7 +2370,9 @@ mod tests {
                                         value: IntLiteral::Int(123)
                                     }
                                 )))),
+                                align: None,
                                 bitfield_size: None,
+                                explicit_spec: None,
                             }),
                         }],
                         cv: CVQualifier::empty(),
@@ -1736,7 +2384,8 @@ mod tests {
                         status: FunStatus::None,
                         requires: None,
                         ctor_init: None,
-                        body: RefCell::new(None)
+                        body: RefCell::new(None),
+                        handlers: RefCell::new(None)
                     })),
                     cv: CVQualifier::empty(),
                     pointers: None,
@@ -1747,7 +2396,9 @@ mod tests {
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -1795,7 +2446,9 @@ mod tests {
                                         value: IntLiteral::Int(123)
                                     }
                                 )))),
+                                align: None,
                                 bitfield_size: None,
+                                explicit_spec: None,
                             }),
                         }],
                         cv: CVQualifier::CONST,
@@ -1822,7 +2475,8 @@ mod tests {
                         status: FunStatus::None,
                         requires: None,
                         ctor_init: None,
-                        body: RefCell::new(None)
+                        body: RefCell::new(None),
+                        handlers: RefCell::new(None)
                     })),
                     cv: CVQualifier::empty(),
                     pointers: None,
@@ -1833,90 +2487,146 @@ mod tests {
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
 
     #[test]
-    fn test_array() {
-        let mut l = Lexer::<DefaultContext>::new(b"int foo[123]");
+    fn test_fun_trailing_return() {
+        let mut l = Lexer::<DefaultContext>::new(b"auto f() -> int;");
         let p = TypeDeclaratorParser::new(&mut l);
         let mut context = Context::default();
-        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let (tok, decl) = p.parse(None, None, true, &mut context).unwrap();
         let decl = decl.unwrap();
 
+        assert_eq!(tok, Some(Token::SemiColon));
         assert_eq!(
             decl,
             Rc::new(TypeDeclarator {
                 typ: Type {
-                    base: BaseType::Array(Box::new(Array {
-                        base: Some(Type {
+                    base: BaseType::Function(Box::new(Function {
+                        return_type: Some(Type {
+                            base: BaseType::Auto,
+                            cv: CVQualifier::empty(),
+                            pointers: None,
+                        }),
+                        params: vec![],
+                        cv: CVQualifier::empty(),
+                        refq: RefQualifier::None,
+                        except: None,
+                        attributes: None,
+                        trailing: Some(Type {
                             base: BaseType::Primitive(Primitive::Int),
                             cv: CVQualifier::empty(),
                             pointers: None,
                         }),
-                        dimensions: vec![Dimension {
-                            size: Some(ExprNode::Integer(Box::new(literals::Integer {
-                                value: IntLiteral::Int(123)
-                            }))),
-                            attributes: None,
-                        }],
+                        virt_specifier: VirtSpecifier::empty(),
+                        status: FunStatus::None,
+                        requires: None,
+                        ctor_init: None,
+                        body: RefCell::new(None),
+                        handlers: RefCell::new(None)
                     })),
                     cv: CVQualifier::empty(),
                     pointers: None,
                 },
                 specifier: Specifier::empty(),
                 identifier: Identifier {
-                    identifier: Some(mk_id!("foo")),
+                    identifier: Some(mk_id!("f")),
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
 
     #[test]
-    fn test_array_no_size() {
-        let mut l = Lexer::<DefaultContext>::new(b"unsigned short foo[]");
+    fn test_fun_trailing_return_ptr_override() {
+        let mut l = Lexer::<DefaultContext>::new(b"auto g(int x) -> const A * override;");
         let p = TypeDeclaratorParser::new(&mut l);
         let mut context = Context::default();
-        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let (tok, decl) = p.parse(None, None, true, &mut context).unwrap();
         let decl = decl.unwrap();
 
+        assert_eq!(tok, Some(Token::SemiColon));
         assert_eq!(
             decl,
             Rc::new(TypeDeclarator {
                 typ: Type {
-                    base: BaseType::Array(Box::new(Array {
-                        base: Some(Type {
-                            base: BaseType::Primitive(Primitive::UnsignedShort),
+                    base: BaseType::Function(Box::new(Function {
+                        return_type: Some(Type {
+                            base: BaseType::Auto,
                             cv: CVQualifier::empty(),
                             pointers: None,
                         }),
-                        dimensions: vec![Dimension {
-                            size: None,
+                        params: vec![Parameter {
                             attributes: None,
+                            decl: Rc::new(TypeDeclarator {
+                                typ: Type {
+                                    base: BaseType::Primitive(Primitive::Int),
+                                    cv: CVQualifier::empty(),
+                                    pointers: None,
+                                },
+                                specifier: Specifier::empty(),
+                                identifier: Identifier {
+                                    identifier: Some(mk_id!("x")),
+                                    attributes: None,
+                                },
+                                init: None,
+                                align: None,
+                                bitfield_size: None,
+                                explicit_spec: None,
+                            }),
                         }],
+                        cv: CVQualifier::empty(),
+                        refq: RefQualifier::None,
+                        except: None,
+                        attributes: None,
+                        trailing: Some(Type {
+                            base: BaseType::UD(Box::new(UserDefined {
+                                name: mk_id!("A"),
+                                typ: UDType::Indirect(TypeToFix::default())
+                            })),
+                            cv: CVQualifier::CONST,
+                            pointers: Some(vec![Pointer {
+                                kind: PtrKind::Pointer,
+                                attributes: None,
+                                cv: CVQualifier::empty(),
+                                ms: MSModifier::empty(),
+                            }]),
+                        }),
+                        virt_specifier: VirtSpecifier::OVERRIDE,
+                        status: FunStatus::None,
+                        requires: None,
+                        ctor_init: None,
+                        body: RefCell::new(None),
+                        handlers: RefCell::new(None)
                     })),
                     cv: CVQualifier::empty(),
                     pointers: None,
                 },
                 specifier: Specifier::empty(),
                 identifier: Identifier {
-                    identifier: Some(mk_id!("foo")),
+                    identifier: Some(mk_id!("g")),
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
 
     #[test]
-    fn test_array_no_size_init() {
-        let mut l = Lexer::<DefaultContext>::new(b"static unsigned short foo[] = { 1, 2 }");
+    fn test_decltype_var() {
+        let mut l = Lexer::<DefaultContext>::new(b"decltype(x) y;");
         let p = TypeDeclaratorParser::new(&mut l);
         let mut context = Context::default();
         let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
@@ -1926,7 +2636,388 @@ mod tests {
             decl,
             Rc::new(TypeDeclarator {
                 typ: Type {
-                    base: BaseType::Array(Box::new(Array {
+                    base: BaseType::Decltype(ExprNode::Variable(Box::new(mk_var!("x")))),
+                    cv: CVQualifier::empty(),
+                    pointers: None,
+                },
+                specifier: Specifier::empty(),
+                identifier: Identifier {
+                    identifier: Some(mk_id!("y")),
+                    attributes: None
+                },
+                init: None,
+                align: None,
+                bitfield_size: None,
+                explicit_spec: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_decltype_auto() {
+        let mut l = Lexer::<DefaultContext>::new(b"decltype(auto) z = w;");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        assert_eq!(
+            decl,
+            Rc::new(TypeDeclarator {
+                typ: Type {
+                    base: BaseType::DecltypeAuto,
+                    cv: CVQualifier::empty(),
+                    pointers: None,
+                },
+                specifier: Specifier::empty(),
+                identifier: Identifier {
+                    identifier: Some(mk_id!("z")),
+                    attributes: None
+                },
+                init: Some(Initializer::Equal(ExprNode::Variable(Box::new(mk_var!(
+                    "w"
+                ))))),
+                align: None,
+                bitfield_size: None,
+                explicit_spec: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_decltype_return() {
+        let mut l = Lexer::<DefaultContext>::new(b"decltype(f()) g();");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        assert_eq!(
+            decl,
+            Rc::new(TypeDeclarator {
+                typ: Type {
+                    base: BaseType::Function(Box::new(Function {
+                        return_type: Some(Type {
+                            base: BaseType::Decltype(node!(CallExpr {
+                                callee: ExprNode::Variable(Box::new(mk_var!("f"))),
+                                params: vec![],
+                            })),
+                            cv: CVQualifier::empty(),
+                            pointers: None,
+                        }),
+                        params: vec![],
+                        cv: CVQualifier::empty(),
+                        refq: RefQualifier::None,
+                        except: None,
+                        attributes: None,
+                        trailing: None,
+                        virt_specifier: VirtSpecifier::empty(),
+                        status: FunStatus::None,
+                        requires: None,
+                        ctor_init: None,
+                        body: RefCell::new(None),
+                        handlers: RefCell::new(None)
+                    })),
+                    cv: CVQualifier::empty(),
+                    pointers: None,
+                },
+                specifier: Specifier::empty(),
+                identifier: Identifier {
+                    identifier: Some(mk_id!("g")),
+                    attributes: None
+                },
+                init: None,
+                align: None,
+                bitfield_size: None,
+                explicit_spec: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_fun_trailing_return_declarator() {
+        let parse = |buf: &[u8]| {
+            let mut l = Lexer::<DefaultContext>::new(buf);
+            let p = TypeDeclaratorParser::new(&mut l);
+            let mut context = Context::default();
+            let (tok, decl) = p.parse(None, None, true, &mut context).unwrap();
+            assert_eq!(tok, Some(Token::SemiColon));
+            let decl = decl.unwrap();
+            get_fun(&decl).trailing.clone().unwrap()
+        };
+        let ptr = |kind| {
+            Some(vec![Pointer {
+                kind,
+                attributes: None,
+                cv: CVQualifier::empty(),
+                ms: MSModifier::empty(),
+            }])
+        };
+        let int = Type {
+            base: BaseType::Primitive(Primitive::Int),
+            cv: CVQualifier::empty(),
+            pointers: None,
+        };
+
+        // pointer to function
+        let trailing = parse(b"auto f() -> int(*)(int);");
+        assert_eq!(trailing.pointers, ptr(PtrKind::Pointer));
+        let fun = match &trailing.base {
+            BaseType::Function(fun) => fun,
+            _ => panic!("Not a function"),
+        };
+        assert_eq!(fun.return_type, Some(int.clone()));
+        assert_eq!(fun.params.len(), 1);
+        assert_eq!(fun.params[0].decl.typ, int);
+
+        // reference to array
+        assert_eq!(
+            parse(b"auto g() -> int(&)[3];"),
+            Type {
+                base: BaseType::Array(Box::new(Array {
+                    base: Some(int),
+                    dimensions: vec![Dimension {
+                        size: Some(ExprNode::Integer(Box::new(literals::Integer {
+                            value: IntLiteral::Int(3)
+                        }))),
+                        attributes: None,
+                        is_static: false,
+                        cv: CVQualifier::empty(),
+                    }],
+                })),
+                cv: CVQualifier::empty(),
+                pointers: ptr(PtrKind::Reference),
+            }
+        );
+
+        // a type-id has no declarator-id
+        let mut l = Lexer::<DefaultContext>::new(b"auto h() -> int x;");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        assert!(p.parse(None, None, true, &mut context).is_err());
+    }
+
+    #[test]
+    fn test_decltype_trailing_return() {
+        let mut l = Lexer::<DefaultContext>::new(b"auto g(int x) -> decltype(x);");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        let fun = match &decl.typ.base {
+            BaseType::Function(fun) => fun,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(
+            fun.return_type,
+            Some(Type {
+                base: BaseType::Auto,
+                cv: CVQualifier::empty(),
+                pointers: None,
+            })
+        );
+        assert_eq!(
+            fun.trailing,
+            Some(Type {
+                base: BaseType::Decltype(ExprNode::Variable(Box::new(mk_var!("x")))),
+                cv: CVQualifier::empty(),
+                pointers: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_noexcept_decltype_trailing_return() {
+        let mut context = Context::default();
+        for (src, except) in [
+            (
+                &b"auto f() noexcept -> decltype(x);"[..],
+                Exception::Noexcept(None),
+            ),
+            (
+                b"auto f() noexcept(true) -> decltype(x);",
+                Exception::Noexcept(Some(ExprNode::Bool(Box::new(literals::Bool {
+                    value: true,
+                })))),
+            ),
+        ] {
+            let mut l = Lexer::<DefaultContext>::new(src);
+            let p = TypeDeclaratorParser::new(&mut l);
+            let (tok, decl) = p.parse(None, None, true, &mut context).unwrap();
+            let decl = decl.unwrap();
+
+            let fun = match &decl.typ.base {
+                BaseType::Function(fun) => fun,
+                _ => unreachable!(),
+            };
+
+            assert_eq!(fun.except, Some(except));
+            assert_eq!(
+                fun.trailing,
+                Some(Type {
+                    base: BaseType::Decltype(ExprNode::Variable(Box::new(mk_var!("x")))),
+                    cv: CVQualifier::empty(),
+                    pointers: None,
+                })
+            );
+            assert_eq!(tok.unwrap_or_else(|| l.next_useful()), Token::SemiColon);
+        }
+    }
+
+    #[test]
+    fn test_array() {
+        let mut l = Lexer::<DefaultContext>::new(b"int foo[123]");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        assert_eq!(
+            decl,
+            Rc::new(TypeDeclarator {
+                typ: Type {
+                    base: BaseType::Array(Box::new(Array {
+                        base: Some(Type {
+                            base: BaseType::Primitive(Primitive::Int),
+                            cv: CVQualifier::empty(),
+                            pointers: None,
+                        }),
+                        dimensions: vec![Dimension {
+                            size: Some(ExprNode::Integer(Box::new(literals::Integer {
+                                value: IntLiteral::Int(123)
+                            }))),
+                            attributes: None,
+                            is_static: false,
+                            cv: CVQualifier::empty(),
+                        }],
+                    })),
+                    cv: CVQualifier::empty(),
+                    pointers: None,
+                },
+                specifier: Specifier::empty(),
+                identifier: Identifier {
+                    identifier: Some(mk_id!("foo")),
+                    attributes: None
+                },
+                init: None,
+                align: None,
+                bitfield_size: None,
+                explicit_spec: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_array_no_size() {
+        let mut l = Lexer::<DefaultContext>::new(b"unsigned short foo[]");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        assert_eq!(
+            decl,
+            Rc::new(TypeDeclarator {
+                typ: Type {
+                    base: BaseType::Array(Box::new(Array {
+                        base: Some(Type {
+                            base: BaseType::Primitive(Primitive::UnsignedShort),
+                            cv: CVQualifier::empty(),
+                            pointers: None,
+                        }),
+                        dimensions: vec![Dimension {
+                            size: None,
+                            attributes: None,
+                            is_static: false,
+                            cv: CVQualifier::empty(),
+                        }],
+                    })),
+                    cv: CVQualifier::empty(),
+                    pointers: None,
+                },
+                specifier: Specifier::empty(),
+                identifier: Identifier {
+                    identifier: Some(mk_id!("foo")),
+                    attributes: None
+                },
+                init: None,
+                align: None,
+                bitfield_size: None,
+                explicit_spec: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_array_param_qualifiers() {
+        let mut l = Lexer::<DefaultContext>::new(
+            b"void f(int a[static 10], int b[const restrict 4], int c[static 2][3]);",
+        );
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+        let fun = get_fun(&decl);
+
+        let dims: Vec<_> = fun
+            .params
+            .iter()
+            .map(|param| match &param.decl.typ.base {
+                BaseType::Array(array) => {
+                    let dim = &array.dimensions[0];
+                    (dim.is_static, dim.cv, array.dimensions.len())
+                }
+                _ => panic!("Not an array"),
+            })
+            .collect();
+        assert_eq!(
+            dims,
+            vec![
+                (true, CVQualifier::empty(), 1),
+                (false, CVQualifier::CONST | CVQualifier::RESTRICT, 1),
+                (true, CVQualifier::empty(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_array_qualifiers_not_in_param() {
+        for buf in [
+            "int a[static 10];",
+            "int b[const 4];",
+            "void f(int a[2][static 3]);",
+            "void f(int (*a)[static 3]);",
+        ] {
+            let mut l = Lexer::<DefaultContext>::new(buf.as_bytes());
+            let p = TypeDeclaratorParser::new(&mut l);
+            let mut context = Context::default();
+
+            assert!(
+                matches!(
+                    p.parse(None, None, true, &mut context),
+                    Err(ParserError::InvalidArrayQualifiers { .. })
+                ),
+                "{}",
+                buf
+            );
+        }
+    }
+
+    #[test]
+    fn test_array_no_size_init() {
+        let mut l = Lexer::<DefaultContext>::new(b"static unsigned short foo[] = { 1, 2 }");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        assert_eq!(
+            decl,
+            Rc::new(TypeDeclarator {
+                typ: Type {
+                    base: BaseType::Array(Box::new(Array {
                         base: Some(Type {
                             base: BaseType::Primitive(Primitive::UnsignedShort),
                             cv: CVQualifier::empty(),
@@ -1935,6 +3026,8 @@ mod tests {
                         dimensions: vec![Dimension {
                             size: None,
                             attributes: None,
+                            is_static: false,
+                            cv: CVQualifier::empty(),
                         }],
                     })),
                     cv: CVQualifier::empty(),
@@ -1953,7 +3046,9 @@ mod tests {
                         value: IntLiteral::Int(2)
                     })),
                 ])))),
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -1990,7 +3085,9 @@ mod tests {
                     attributes: None,
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -2026,7 +3123,9 @@ mod tests {
                                     attributes: None,
                                 },
                                 init: None,
+                                align: None,
                                 bitfield_size: None,
+                                explicit_spec: None,
                             })),],
                             protected: vec![],
                             private: vec![],
@@ -2041,27 +3140,132 @@ mod tests {
                     attributes: None,
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
 
     #[test]
-    fn test_operator_unary() {
-        let mut l = Lexer::<DefaultContext>::new(b"A B::operator+()");
+    fn test_struct_nodiscard() {
+        let mut l = Lexer::<DefaultContext>::new(b"struct [[nodiscard]] ErrorCode {};");
         let p = TypeDeclaratorParser::new(&mut l);
         let mut context = Context::default();
-        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let (tok, decl) = p.parse(None, None, true, &mut context).unwrap();
         let decl = decl.unwrap();
 
+        assert_eq!(tok, Some(Token::SemiColon));
         assert_eq!(
             decl,
             Rc::new(TypeDeclarator {
                 typ: Type {
-                    base: BaseType::Function(Box::new(Function {
-                        return_type: Some(Type {
-                            base: BaseType::UD(Box::new(UserDefined {
-                                name: mk_id!("A"),
+                    base: BaseType::Class(Box::new(Class {
+                        kind: class::Kind::Struct,
+                        attributes: Some(vec![Attribute {
+                            namespace: None,
+                            name: "nodiscard".to_string(),
+                            arg: None,
+                            has_using: false,
+                        }]),
+                        name: Some(mk_id!("ErrorCode")),
+                        r#final: false,
+                        bases: None,
+                        body: Some(ClassBody {
+                            public: vec![],
+                            protected: vec![],
+                            private: vec![],
+                        }),
+                    })),
+                    cv: CVQualifier::empty(),
+                    pointers: None,
+                },
+                specifier: Specifier::empty(),
+                identifier: Identifier {
+                    identifier: None,
+                    attributes: None,
+                },
+                init: None,
+                align: None,
+                bitfield_size: None,
+                explicit_spec: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_enum_nodiscard() {
+        let mut l = Lexer::<DefaultContext>::new(
+            b"enum class [[nodiscard, deprecated(\"old\")]] Status { ok };",
+        );
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (tok, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        assert_eq!(tok, Some(Token::SemiColon));
+        assert_eq!(
+            decl,
+            Rc::new(TypeDeclarator {
+                typ: Type {
+                    base: BaseType::Enum(Box::new(Enum {
+                        kind: r#enum::Kind::Class,
+                        attributes: Some(vec![
+                            Attribute {
+                                namespace: None,
+                                name: "nodiscard".to_string(),
+                                arg: None,
+                                has_using: false,
+                            },
+                            Attribute {
+                                namespace: None,
+                                name: "deprecated".to_string(),
+                                arg: Some(AttributeArg {
+                                    tokens: vec![Token::LiteralString("old".to_string())],
+                                }),
+                                has_using: false,
+                            },
+                        ]),
+                        name: Some(mk_id!("Status")),
+                        base: None,
+                        entries: Some(vec![Entry {
+                            name: "ok".to_string(),
+                            attributes: None,
+                            init: None
+                        },]),
+                    })),
+                    cv: CVQualifier::empty(),
+                    pointers: None,
+                },
+                specifier: Specifier::empty(),
+                identifier: Identifier {
+                    identifier: None,
+                    attributes: None,
+                },
+                init: None,
+                align: None,
+                bitfield_size: None,
+                explicit_spec: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_operator_unary() {
+        let mut l = Lexer::<DefaultContext>::new(b"A B::operator+()");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        assert_eq!(
+            decl,
+            Rc::new(TypeDeclarator {
+                typ: Type {
+                    base: BaseType::Function(Box::new(Function {
+                        return_type: Some(Type {
+                            base: BaseType::UD(Box::new(UserDefined {
+                                name: mk_id!("A"),
                                 typ: UDType::Indirect(TypeToFix::default())
                             })),
                             cv: CVQualifier::empty(),
@@ -2077,7 +3281,8 @@ mod tests {
                         status: FunStatus::None,
                         requires: None,
                         ctor_init: None,
-                        body: RefCell::new(None)
+                        body: RefCell::new(None),
+                        handlers: RefCell::new(None)
                     })),
                     cv: CVQualifier::empty(),
                     pointers: None,
@@ -2097,11 +3302,55 @@ mod tests {
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
 
+    #[test]
+    fn test_gnu_auto_type() {
+        let mut l = Lexer::<DefaultContext>::new(b"__auto_type y = f();");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        assert_eq!(decl.typ.base, BaseType::Auto);
+        assert_eq!(decl.identifier.identifier, Some(mk_id!("y")));
+        assert_eq!(
+            decl.init,
+            Some(Initializer::Equal(node!(CallExpr {
+                callee: ExprNode::Variable(Box::new(mk_var!("f"))),
+                params: vec![],
+            })))
+        );
+    }
+
+    #[test]
+    fn test_operator_subscript_multi() {
+        let mut l = Lexer::<DefaultContext>::new(b"T operator[](int, int);");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        assert_eq!(
+            decl.identifier.identifier,
+            Some(Qualified {
+                names: vec![Name::Operator(Box::new(operator::Operator::Op(
+                    expressions::Operator::Subscript
+                )))]
+            })
+        );
+        if let BaseType::Function(fun) = &decl.typ.base {
+            assert_eq!(fun.params.len(), 2);
+        } else {
+            panic!("Not a function");
+        }
+    }
+
     #[test]
     fn test_operator_conv() {
         let mut l = Lexer::<DefaultContext>::new(b"operator A()");
@@ -2126,7 +3375,8 @@ mod tests {
                         status: FunStatus::None,
                         requires: None,
                         ctor_init: None,
-                        body: RefCell::new(None)
+                        body: RefCell::new(None),
+                        handlers: RefCell::new(None)
                     })),
                     cv: CVQualifier::empty(),
                     pointers: None,
@@ -2148,7 +3398,9 @@ mod tests {
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })
         );
     }
@@ -2177,7 +3429,8 @@ mod tests {
                         status: FunStatus::None,
                         requires: None,
                         ctor_init: None,
-                        body: RefCell::new(None)
+                        body: RefCell::new(None),
+                        handlers: RefCell::new(None)
                     })),
                     cv: CVQualifier::empty(),
                     pointers: None,
@@ -2202,7 +3455,36 @@ mod tests {
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_operator_conv_typename() {
+        let mut l = Lexer::<DefaultContext>::new(b"operator typename Traits::value_type() const;");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (tok, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        assert_eq!(tok, Some(Token::SemiColon));
+        assert!(matches!(&decl.typ.base, BaseType::Function(f) if f.cv == CVQualifier::CONST));
+        assert_eq!(
+            decl.identifier.identifier,
+            Some(Qualified {
+                names: vec![Name::Operator(Box::new(operator::Operator::Conv(
+                    ConvType {
+                        base: ConvBaseType::UD(Box::new(UserDefined {
+                            name: mk_id!("Traits", "value_type"),
+                            typ: UDType::Indirect(TypeToFix::default())
+                        })),
+                        cv: CVQualifier::empty(),
+                        pointers: None,
+                    },
+                )))]
             })
         );
     }
@@ -2318,7 +3600,9 @@ mod tests {
                                     attributes: None
                                 },
                                 init: None,
+                                align: None,
                                 bitfield_size: None,
+                                explicit_spec: None,
                             }),
                         }],
                         cv: CVQualifier::empty(),
@@ -2330,7 +3614,8 @@ mod tests {
                         status: FunStatus::None,
                         requires: None,
                         ctor_init: None,
-                        body: RefCell::new(None)
+                        body: RefCell::new(None),
+                        handlers: RefCell::new(None)
                     })),
                     cv: CVQualifier::empty(),
                     pointers: Some(vec![Pointer {
@@ -2346,8 +3631,605 @@ mod tests {
                     attributes: None
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             }))
         );
     }
+
+    #[test]
+    fn test_alignas_expr() {
+        let mut l = Lexer::<DefaultContext>::new(b"alignas(32) int x;");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        assert_eq!(
+            decl,
+            Rc::new(TypeDeclarator {
+                typ: Type {
+                    base: BaseType::Primitive(Primitive::Int),
+                    cv: CVQualifier::empty(),
+                    pointers: None,
+                },
+                specifier: Specifier::empty(),
+                identifier: Identifier {
+                    identifier: Some(mk_id!("x")),
+                    attributes: None
+                },
+                init: None,
+                align: Some(vec![Alignas::Expr(ExprNode::Integer(Box::new(
+                    literals::Integer {
+                        value: IntLiteral::Int(32)
+                    }
+                )))]),
+                bitfield_size: None,
+                explicit_spec: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_alignas_multiple() {
+        let mut l = Lexer::<DefaultContext>::new(b"static alignas(16) alignas(double) char c;");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        assert_eq!(
+            decl,
+            Rc::new(TypeDeclarator {
+                typ: Type {
+                    base: BaseType::Primitive(Primitive::Char),
+                    cv: CVQualifier::empty(),
+                    pointers: None,
+                },
+                specifier: Specifier::STATIC,
+                identifier: Identifier {
+                    identifier: Some(mk_id!("c")),
+                    attributes: None
+                },
+                init: None,
+                align: Some(vec![
+                    Alignas::Expr(ExprNode::Integer(Box::new(literals::Integer {
+                        value: IntLiteral::Int(16)
+                    }))),
+                    Alignas::Type(Type {
+                        base: BaseType::Primitive(Primitive::Double),
+                        cv: CVQualifier::empty(),
+                        pointers: None,
+                    }),
+                ]),
+                bitfield_size: None,
+                explicit_spec: None,
+            })
+        );
+        assert_eq!(decl.get_alignment(), None);
+    }
+
+    #[test]
+    fn test_alignas_strictest() {
+        let mut l =
+            Lexer::<DefaultContext>::new(b"alignas(8) alignas(0) alignas(32) alignas(4) int x;");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        assert_eq!(decl.align.as_ref().map(|a| a.len()), Some(4));
+        assert_eq!(decl.get_alignment(), Some(32));
+    }
+
+    #[test]
+    fn test_alignas_unclosed() {
+        for code in [
+            &b"alignas(16] int y;"[..],
+            b"alignas(16 int y;",
+            b"alignas(double] int y;",
+        ] {
+            let mut l = Lexer::<DefaultContext>::new(code);
+            let p = TypeDeclaratorParser::new(&mut l);
+            let mut context = Context::default();
+            assert!(p.parse(None, None, true, &mut context).is_err());
+        }
+    }
+
+    #[test]
+    fn test_alignas_member() {
+        let mut l = Lexer::<DefaultContext>::new(b"struct S { alignas(8) int x; };");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        assert_eq!(
+            decl,
+            Rc::new(TypeDeclarator {
+                typ: Type {
+                    base: BaseType::Class(Box::new(Class {
+                        kind: class::Kind::Struct,
+                        attributes: None,
+                        name: Some(mk_id!("S")),
+                        r#final: false,
+                        bases: None,
+                        body: Some(ClassBody {
+                            public: vec![Member::Type(Rc::new(TypeDeclarator {
+                                typ: Type {
+                                    base: BaseType::Primitive(Primitive::Int),
+                                    cv: CVQualifier::empty(),
+                                    pointers: None,
+                                },
+                                specifier: Specifier::empty(),
+                                identifier: Identifier {
+                                    identifier: Some(mk_id!("x")),
+                                    attributes: None
+                                },
+                                init: None,
+                                align: Some(vec![Alignas::Expr(ExprNode::Integer(Box::new(
+                                    literals::Integer {
+                                        value: IntLiteral::Int(8)
+                                    }
+                                )))]),
+                                bitfield_size: None,
+                                explicit_spec: None,
+                            }))],
+                            protected: vec![],
+                            private: vec![],
+                        }),
+                    })),
+                    cv: CVQualifier::empty(),
+                    pointers: None,
+                },
+                specifier: Specifier::empty(),
+                identifier: Identifier {
+                    identifier: None,
+                    attributes: None,
+                },
+                init: None,
+                align: None,
+                bitfield_size: None,
+                explicit_spec: None,
+            })
+        );
+    }
+
+    fn mk_fun(return_type: Type, param: Primitive) -> BaseType {
+        BaseType::Function(Box::new(Function {
+            return_type: Some(return_type),
+            params: vec![Parameter {
+                attributes: None,
+                decl: Rc::new(TypeDeclarator {
+                    typ: Type {
+                        base: BaseType::Primitive(param),
+                        cv: CVQualifier::empty(),
+                        pointers: None,
+                    },
+                    specifier: Specifier::empty(),
+                    identifier: Identifier {
+                        identifier: None,
+                        attributes: None,
+                    },
+                    init: None,
+                    align: None,
+                    bitfield_size: None,
+                    explicit_spec: None,
+                }),
+            }],
+            cv: CVQualifier::empty(),
+            refq: RefQualifier::None,
+            except: None,
+            attributes: None,
+            trailing: None,
+            virt_specifier: VirtSpecifier::empty(),
+            status: FunStatus::None,
+            requires: None,
+            ctor_init: None,
+            body: RefCell::new(None),
+            handlers: RefCell::new(None),
+        }))
+    }
+
+    fn mk_ptr() -> Option<Pointers> {
+        Some(vec![Pointer {
+            kind: PtrKind::Pointer,
+            attributes: None,
+            cv: CVQualifier::empty(),
+            ms: MSModifier::empty(),
+        }])
+    }
+
+    fn mk_decl(typ: Type, name: &str) -> Rc<TypeDeclarator> {
+        Rc::new(TypeDeclarator {
+            typ,
+            specifier: Specifier::empty(),
+            identifier: Identifier {
+                identifier: Some(mk_id!(name)),
+                attributes: None,
+            },
+            init: None,
+            align: None,
+            bitfield_size: None,
+            explicit_spec: None,
+        })
+    }
+
+    #[test]
+    fn test_paren_ptr_to_array() {
+        let mut l = Lexer::<DefaultContext>::new(b"int (*p)[3];");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+
+        assert_eq!(
+            decl.unwrap(),
+            mk_decl(
+                Type {
+                    base: BaseType::Array(Box::new(Array {
+                        base: Some(Type {
+                            base: BaseType::Primitive(Primitive::Int),
+                            cv: CVQualifier::empty(),
+                            pointers: None,
+                        }),
+                        dimensions: vec![Dimension {
+                            size: Some(ExprNode::Integer(Box::new(literals::Integer {
+                                value: IntLiteral::Int(3)
+                            }))),
+                            attributes: None,
+                            is_static: false,
+                            cv: CVQualifier::empty(),
+                        }],
+                    })),
+                    cv: CVQualifier::empty(),
+                    pointers: mk_ptr(),
+                },
+                "p"
+            )
+        );
+    }
+
+    #[test]
+    fn test_paren_fun_ptr() {
+        let mut l = Lexer::<DefaultContext>::new(b"void (*f)(int);");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+
+        let void = Type {
+            base: BaseType::Primitive(Primitive::Void),
+            cv: CVQualifier::empty(),
+            pointers: None,
+        };
+        assert_eq!(
+            decl.unwrap(),
+            mk_decl(
+                Type {
+                    base: mk_fun(void, Primitive::Int),
+                    cv: CVQualifier::empty(),
+                    pointers: mk_ptr(),
+                },
+                "f"
+            )
+        );
+    }
+
+    #[test]
+    fn test_paren_fun_ptr_returning_ptr() {
+        let mut l = Lexer::<DefaultContext>::new(b"int *(*g)(char);");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+
+        let int_ptr = Type {
+            base: BaseType::Primitive(Primitive::Int),
+            cv: CVQualifier::empty(),
+            pointers: mk_ptr(),
+        };
+        assert_eq!(
+            decl.unwrap(),
+            mk_decl(
+                Type {
+                    base: mk_fun(int_ptr, Primitive::Char),
+                    cv: CVQualifier::empty(),
+                    pointers: mk_ptr(),
+                },
+                "g"
+            )
+        );
+    }
+
+    #[test]
+    fn test_paren_nested() {
+        let mut l = Lexer::<DefaultContext>::new(b"void (*(*h)(int))(double);");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+
+        // h is a pointer to a function taking an int and returning
+        // a pointer to a function taking a double and returning void
+        let void = Type {
+            base: BaseType::Primitive(Primitive::Void),
+            cv: CVQualifier::empty(),
+            pointers: None,
+        };
+        let inner = Type {
+            base: mk_fun(void, Primitive::Double),
+            cv: CVQualifier::empty(),
+            pointers: mk_ptr(),
+        };
+        assert_eq!(
+            decl.unwrap(),
+            mk_decl(
+                Type {
+                    base: mk_fun(inner, Primitive::Int),
+                    cv: CVQualifier::empty(),
+                    pointers: mk_ptr(),
+                },
+                "h"
+            )
+        );
+    }
+
+    #[test]
+    fn test_consteval_function() {
+        let mut l = Lexer::<DefaultContext>::new(b"consteval int f();");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        assert_eq!(decl.specifier, Specifier::CONSTEVAL);
+        assert_eq!(decl.identifier.identifier, Some(mk_id!("f")));
+        assert!(matches!(decl.typ.base, BaseType::Function(_)));
+    }
+
+    #[test]
+    fn test_constinit_variable() {
+        let mut l = Lexer::<DefaultContext>::new(b"constinit int x = 0;");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        assert_eq!(decl.specifier, Specifier::CONSTINIT);
+        assert_eq!(decl.identifier.identifier, Some(mk_id!("x")));
+        assert_eq!(decl.typ.base, BaseType::Primitive(Primitive::Int));
+        assert_eq!(
+            decl.init,
+            Some(Initializer::Equal(ExprNode::Integer(Box::new(Integer {
+                value: IntLiteral::Int(0)
+            }))))
+        );
+    }
+
+    fn get_fun(decl: &TypeDeclarator) -> &Function {
+        if let BaseType::Function(fun) = &decl.typ.base {
+            fun
+        } else {
+            panic!("Not a function: {:?}", decl);
+        }
+    }
+
+    #[test]
+    fn test_function_try_block() {
+        let mut l =
+            Lexer::<DefaultContext>::new(b"void f(int x) try { g(x); } catch (int e) { h(e, x); }");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+        let fun = get_fun(&decl);
+
+        assert!(fun.body.borrow().is_some());
+        assert!(fun.ctor_init.is_none());
+        let handlers = fun.handlers.borrow();
+        let handlers = handlers.as_ref().unwrap();
+        assert_eq!(handlers.len(), 1);
+        assert_eq!(
+            handlers[0].clause.as_ref().unwrap().identifier.identifier,
+            Some(mk_id!("e"))
+        );
+    }
+
+    #[test]
+    fn test_function_try_block_ctor_init() {
+        let mut l = Lexer::<DefaultContext>::new(
+            b"C::C() try : B(), x(1) { } catch (E& e) { } catch (...) { throw; }",
+        );
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+        let fun = get_fun(&decl);
+
+        let names: Vec<_> = fun
+            .ctor_init
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|i| i.name.clone())
+            .collect();
+        assert_eq!(names, vec![mk_id!("B"), mk_id!("x")]);
+        assert!(fun.body.borrow().is_some());
+
+        let handlers = fun.handlers.borrow();
+        let handlers = handlers.as_ref().unwrap();
+        assert_eq!(handlers.len(), 2);
+        assert!(handlers[0].clause.is_some());
+        assert!(handlers[1].clause.is_none());
+    }
+
+    #[test]
+    fn test_function_try_block_in_class() {
+        let mut l = Lexer::<DefaultContext>::new(
+            b"struct S { S() try : x(0) { } catch (E& e) { y; } catch (...) { } int x; int y; };",
+        );
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        let members = match &decl.typ.base {
+            BaseType::Class(c) => c.body.as_ref().unwrap().public.clone(),
+            _ => panic!("Not a class: {:?}", decl),
+        };
+        assert_eq!(members.len(), 3);
+
+        let ctor = match &members[0] {
+            Member::Type(t) => t,
+            _ => panic!("Not a type member"),
+        };
+        let fun = get_fun(ctor);
+        assert!(fun.body.borrow().is_some());
+
+        let handlers = fun.handlers.borrow();
+        let handlers = handlers.as_ref().unwrap();
+        assert_eq!(handlers.len(), 2);
+        assert!(handlers[0].clause.is_some());
+        assert!(handlers[1].clause.is_none());
+
+        // y is declared after the constructor
+        let stmt = match &*handlers[0].body {
+            Statement::Compound(c) => c.stmts[0].clone(),
+            _ => panic!("Not a compound statement"),
+        };
+        assert!(matches!(
+            stmt,
+            Statement::Expression(e) if matches!(&*e, ExprNode::Variable(v) if matches!(v.decl, VarDecl::Direct(_)))
+        ));
+    }
+
+    #[test]
+    fn test_explicit_spec() {
+        let parse = |src: &[u8]| {
+            let mut l = Lexer::<DefaultContext>::new(src);
+            let p = TypeDeclaratorParser::new(&mut l);
+            let mut context = Context::default();
+            let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+            let decl = decl.unwrap();
+            assert!(decl.specifier.intersects(Specifier::EXPLICIT));
+            assert!(decl.is_function());
+            decl.explicit_spec.clone()
+        };
+
+        assert_eq!(parse(b"explicit C(int);"), Some(None));
+        assert_eq!(
+            parse(b"explicit(true) C(int);"),
+            Some(Some(ExprNode::Bool(Box::new(Bool { value: true }))))
+        );
+        assert_eq!(
+            parse(b"explicit(sizeof(T) > 4) operator int();"),
+            Some(Some(node!(BinaryOp {
+                op: Operator::Gt,
+                arg1: node!(UnaryOp {
+                    op: Operator::Sizeof,
+                    arg: ExprNode::Variable(Box::new(mk_var!("T"))),
+                }),
+                arg2: ExprNode::Integer(Box::new(Integer {
+                    value: IntLiteral::Int(4)
+                })),
+            })))
+        );
+        assert_eq!(
+            parse(b"explicit(false) C(int);"),
+            Some(Some(ExprNode::Bool(Box::new(Bool { value: false }))))
+        );
+    }
+
+    #[test]
+    fn test_c11_noreturn_alignas() {
+        let mut l = Lexer::<DefaultContext>::new(b"_Noreturn void abort_now();");
+        l.set_language(Language::C);
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        assert_eq!(decl.specifier, Specifier::NORETURN);
+        assert_eq!(decl.identifier.identifier, Some(mk_id!("abort_now")));
+        assert!(decl.is_function());
+
+        let mut l = Lexer::<DefaultContext>::new(b"_Alignas(8) char buf[8];");
+        l.set_language(Language::C);
+        let p = TypeDeclaratorParser::new(&mut l);
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        assert_eq!(decl.identifier.identifier, Some(mk_id!("buf")));
+        assert!(matches!(decl.typ.base, BaseType::Array(_)));
+        assert_eq!(
+            decl.align,
+            Some(vec![Alignas::Expr(ExprNode::Integer(Box::new(
+                literals::Integer {
+                    value: IntLiteral::Int(8)
+                }
+            )))])
+        );
+    }
+
+    #[test]
+    fn test_gnu_thread() {
+        let mut l = Lexer::<DefaultContext>::new(b"__thread int x;");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        assert_eq!(decl.specifier, Specifier::THREAD_LOCAL);
+        assert_eq!(decl.typ.base, BaseType::Primitive(Primitive::Int));
+        assert_eq!(decl.identifier.identifier, Some(mk_id!("x")));
+
+        let mut l = Lexer::<DefaultContext>::new(b"static __thread int y;");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let (_, decl) = p.parse(None, None, true, &mut context).unwrap();
+
+        assert_eq!(
+            decl.unwrap().specifier,
+            Specifier::STATIC | Specifier::THREAD_LOCAL
+        );
+    }
+
+    #[test]
+    fn test_decltype_sfinae_trailing_return() {
+        let mut l = Lexer::<DefaultContext>::new(b"auto f(T t) -> decltype(t.begin(), void());");
+        let p = TypeDeclaratorParser::new(&mut l);
+        let mut context = Context::default();
+        let (tok, decl) = p.parse(None, None, true, &mut context).unwrap();
+        let decl = decl.unwrap();
+
+        let fun = match &decl.typ.base {
+            BaseType::Function(fun) => fun,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(
+            fun.trailing,
+            Some(Type {
+                base: BaseType::Decltype(node!(BinaryOp {
+                    op: Operator::Comma,
+                    arg1: node!(CallExpr {
+                        callee: node!(BinaryOp {
+                            op: Operator::Dot,
+                            arg1: ExprNode::Variable(Box::new(mk_var!("t"))),
+                            arg2: ExprNode::Variable(Box::new(mk_var!("begin"))),
+                        }),
+                        params: vec![],
+                    }),
+                    arg2: node!(CallExpr {
+                        callee: ExprNode::Type(Box::new(Type {
+                            base: BaseType::Primitive(Primitive::Void),
+                            cv: CVQualifier::empty(),
+                            pointers: None,
+                        })),
+                        params: vec![],
+                    }),
+                })),
+                cv: CVQualifier::empty(),
+                pointers: None,
+            })
+        );
+        assert_eq!(tok.unwrap_or_else(|| l.next_useful()), Token::SemiColon);
+    }
 }
@@ -7,6 +7,7 @@ use std::rc::Rc;
 use termcolor::StandardStreamLock;
 
 use super::decl::Declaration;
+use super::template::TemplateParameters;
 use super::types::{TypeDeclarator, TypeDeclaratorParser};
 use crate::lexer::{TLexer, Token};
 use crate::parser::attributes::{Attributes, AttributesParser};
@@ -82,6 +83,7 @@ impl Dump for UsingNS {
 #[derive(Clone, Debug, PartialEq)]
 pub struct UsingAlias {
     pub name: String,
+    pub template: Option<TemplateParameters>,
     pub typ: Rc<TypeDeclarator>,
     pub attributes: Option<Attributes>,
 }
@@ -96,6 +98,7 @@ impl Dump for UsingAlias {
             last,
             stdout,
             name,
+            template,
             typ,
             attributes
         );
@@ -194,9 +197,17 @@ impl<'a, L: TLexer> UsingParser<'a, L> {
                         })),
                     ));
                 }
-                Token::DoubleLeftBrack => {
+                Token::DoubleLeftBrack | Token::Equal => {
+                    // using A [[...]] = type: the name must be a simple identifier
+                    if !names.is_empty() || typename || name.names.len() != 1 {
+                        return Err(ParserError::InvalidTokenInAlias {
+                            sp: self.lexer.span(),
+                            tok: tk,
+                        });
+                    }
+
                     let ap = AttributesParser::new(self.lexer);
-                    let (tok, attrs) = ap.parse(Some(tk), context)?;
+                    let (tok, attributes) = ap.parse(Some(tk), context)?;
                     let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
 
                     if tok != Token::Equal {
@@ -209,24 +220,15 @@ impl<'a, L: TLexer> UsingParser<'a, L> {
                     let tdp = TypeDeclaratorParser::new(self.lexer);
                     let (tok, typ) = tdp.parse(None, None, false, context)?;
                     let name = name.get_first_name();
-                    let typ = typ.unwrap();
-
-                    context.add_alias(&name, Rc::clone(&typ));
-
-                    return Ok((
-                        tok,
-                        Some(Declaration::UsingAlias(UsingAlias {
-                            name,
-                            typ,
-                            attributes: attrs,
-                        })),
-                    ));
-                }
-                Token::Equal => {
-                    let tdp = TypeDeclaratorParser::new(self.lexer);
-                    let (tok, typ) = tdp.parse(None, None, false, context)?;
-                    let name = name.get_first_name();
-                    let typ = typ.unwrap();
+                    let typ = if let Some(typ) = typ {
+                        typ
+                    } else {
+                        let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+                        return Err(ParserError::InvalidTokenInAlias {
+                            sp: self.lexer.span(),
+                            tok,
+                        });
+                    };
 
                     context.add_alias(&name, Rc::clone(&typ));
 
@@ -234,8 +236,9 @@ impl<'a, L: TLexer> UsingParser<'a, L> {
                         tok,
                         Some(Declaration::UsingAlias(UsingAlias {
                             name,
+                            template: None,
                             typ,
-                            attributes: None,
+                            attributes,
                         })),
                     ));
                 }
@@ -260,6 +263,7 @@ mod tests {
     use super::*;
     use crate::lexer::{preprocessor::context::DefaultContext, Lexer};
     use crate::parser::names::Qualified;
+    use crate::parser::types::{BaseType, Primitive};
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -378,4 +382,59 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn test_using_alias() {
+        let mut l = Lexer::<DefaultContext>::new(b"using T = int");
+        let p = UsingParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, u) = p.parse(None, &mut context).unwrap();
+
+        let alias = if let Some(Declaration::UsingAlias(alias)) = u {
+            alias
+        } else {
+            panic!("Not an alias: {:?}", u);
+        };
+        assert_eq!(alias.name, "T");
+        assert!(alias.template.is_none());
+        assert_eq!(alias.typ.typ.base, BaseType::Primitive(Primitive::Int));
+        assert!(context.search(Some(&mk_id!("T"))).unwrap().is_type());
+    }
+
+    #[test]
+    fn test_using_alias_function_pointer() {
+        let mut l = Lexer::<DefaultContext>::new(b"using F [[deprecated]] = void (*)(int)");
+        let p = UsingParser::new(&mut l);
+        let mut context = Context::default();
+        let (_, u) = p.parse(None, &mut context).unwrap();
+
+        let alias = if let Some(Declaration::UsingAlias(alias)) = u {
+            alias
+        } else {
+            panic!("Not an alias: {:?}", u);
+        };
+        assert_eq!(alias.name, "F");
+        assert_eq!(alias.attributes.unwrap()[0].name, "deprecated");
+
+        let typ = &alias.typ.typ;
+        assert_eq!(typ.pointers.as_ref().unwrap().len(), 1);
+        if let BaseType::Function(f) = &typ.base {
+            assert_eq!(f.params.len(), 1);
+            assert_eq!(
+                f.return_type.as_ref().unwrap().base,
+                BaseType::Primitive(Primitive::Void)
+            );
+        } else {
+            panic!("Not a function: {:?}", typ.base);
+        }
+    }
+
+    #[test]
+    fn test_using_alias_qualified() {
+        let mut l = Lexer::<DefaultContext>::new(b"using A::B = int");
+        let p = UsingParser::new(&mut l);
+        let mut context = Context::default();
+
+        assert!(p.parse(None, &mut context).is_err());
+    }
 }
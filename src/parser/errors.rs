@@ -16,6 +16,7 @@ pub enum ParserError {
     InvalidTokenInAttrs { sp: Span, tok: Token },
     InvalidTokenInAsm { sp: Span, tok: Token },
     InvalidTokenInExtern { sp: Span, tok: Token },
+    InvalidTokenInModule { sp: Span, tok: Token },
     InvalidTokenInParamList { sp: Span, tok: Token },
     InvalidTokenInFuncDecl { sp: Span, tok: Token },
     InvalidTokenInThrow { sp: Span, tok: Token },
@@ -38,16 +39,23 @@ pub enum ParserError {
     InvalidTokenInWhile { sp: Span, tok: Token },
     InvalidTokenInUnit { sp: Span, tok: Token },
     InvalidTokenInExpr { sp: Span, tok: Token },
+    InvalidTokenInDecltype { sp: Span, tok: Token },
+    InvalidTokenInAlignas { sp: Span, tok: Token },
+    InvalidTokenInBinding { sp: Span, tok: Token },
+    InvalidTokenInTemplate { sp: Span, tok: Token },
     InvalidExprInFor { sp: Span },
     InvalidTypeInOp { sp: Span, name: String },
     InvalidTypeInExpr { sp: Span, name: String },
     UnknownId { sp: Span, name: String },
     InvalidArgInStaticAssert { sp: Span },
     UnbalancedAttr { sp: Span, tok: Token },
+    InvalidAttrInDecl { sp: Span, name: String },
     UnexpectedEof { sp: Span },
     InvalidBitfieldSize { sp: Span },
     InvalidCtorInit { sp: Span },
     InvalidCast { sp: Span },
+    InvalidArraySizeInNew { sp: Span },
+    InvalidArrayQualifiers { sp: Span },
     InvalidDeclOrExpr { sp: Span },
 }
 
@@ -80,6 +88,10 @@ impl ParserError {
                 *sp,
                 format!("Invalid token {:?} in extern declaration", tok),
             ),
+            InvalidTokenInModule { sp, tok } => (
+                *sp,
+                format!("Invalid token {:?} in module declaration", tok),
+            ),
             InvalidTokenInFuncDecl { sp, tok } => (
                 *sp,
                 format!("Invalid token {:?} in function declaration", tok),
@@ -145,6 +157,21 @@ impl ParserError {
             InvalidTokenInExpr { sp, tok } => {
                 (*sp, format!("Invalid token {:?} in expression", tok))
             }
+            InvalidTokenInDecltype { sp, tok } => (
+                *sp,
+                format!("Invalid token {:?} in decltype specifier", tok),
+            ),
+            InvalidTokenInAlignas { sp, tok } => {
+                (*sp, format!("Invalid token {:?} in alignas specifier", tok))
+            }
+            InvalidTokenInBinding { sp, tok } => (
+                *sp,
+                format!("Invalid token {:?} in structured binding", tok),
+            ),
+            InvalidTokenInTemplate { sp, tok } => (
+                *sp,
+                format!("Invalid token {:?} in template declaration", tok),
+            ),
             InvalidExprInFor { sp } => (*sp, format!("Invalid expression in for statement")),
             InvalidTypeInOp { sp, name } => {
                 (*sp, format!("Invalid type {} in conversion operator", name))
@@ -152,12 +179,25 @@ impl ParserError {
             InvalidTypeInExpr { sp, name } => (*sp, format!("Invalid type {} in expression", name)),
             UnknownId { sp, name } => (*sp, format!("Unknown identifier {}", name)),
             UnbalancedAttr { sp, tok } => (*sp, format!("Unbalanced {:?} in attriute", tok)),
+            InvalidAttrInDecl { sp, name } => (
+                *sp,
+                format!("Attribute {} cannot be applied to a declaration", name),
+            ),
             UnexpectedEof { sp } => (*sp, format!("Unexpected eof")),
             InvalidBitfieldSize { sp } => (*sp, format!("Invalid bitfield size")),
             InvalidCtorInit { sp } => (*sp, format!("Invalid ctor initializer")),
             InvalidArgInStaticAssert { sp } => (*sp, format!("Invalid argument in static_assert")),
             InvalidCast { sp } => (*sp, format!("Invalid cast")),
             InvalidDeclOrExpr { sp } => (*sp, format!("Invalid declaration or expression")),
+            InvalidArraySizeInNew { sp } => (
+                *sp,
+                "Only the first dimension of an array new can be non-constant".to_string(),
+            ),
+            InvalidArrayQualifiers { sp } => (
+                *sp,
+                "Array qualifiers are only allowed in the outermost dimension of a parameter"
+                    .to_string(),
+            ),
         };
         StringlyError { message, sp }
     }
@@ -0,0 +1,189 @@
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::rc::Rc;
+use termcolor::StandardStreamLock;
+
+use crate::lexer::{TLexer, Token};
+use crate::parser::declarations::TypeDeclaratorParser;
+use crate::parser::dump::Dump;
+use crate::parser::errors::ParserError;
+use crate::parser::expressions::{ExprNode, ParametersParser};
+use crate::parser::types::Type;
+use crate::parser::Context;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypesCompatible {
+    pub typ1: Type,
+    pub typ2: Type,
+}
+
+impl Dump for TypesCompatible {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(
+            self,
+            name,
+            "__builtin_types_compatible_p",
+            prefix,
+            last,
+            stdout,
+            typ1,
+            typ2
+        );
+    }
+}
+
+/// __builtin_expect(cond, expected): a branch prediction hint
+#[derive(Clone, Debug, PartialEq)]
+pub struct BuiltinExpect {
+    pub cond: ExprNode,
+    pub expected: ExprNode,
+}
+
+impl Dump for BuiltinExpect {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(
+            self,
+            name,
+            "__builtin_expect",
+            prefix,
+            last,
+            stdout,
+            cond,
+            expected
+        );
+    }
+}
+
+/// __builtin_constant_p(expr): check if expr is known to be a constant
+#[derive(Clone, Debug, PartialEq)]
+pub struct BuiltinConstantP {
+    pub arg: ExprNode,
+}
+
+impl Dump for BuiltinConstantP {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(
+            self,
+            name,
+            "__builtin_constant_p",
+            prefix,
+            last,
+            stdout,
+            arg
+        );
+    }
+}
+
+pub(crate) fn is_builtin(name: &str) -> bool {
+    matches!(
+        name,
+        "__builtin_types_compatible_p" | "__builtin_expect" | "__builtin_constant_p"
+    )
+}
+
+pub(crate) struct BuiltinParser<'a, L: TLexer> {
+    lexer: &'a mut L,
+}
+
+impl<'a, L: TLexer> BuiltinParser<'a, L> {
+    pub(crate) fn new(lexer: &'a mut L) -> Self {
+        Self { lexer }
+    }
+
+    fn parse_type(&mut self, term: Token, context: &mut Context) -> Result<Type, ParserError> {
+        let tdp = TypeDeclaratorParser::new(self.lexer);
+        let (tok, typ) = tdp.parse(None, None, false, context)?;
+
+        let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        if tok != term {
+            return Err(ParserError::InvalidTokenInExpr {
+                sp: self.lexer.span(),
+                tok,
+            });
+        }
+
+        if let Some(typ) = typ {
+            Ok(Rc::try_unwrap(typ).unwrap().typ)
+        } else {
+            Err(ParserError::InvalidTokenInExpr {
+                sp: self.lexer.span(),
+                tok,
+            })
+        }
+    }
+
+    pub(crate) fn parse(
+        mut self,
+        name: &str,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<ExprNode>), ParserError> {
+        let tok = self.lexer.next_useful();
+        if tok != Token::LeftParen {
+            return Err(ParserError::InvalidTokenInExpr {
+                sp: self.lexer.span(),
+                tok,
+            });
+        }
+
+        match name {
+            "__builtin_types_compatible_p" => {
+                let typ1 = self.parse_type(Token::Comma, context)?;
+                let typ2 = self.parse_type(Token::RightParen, context)?;
+
+                Ok((
+                    None,
+                    Some(ExprNode::TypesCompatible(Box::new(TypesCompatible {
+                        typ1,
+                        typ2,
+                    }))),
+                ))
+            }
+            "__builtin_expect" => {
+                let pp = ParametersParser::new(self.lexer, Token::RightParen);
+                let (_, params) = pp.parse(None, None, context)?;
+                let mut params = params.unwrap_or_default();
+
+                if params.len() != 2 {
+                    return Err(ParserError::InvalidTokenInExpr {
+                        sp: self.lexer.span(),
+                        tok: Token::RightParen,
+                    });
+                }
+
+                let expected = params.pop().unwrap();
+                let cond = params.pop().unwrap();
+
+                Ok((
+                    None,
+                    Some(ExprNode::BuiltinExpect(Box::new(BuiltinExpect {
+                        cond,
+                        expected,
+                    }))),
+                ))
+            }
+            "__builtin_constant_p" => {
+                let pp = ParametersParser::new(self.lexer, Token::RightParen);
+                let (_, params) = pp.parse(None, None, context)?;
+                let mut params = params.unwrap_or_default();
+
+                if params.len() != 1 {
+                    return Err(ParserError::InvalidTokenInExpr {
+                        sp: self.lexer.span(),
+                        tok: Token::RightParen,
+                    });
+                }
+
+                Ok((
+                    None,
+                    Some(ExprNode::BuiltinConstantP(Box::new(BuiltinConstantP {
+                        arg: params.pop().unwrap(),
+                    }))),
+                ))
+            }
+            _ => Ok((Some(tok), None)),
+        }
+    }
+}
@@ -38,6 +38,12 @@ pub struct ReinterpretCast {
     pub arg: ExprNode,
 }
 
+/// typeid(type) or typeid(expr): a type operand is an ExprNode::Type
+#[derive(Clone, Debug, PartialEq)]
+pub struct Typeid {
+    pub arg: ExprNode,
+}
+
 impl Dump for StaticCast {
     fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
         dump_obj!(self, name, "static_cast", prefix, last, stdout, typ, arg);
@@ -71,6 +77,12 @@ impl Dump for ReinterpretCast {
     }
 }
 
+impl Dump for Typeid {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(self, name, "typeid", prefix, last, stdout, arg);
+    }
+}
+
 pub(crate) struct FooCastParser<'a, L: TLexer> {
     lexer: &'a mut L,
 }
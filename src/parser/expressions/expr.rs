@@ -6,14 +6,18 @@
 use std::rc::Rc;
 use termcolor::StandardStreamLock;
 
-use super::casts::{ConstCast, DynamicCast, FooCastParser, ReinterpretCast, StaticCast};
+use super::builtins::{self, BuiltinConstantP, BuiltinExpect, BuiltinParser, TypesCompatible};
+use super::casts::{ConstCast, DynamicCast, FooCastParser, ReinterpretCast, StaticCast, Typeid};
+use super::fold::Fold;
 use super::list::{ListInitialization, ListInitializationParser};
+use super::new::{New, NewParser};
 use super::operator::{BinaryOp, Conditional, Operator, UnaryOp};
 use super::params::{Parameters, ParametersParser};
+use super::requires::{parse_template_args, RequiresExpr, RequiresExprParser};
 use crate::lexer::lexer::{TLexer, Token};
 use crate::parser::context::{Context, SearchResult, TypeToFix};
 use crate::parser::declarations::{
-    DeclOrExpr, DeclOrExprParser, DeclSpecifierParser, TypeDeclarator,
+    DeclOrExpr, DeclOrExprParser, DeclSpecifierParser, DeclSpecifiers, TypeDeclarator,
 };
 use crate::parser::dump::Dump;
 use crate::parser::errors::ParserError;
@@ -39,6 +43,16 @@ impl Dump for Nullptr {
     }
 }
 
+/// GNU __null: the null pointer constant used by glibc's NULL
+#[derive(Clone, Debug, PartialEq)]
+pub struct GnuNull {}
+
+impl Dump for GnuNull {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_str!(name, "__null", prefix, last, stdout);
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct This {}
 
@@ -54,6 +68,44 @@ impl Dump for This {
     }
 }
 
+/// A throw-expression without operand: rethrow the current exception
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rethrow {}
+
+impl Dump for Rethrow {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_str!(name, "throw", prefix, last, stdout);
+    }
+}
+
+/// The number of elements in a pack: sizeof...(Args)
+#[derive(Clone, Debug, PartialEq)]
+pub struct SizeofPack {
+    pub name: String,
+}
+
+impl Dump for SizeofPack {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_str!(
+            name,
+            format!("sizeof...({})", self.name),
+            prefix,
+            last,
+            stdout
+        );
+    }
+}
+
+/// A placeholder for an expression which couldn't be parsed (in recovery mode)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Invalid {}
+
+impl Dump for Invalid {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_str!(name, "<invalid>", prefix, last, stdout);
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum VarDecl {
     Direct(Rc<TypeDeclarator>),
@@ -72,6 +124,32 @@ impl Dump for Variable {
     }
 }
 
+/// A subscript with several indices (C++23) as in a[i, j]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MultiSubscript {
+    pub array: ExprNode,
+    pub indices: Parameters,
+}
+
+impl Dump for MultiSubscript {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(self, name, "[]", prefix, last, stdout, array, indices);
+    }
+}
+
+/// A member template-id as in a.template foo<int>() or p->template bar<T>()
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemplateId {
+    pub name: Qualified,
+    pub args: Parameters,
+}
+
+impl Dump for TemplateId {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(self, name, "template-id", prefix, last, stdout, name, args);
+    }
+}
+
 impl Dump for VarDecl {
     fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
         // TODO: fix me
@@ -99,20 +177,35 @@ pub enum ExprNode {
     Conditional(Box<Conditional>),
     CallExpr(Box<CallExpr>),
     Variable(Box<Variable>),
+    TemplateId(Box<TemplateId>),
+    MultiSubscript(Box<MultiSubscript>),
+    Fold(Box<Fold>),
+    RequiresExpr(Box<RequiresExpr>),
     ListInit(Box<ListInitialization>),
     InitExpr(Box<InitExpr>),
+    CompoundLiteral(Box<CompoundLiteral>),
+    Designator(Box<Designator>),
     Integer(Box<Integer>),
     Float(Box<Float>),
     Char(Box<Char>),
     Str(Box<Str>),
     Bool(Box<Bool>),
     Nullptr(Box<Nullptr>),
+    GnuNull(Box<GnuNull>),
     This(Box<This>),
+    Rethrow(Box<Rethrow>),
+    SizeofPack(Box<SizeofPack>),
+    Invalid(Box<Invalid>),
     Type(Box<Type>),
+    New(Box<New>),
     StaticCast(Box<StaticCast>),
     DynamicCast(Box<DynamicCast>),
     ConstCast(Box<ConstCast>),
     ReinterpretCast(Box<ReinterpretCast>),
+    Typeid(Box<Typeid>),
+    TypesCompatible(Box<TypesCompatible>),
+    BuiltinExpect(Box<BuiltinExpect>),
+    BuiltinConstantP(Box<BuiltinConstantP>),
 }
 
 impl Dump for ExprNode {
@@ -129,20 +222,35 @@ impl Dump for ExprNode {
             Self::Conditional(x) => dump!(x),
             Self::CallExpr(x) => dump!(x),
             Self::Variable(x) => dump!(x),
+            Self::TemplateId(x) => dump!(x),
+            Self::MultiSubscript(x) => dump!(x),
+            Self::Fold(x) => dump!(x),
+            Self::RequiresExpr(x) => dump!(x),
             Self::ListInit(x) => dump!(x),
             Self::InitExpr(x) => dump!(x),
+            Self::CompoundLiteral(x) => dump!(x),
+            Self::Designator(x) => dump!(x),
             Self::Integer(x) => dump!(x),
             Self::Float(x) => dump!(x),
             Self::Char(x) => dump!(x),
             Self::Str(x) => dump!(x),
             Self::Bool(x) => dump!(x),
             Self::Nullptr(x) => dump!(x),
+            Self::GnuNull(x) => dump!(x),
             Self::This(x) => dump!(x),
+            Self::Rethrow(x) => dump!(x),
+            Self::SizeofPack(x) => dump!(x),
+            Self::Invalid(x) => dump!(x),
             Self::Type(x) => dump!(x),
+            Self::New(x) => dump!(x),
             Self::StaticCast(x) => dump!(x),
             Self::DynamicCast(x) => dump!(x),
             Self::ConstCast(x) => dump!(x),
             Self::ReinterpretCast(x) => dump!(x),
+            Self::Typeid(x) => dump!(x),
+            Self::TypesCompatible(x) => dump!(x),
+            Self::BuiltinExpect(x) => dump!(x),
+            Self::BuiltinConstantP(x) => dump!(x),
         }
     }
 }
@@ -171,6 +279,40 @@ impl Dump for InitExpr {
     }
 }
 
+/// A C99 compound literal: (struct S){ .x = 1 }
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompoundLiteral {
+    pub typ: Type,
+    pub list: ListInitialization,
+}
+
+impl Dump for CompoundLiteral {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(
+            self,
+            name,
+            "compound-literal",
+            prefix,
+            last,
+            stdout,
+            typ,
+            list
+        );
+    }
+}
+
+/// The field in a designated initializer: { .x = 1 }
+#[derive(Clone, Debug, PartialEq)]
+pub struct Designator {
+    pub field: String,
+}
+
+impl Dump for Designator {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_str!(name, format!(".{}", self.field), Cyan, prefix, last, stdout);
+    }
+}
+
 #[derive(PartialEq)]
 pub(super) enum LastKind {
     Operator,
@@ -192,7 +334,7 @@ fn precedence(op: Operator) -> (u32, Associativity) {
         ScopeResolution => (1, Associativity::LR),
         PostInc | PostDec | Call | Dot | Arrow | Subscript => (2, Associativity::LR),
         PreInc | PreDec | Plus | Minus | Indirection | AddressOf | AddressOfLabel | Not
-        | BitNeg | Sizeof | New | NewArray | Delete | DeleteArray | CoAwait | Cast => {
+        | BitNeg | Sizeof | Alignof | New | NewArray | Delete | DeleteArray | CoAwait | Cast => {
             (3, Associativity::RL)
         }
         DotIndirection | ArrowIndirection => (4, Associativity::LR),
@@ -306,6 +448,28 @@ impl<'a, L: TLexer> ExpressionParser<'a, L> {
         self.term == tok || (tok == Token::RightParen && !self.is_nested())
     }
 
+    /// Parse the argument of sizeof, alignof or typeid: a type-id or an expression
+    /// (the left parenthesis has already been consumed)
+    fn parse_type_or_expr(
+        &mut self,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, ExprNode), ParserError> {
+        let doep = DeclOrExprParser::new(self.lexer);
+        let (tok, doe) = doep.parse(None, context)?;
+        match doe {
+            // a type-id has no declarator-id: sizeof(int x) is invalid
+            Some(DeclOrExpr::Decl(d)) if d.identifier.identifier.is_none() => {
+                let typ = Rc::try_unwrap(d).map_or_else(|d| d.typ.clone(), |d| d.typ);
+                Ok((tok, ExprNode::Type(Box::new(typ))))
+            }
+            Some(DeclOrExpr::Expr(e)) => Ok((tok, e)),
+            _ => Err(ParserError::InvalidTokenInExpr {
+                sp: self.lexer.span(),
+                tok: tok.unwrap_or_else(|| self.lexer.next_useful()),
+            }),
+        }
+    }
+
     fn handle_id(&mut self, id: String, context: &mut Context) -> Result<Token, ParserError> {
         let qp = QualifiedParser::new(self.lexer);
         let (tk, qual) = qp.parse(None, Some(id), context)?;
@@ -345,6 +509,38 @@ impl<'a, L: TLexer> ExpressionParser<'a, L> {
         Ok(())
     }
 
+    fn parse_member_template(&mut self, context: &mut Context) -> Result<(), ParserError> {
+        let tok = self.lexer.next_useful();
+        let id = if let Token::Identifier(id) = tok {
+            id
+        } else {
+            return Err(ParserError::InvalidTokenInExpr {
+                sp: self.lexer.span(),
+                tok,
+            });
+        };
+
+        let qp = QualifiedParser::new(self.lexer);
+        let (tok, name) = qp.parse(None, Some(id), context)?;
+
+        let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        if tok != Token::Lower {
+            return Err(ParserError::InvalidTokenInExpr {
+                sp: self.lexer.span(),
+                tok,
+            });
+        }
+
+        let args = parse_template_args(self.lexer, context)?;
+
+        self.push_operand(ExprNode::TemplateId(Box::new(TemplateId {
+            name: name.unwrap(),
+            args,
+        })));
+
+        Ok(())
+    }
+
     pub(crate) fn parse_with_id(
         &mut self,
         tok: Option<Token>,
@@ -436,32 +632,98 @@ impl<'a, L: TLexer> ExpressionParser<'a, L> {
                 Token::Sizeof => {
                     let tk = self.lexer.next_useful();
                     if tk == Token::LeftParen {
-                        let doep = DeclOrExprParser::new(self.lexer);
-                        let (_, doe) = doep.parse(None, context)?;
-                        let arg = match doe.unwrap() {
-                            DeclOrExpr::Decl(d) => {
-                                ExprNode::Type(Box::new(Rc::try_unwrap(d).unwrap().typ))
-                            }
-                            DeclOrExpr::Expr(e) => e,
-                        };
+                        let (tk, arg) = self.parse_type_or_expr(context)?;
+                        let tk = tk.unwrap_or_else(|| self.lexer.next_useful());
+                        if tk != Token::RightParen {
+                            return Err(ParserError::InvalidTokenInExpr {
+                                sp: self.lexer.span(),
+                                tok: tk,
+                            });
+                        }
 
                         self.operands.push(ExprNode::UnaryOp(Box::new(UnaryOp {
                             op: Operator::Sizeof,
                             arg,
                         })));
                         self.last = LastKind::Operand;
+                    } else if tk == Token::Ellipsis {
+                        // sizeof ... ( identifier )
+                        let name = match (
+                            self.lexer.next_useful(),
+                            self.lexer.next_useful(),
+                            self.lexer.next_useful(),
+                        ) {
+                            (Token::LeftParen, Token::Identifier(name), Token::RightParen) => name,
+                            (_, _, tok) => {
+                                return Err(ParserError::InvalidTokenInExpr {
+                                    sp: self.lexer.span(),
+                                    tok,
+                                });
+                            }
+                        };
+
+                        self.operands
+                            .push(ExprNode::SizeofPack(Box::new(SizeofPack { name })));
+                        self.last = LastKind::Operand;
                     } else {
                         self.push_operator(Operator::Sizeof);
                         tok = tk;
                         continue;
                     }
                 }
+                Token::Alignof => {
+                    let tk = self.lexer.next_useful();
+                    if tk != Token::LeftParen {
+                        return Err(ParserError::InvalidTokenInExpr {
+                            sp: self.lexer.span(),
+                            tok: tk,
+                        });
+                    }
+
+                    let (tk, arg) = self.parse_type_or_expr(context)?;
+                    let tk = tk.unwrap_or_else(|| self.lexer.next_useful());
+                    if tk != Token::RightParen {
+                        return Err(ParserError::InvalidTokenInExpr {
+                            sp: self.lexer.span(),
+                            tok: tk,
+                        });
+                    }
+
+                    self.operands.push(ExprNode::UnaryOp(Box::new(UnaryOp {
+                        op: Operator::Alignof,
+                        arg,
+                    })));
+                    self.last = LastKind::Operand;
+                }
                 Token::Arrow => {
                     self.push_operator(Operator::Arrow);
                 }
+                Token::Dot if self.last == LastKind::Operator => {
+                    // designated initializer: { .x = 1 }
+                    let tk = self.lexer.next_useful();
+                    if let Token::Identifier(field) = tk {
+                        self.operands
+                            .push(ExprNode::Designator(Box::new(Designator { field })));
+                        self.last = LastKind::Operand;
+                    } else {
+                        return Err(ParserError::InvalidTokenInExpr {
+                            sp: self.lexer.span(),
+                            tok: tk,
+                        });
+                    }
+                }
                 Token::Dot => {
                     self.push_operator(Operator::Dot);
                 }
+                Token::Template
+                    if self.last == LastKind::Operator
+                        && matches!(
+                            self.operators.last(),
+                            Some(Operator::Dot) | Some(Operator::Arrow)
+                        ) =>
+                {
+                    self.parse_member_template(context)?;
+                }
                 Token::Not => {
                     self.push_operator(Operator::Not);
                 }
@@ -506,15 +768,38 @@ impl<'a, L: TLexer> ExpressionParser<'a, L> {
                 Token::LeftBrack => {
                     if self.last == LastKind::Operand {
                         self.flush_with_op(Operator::Subscript);
-                        let mut ep = ExpressionParser::new(self.lexer, Token::RightBrack);
-                        let (tk, expr) = ep.parse(None, context)?;
+                        let mut indices = Vec::new();
+                        let tk = loop {
+                            let mut ep = ExpressionParser::new(self.lexer, Token::RightBrack);
+                            let (tk, expr) = ep.parse(None, context)?;
+                            if let Some(expr) = expr {
+                                indices.push(expr);
+                            } else {
+                                // a[] or a[i, ]
+                                return Err(ParserError::InvalidTokenInExpr {
+                                    sp: self.lexer.span(),
+                                    tok: tk.unwrap_or_else(|| self.lexer.next_useful()),
+                                });
+                            }
+                            if tk != Some(Token::Comma) {
+                                break tk;
+                            }
+                        };
+
                         if let Some(tk) = tk {
                             let array = self.operands.pop().unwrap();
-                            self.operands.push(ExprNode::BinaryOp(Box::new(BinaryOp {
-                                op: Operator::Subscript,
-                                arg1: array,
-                                arg2: expr.unwrap(),
-                            })));
+                            if indices.len() == 1 {
+                                self.operands.push(ExprNode::BinaryOp(Box::new(BinaryOp {
+                                    op: Operator::Subscript,
+                                    arg1: array,
+                                    arg2: indices.pop().unwrap(),
+                                })));
+                            } else {
+                                // C++23: a[i, j]
+                                self.operands.push(ExprNode::MultiSubscript(Box::new(
+                                    MultiSubscript { array, indices },
+                                )));
+                            }
                             if tk == Token::DoubleRightBrack {
                                 tok = Token::RightBrack;
                                 continue;
@@ -663,6 +948,9 @@ impl<'a, L: TLexer> ExpressionParser<'a, L> {
                     self.operands.push(left);
                     self.last = LastKind::Operator;
                 }
+                Token::Ellipsis if self.last == LastKind::Operator && self.is_nested() => {
+                    self.parse_fold(context)?;
+                }
                 Token::LeftParen => {
                     if self.last == LastKind::Operand {
                         // We've a call
@@ -692,6 +980,23 @@ impl<'a, L: TLexer> ExpressionParser<'a, L> {
                             base,
                             list: list.unwrap(),
                         })));
+                    } else if self.operators.last() == Some(&Operator::Cast) {
+                        // (T){...}: a cast can't be applied to a braced list so it's a compound literal
+                        self.operators.pop();
+                        let typ = match self.operands.pop() {
+                            Some(ExprNode::Type(typ)) => *typ,
+                            _ => {
+                                return Err(ParserError::InvalidTokenInExpr {
+                                    sp: self.lexer.span(),
+                                    tok: Token::LeftBrace,
+                                });
+                            }
+                        };
+                        self.operands
+                            .push(ExprNode::CompoundLiteral(Box::new(CompoundLiteral {
+                                typ,
+                                list: list.unwrap(),
+                            })));
                     } else {
                         // Initializer-list
                         self.operands
@@ -700,6 +1005,12 @@ impl<'a, L: TLexer> ExpressionParser<'a, L> {
                     self.last = LastKind::Operand;
                 }
                 Token::Comma => {
+                    if (self.is_terminal(Token::Greater) || self.is_terminal(Token::RightBrack))
+                        && !self.is_nested()
+                    {
+                        // comma separating template parameters or subscript indices
+                        return Ok((Some(tok), self.get_node()));
+                    }
                     self.push_operator(Operator::Comma);
                 }
                 Token::RightParen => {
@@ -783,8 +1094,15 @@ impl<'a, L: TLexer> ExpressionParser<'a, L> {
                     continue;
                 }
                 Token::Identifier(id) => {
-                    tok = self.handle_id(id, context)?;
-                    continue;
+                    if builtins::is_builtin(&id) {
+                        let bp = BuiltinParser::new(self.lexer);
+                        let (_, node) = bp.parse(&id, context)?;
+
+                        self.push_operand(node.unwrap());
+                    } else {
+                        tok = self.handle_id(id, context)?;
+                        continue;
+                    }
                 }
                 Token::LiteralChar(x) => {
                     self.operands.push(ExprNode::Char(Box::new(Char {
@@ -906,6 +1224,18 @@ impl<'a, L: TLexer> ExpressionParser<'a, L> {
                     })));
                     self.last = LastKind::Operand;
                 }
+                Token::LiteralSize(x) => {
+                    self.operands.push(ExprNode::Integer(Box::new(Integer {
+                        value: IntLiteral::Size(x),
+                    })));
+                    self.last = LastKind::Operand;
+                }
+                Token::LiteralUSize(x) => {
+                    self.operands.push(ExprNode::Integer(Box::new(Integer {
+                        value: IntLiteral::USize(x),
+                    })));
+                    self.last = LastKind::Operand;
+                }
                 Token::LiteralString(x) => {
                     tok = str_literal!(x, Str);
                     continue;
@@ -990,12 +1320,33 @@ impl<'a, L: TLexer> ExpressionParser<'a, L> {
                     self.operands.push(ExprNode::Nullptr(Box::new(Nullptr {})));
                     self.last = LastKind::Operand;
                 }
+                Token::GnuNull => {
+                    self.operands.push(ExprNode::GnuNull(Box::new(GnuNull {})));
+                    self.last = LastKind::Operand;
+                }
                 Token::This => {
                     self.operands.push(ExprNode::This(Box::new(This {})));
                     self.last = LastKind::Operand;
                 }
                 Token::Throw => {
-                    self.push_operator(Operator::Throw);
+                    let tk = self.lexer.next_useful();
+                    match tk {
+                        Token::SemiColon
+                        | Token::Colon
+                        | Token::Comma
+                        | Token::RightParen
+                        | Token::RightBrack
+                        | Token::RightBrace
+                        | Token::Eof => {
+                            self.operands.push(ExprNode::Rethrow(Box::new(Rethrow {})));
+                            self.last = LastKind::Operand;
+                        }
+                        _ => {
+                            self.push_operator(Operator::Throw);
+                        }
+                    }
+                    tok = tk;
+                    continue;
                 }
                 Token::CoAwait => {
                     self.push_operator(Operator::CoAwait);
@@ -1022,9 +1373,55 @@ impl<'a, L: TLexer> ExpressionParser<'a, L> {
 
                     self.push_operand(node.unwrap());
                 }
+                Token::ColonColon if self.last == LastKind::Operator => {
+                    // ::new T: the global allocation function is used
+                    // (::delete and the other ::names are not handled here)
+                    let tk = self.lexer.next_useful();
+                    if tk != Token::New {
+                        return Err(ParserError::InvalidTokenInExpr {
+                            sp: self.lexer.span(),
+                            tok: tk,
+                        });
+                    }
+                    tok = tk;
+                    continue;
+                }
+                Token::New => {
+                    let np = NewParser::new(self.lexer);
+                    let (tk, new) = np.parse(context)?;
+
+                    self.push_operand(ExprNode::New(Box::new(new.unwrap())));
+                    tok = tk.unwrap_or_else(|| self.lexer.next_useful());
+                    continue;
+                }
+                Token::Requires => {
+                    let rep = RequiresExprParser::new(self.lexer);
+                    let (_, req) = rep.parse(None, context)?;
+                    self.push_operand(ExprNode::RequiresExpr(Box::new(req.unwrap())));
+                }
+                Token::Typeid => {
+                    let tk = self.lexer.next_useful();
+                    if tk != Token::LeftParen {
+                        return Err(ParserError::InvalidTokenInExpr {
+                            sp: self.lexer.span(),
+                            tok: tk,
+                        });
+                    }
+
+                    let (tk, arg) = self.parse_type_or_expr(context)?;
+                    let tk = tk.unwrap_or_else(|| self.lexer.next_useful());
+                    if tk != Token::RightParen {
+                        return Err(ParserError::InvalidTokenInExpr {
+                            sp: self.lexer.span(),
+                            tok: tk,
+                        });
+                    }
+
+                    self.push_operand(ExprNode::Typeid(Box::new(Typeid { arg })));
+                }
                 _ => {
                     let dsp = DeclSpecifierParser::new(self.lexer);
-                    let (tk, (_, typ, _, _)) = dsp.parse(Some(tok), None, context)?;
+                    let (tk, DeclSpecifiers { typ, .. }) = dsp.parse(Some(tok), None, context)?;
 
                     if let Some(typ) = typ {
                         self.operands.push(ExprNode::Type(Box::new(typ)));
@@ -1200,6 +1597,61 @@ mod tests {
         assert_eq!(node, expected);
     }
 
+    #[test]
+    fn test_alignof_double() {
+        let mut lexer = Lexer::<DefaultContext>::new(b"alignof(double)");
+        let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+        let mut context = Context::default();
+        let node = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let expected = node!(UnaryOp {
+            op: Operator::Alignof,
+            arg: ExprNode::Type(Box::new(Type {
+                base: BaseType::Primitive(Primitive::Double),
+                cv: CVQualifier::empty(),
+                pointers: None,
+            })),
+        });
+
+        assert_eq!(node, expected);
+    }
+
+    #[test]
+    fn test_alignof_empty() {
+        let mut lexer = Lexer::<DefaultContext>::new(b"alignof()");
+        let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+        let mut context = Context::default();
+
+        assert!(parser.parse(None, &mut context).is_err());
+    }
+
+    #[test]
+    fn test_type_or_expr_unclosed() {
+        for code in [
+            &b"typeid(int x;"[..],
+            b"typeid(int]",
+            b"sizeof(int]",
+            b"alignof(int x)",
+        ] {
+            let mut lexer = Lexer::<DefaultContext>::new(code);
+            let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+            let mut context = Context::default();
+
+            assert!(parser.parse(None, &mut context).is_err());
+        }
+    }
+
+    #[test]
+    fn test_sizeof_typeid_empty() {
+        for s in &["sizeof()", "typeid()"] {
+            let mut lexer = Lexer::<DefaultContext>::new(s.as_bytes());
+            let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+            let mut context = Context::default();
+
+            assert!(parser.parse(None, &mut context).is_err(), "{}", s);
+        }
+    }
+
     #[test]
     fn test_sizeof_expr_no_par() {
         let mut lexer = Lexer::<DefaultContext>::new(b"sizeof x + y");
@@ -1237,7 +1689,9 @@ mod tests {
                 attributes: None,
             },
             init: None,
+            align: None,
             bitfield_size: None,
+            explicit_spec: None,
         });
         context.add_type_decl(Rc::clone(&x));
 
@@ -1261,6 +1715,36 @@ mod tests {
         assert_eq!(node, expected);
     }
 
+    #[test]
+    fn test_sizeof_pack() {
+        let mut lexer = Lexer::<DefaultContext>::new(b"sizeof...(Ts) + sizeof ... ( Args )");
+        let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+        let mut context = Context::default();
+        let node = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let expected = node!(BinaryOp {
+            op: Operator::Add,
+            arg1: node!(SizeofPack {
+                name: "Ts".to_string(),
+            }),
+            arg2: node!(SizeofPack {
+                name: "Args".to_string(),
+            }),
+        });
+
+        assert_eq!(node, expected);
+
+        let mut lexer = Lexer::<DefaultContext>::new(b"sizeof(T)");
+        let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+        let node = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        assert!(matches!(node, ExprNode::UnaryOp(ref op) if op.op == Operator::Sizeof));
+
+        let mut lexer = Lexer::<DefaultContext>::new(b"sizeof...(1)");
+        let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+        assert!(parser.parse(None, &mut context).is_err());
+    }
+
     #[test]
     fn test_array() {
         let mut lexer = Lexer::<DefaultContext>::new(b"abc[x]");
@@ -1277,6 +1761,46 @@ mod tests {
         assert_eq!(node, expected);
     }
 
+    #[test]
+    fn test_array_multi() {
+        let mut lexer = Lexer::<DefaultContext>::new(b"a[i, j] + a[(i, j)]");
+        let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+        let mut context = Context::default();
+        let node = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let expected = node!(BinaryOp {
+            op: Operator::Add,
+            arg1: node!(MultiSubscript {
+                array: ExprNode::Variable(Box::new(mk_var!("a"))),
+                indices: vec![
+                    ExprNode::Variable(Box::new(mk_var!("i"))),
+                    ExprNode::Variable(Box::new(mk_var!("j"))),
+                ],
+            }),
+            arg2: node!(BinaryOp {
+                op: Operator::Subscript,
+                arg1: ExprNode::Variable(Box::new(mk_var!("a"))),
+                arg2: node!(BinaryOp {
+                    op: Operator::Comma,
+                    arg1: ExprNode::Variable(Box::new(mk_var!("i"))),
+                    arg2: ExprNode::Variable(Box::new(mk_var!("j"))),
+                }),
+            }),
+        });
+
+        assert_eq!(node, expected);
+    }
+
+    #[test]
+    fn test_array_empty() {
+        for s in &["a[]", "a[i, ]", "a[, j]"] {
+            let mut lexer = Lexer::<DefaultContext>::new(s.as_bytes());
+            let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+            let mut context = Context::default();
+            assert!(parser.parse(None, &mut context).is_err(), "{}", s);
+        }
+    }
+
     #[test]
     fn test_array1() {
         let mut lexer = Lexer::<DefaultContext>::new(b"a[b[x]]");
@@ -1372,6 +1896,90 @@ mod tests {
         assert_eq!(node, expected);
     }
 
+    #[test]
+    fn test_member_template() {
+        let mut lexer =
+            Lexer::<DefaultContext>::new(b"a.template foo<int>() + p->template bar<int, 2>(c)");
+        let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+        let mut context = Context::default();
+        let node = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let int = ExprNode::Type(Box::new(Type {
+            base: BaseType::Primitive(Primitive::Int),
+            cv: CVQualifier::empty(),
+            pointers: None,
+        }));
+        let expected = node!(BinaryOp {
+            op: Operator::Add,
+            arg1: node!(CallExpr {
+                callee: node!(BinaryOp {
+                    op: Operator::Dot,
+                    arg1: ExprNode::Variable(Box::new(mk_var!("a"))),
+                    arg2: node!(TemplateId {
+                        name: mk_id!("foo"),
+                        args: vec![int.clone()],
+                    }),
+                }),
+                params: vec![],
+            }),
+            arg2: node!(CallExpr {
+                callee: node!(BinaryOp {
+                    op: Operator::Arrow,
+                    arg1: ExprNode::Variable(Box::new(mk_var!("p"))),
+                    arg2: node!(TemplateId {
+                        name: mk_id!("bar"),
+                        args: vec![
+                            int,
+                            ExprNode::Integer(Box::new(Integer {
+                                value: IntLiteral::Int(2),
+                            })),
+                        ],
+                    }),
+                }),
+                params: vec![ExprNode::Variable(Box::new(mk_var!("c"))),],
+            }),
+        });
+
+        assert_eq!(node, expected);
+    }
+
+    #[test]
+    fn test_member_template_ptr_args() {
+        let mut lexer = Lexer::<DefaultContext>::new(b"a.template foo<int*, const char*>()");
+        let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+        let mut context = Context::default();
+        let node = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let ptr = |base, cv| {
+            ExprNode::Type(Box::new(Type {
+                base: BaseType::Primitive(base),
+                cv,
+                pointers: Some(vec![Pointer {
+                    kind: PtrKind::Pointer,
+                    attributes: None,
+                    cv: CVQualifier::empty(),
+                    ms: MSModifier::empty(),
+                }]),
+            }))
+        };
+        let expected = node!(CallExpr {
+            callee: node!(BinaryOp {
+                op: Operator::Dot,
+                arg1: ExprNode::Variable(Box::new(mk_var!("a"))),
+                arg2: node!(TemplateId {
+                    name: mk_id!("foo"),
+                    args: vec![
+                        ptr(Primitive::Int, CVQualifier::empty()),
+                        ptr(Primitive::Char, CVQualifier::CONST),
+                    ],
+                }),
+            }),
+            params: vec![],
+        });
+
+        assert_eq!(node, expected);
+    }
+
     #[test]
     fn test_pre_post_inc() {
         let mut lexer = Lexer::<DefaultContext>::new(b"++a++");
@@ -1424,6 +2032,52 @@ mod tests {
         assert_eq!(node, expected);
     }
 
+    #[test]
+    fn test_question_throw() {
+        let mut lexer = Lexer::<DefaultContext>::new(b"x ? y : throw e");
+        let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+        let mut context = Context::default();
+        let node = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let expected = node!(Conditional {
+            condition: ExprNode::Variable(Box::new(mk_var!("x"))),
+            left: ExprNode::Variable(Box::new(mk_var!("y"))),
+            right: node!(UnaryOp {
+                op: Operator::Throw,
+                arg: ExprNode::Variable(Box::new(mk_var!("e"))),
+            }),
+        });
+
+        assert_eq!(node, expected);
+
+        let mut lexer = Lexer::<DefaultContext>::new(b"x ? throw e : y");
+        let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+        let node = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let expected = node!(Conditional {
+            condition: ExprNode::Variable(Box::new(mk_var!("x"))),
+            left: node!(UnaryOp {
+                op: Operator::Throw,
+                arg: ExprNode::Variable(Box::new(mk_var!("e"))),
+            }),
+            right: ExprNode::Variable(Box::new(mk_var!("y"))),
+        });
+
+        assert_eq!(node, expected);
+
+        let mut lexer = Lexer::<DefaultContext>::new(b"x ? y : throw");
+        let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+        let node = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let expected = node!(Conditional {
+            condition: ExprNode::Variable(Box::new(mk_var!("x"))),
+            left: ExprNode::Variable(Box::new(mk_var!("y"))),
+            right: ExprNode::Rethrow(Box::new(Rethrow {})),
+        });
+
+        assert_eq!(node, expected);
+    }
+
     #[test]
     fn test_question_2() {
         let mut lexer = Lexer::<DefaultContext>::new(b"a ? (void)b,c : d");
@@ -1561,4 +2215,114 @@ mod tests {
 
         assert_eq!(node, expected);
     }
+
+    #[test]
+    fn test_typeid() {
+        let mut lexer = Lexer::<DefaultContext>::new(b"typeid(const int *) == typeid(1 + 2)");
+        let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+        let mut context = Context::default();
+        let node = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let expected = node!(BinaryOp {
+            op: Operator::Eq,
+            arg1: node!(Typeid {
+                arg: ExprNode::Type(Box::new(Type {
+                    base: BaseType::Primitive(Primitive::Int),
+                    cv: CVQualifier::CONST,
+                    pointers: Some(vec![Pointer {
+                        kind: PtrKind::Pointer,
+                        attributes: None,
+                        cv: CVQualifier::empty(),
+                        ms: MSModifier::empty(),
+                    }]),
+                })),
+            }),
+            arg2: node!(Typeid {
+                arg: node!(BinaryOp {
+                    op: Operator::Add,
+                    arg1: ExprNode::Integer(Box::new(Integer {
+                        value: IntLiteral::Int(1),
+                    })),
+                    arg2: ExprNode::Integer(Box::new(Integer {
+                        value: IntLiteral::Int(2),
+                    })),
+                }),
+            }),
+        });
+
+        assert_eq!(node, expected);
+    }
+
+    #[test]
+    fn test_builtin_types_compatible() {
+        let mut lexer =
+            Lexer::<DefaultContext>::new(b"__builtin_types_compatible_p(int, long) + 1");
+        let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+        let mut context = Context::default();
+        let node = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let expected = node!(BinaryOp {
+            op: Operator::Add,
+            arg1: node!(TypesCompatible {
+                typ1: Type {
+                    base: BaseType::Primitive(Primitive::Int),
+                    cv: CVQualifier::empty(),
+                    pointers: None,
+                },
+                typ2: Type {
+                    base: BaseType::Primitive(Primitive::Long),
+                    cv: CVQualifier::empty(),
+                    pointers: None,
+                },
+            }),
+            arg2: ExprNode::Integer(Box::new(Integer {
+                value: IntLiteral::Int(1),
+            })),
+        });
+
+        assert_eq!(node, expected);
+    }
+
+    #[test]
+    fn test_co_await_member_call() {
+        let mut lexer = Lexer::<DefaultContext>::new(b"co_await obj.method()");
+        let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+        let mut context = Context::default();
+        let node = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let expected = node!(UnaryOp {
+            op: Operator::CoAwait,
+            arg: node!(CallExpr {
+                callee: node!(BinaryOp {
+                    op: Operator::Dot,
+                    arg1: ExprNode::Variable(Box::new(mk_var!("obj"))),
+                    arg2: ExprNode::Variable(Box::new(mk_var!("method"))),
+                }),
+                params: vec![],
+            }),
+        });
+
+        assert_eq!(node, expected);
+    }
+
+    #[test]
+    fn test_co_await_paren_plus() {
+        let mut lexer = Lexer::<DefaultContext>::new(b"(co_await x) + 1");
+        let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+        let mut context = Context::default();
+        let node = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let expected = node!(BinaryOp {
+            op: Operator::Add,
+            arg1: node!(UnaryOp {
+                op: Operator::CoAwait,
+                arg: ExprNode::Variable(Box::new(mk_var!("x"))),
+            }),
+            arg2: ExprNode::Integer(Box::new(Integer {
+                value: IntLiteral::Int(1)
+            })),
+        });
+
+        assert_eq!(node, expected);
+    }
 }
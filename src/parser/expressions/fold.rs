@@ -0,0 +1,265 @@
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use termcolor::StandardStreamLock;
+
+use super::expr::{ExprNode, ExpressionParser, LastKind};
+use super::operator::Operator;
+use crate::lexer::{TLexer, Token};
+use crate::parser::dump::Dump;
+use crate::parser::errors::ParserError;
+use crate::parser::Context;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FoldKind {
+    /// (... op E)
+    Left,
+    /// (E op ...)
+    Right,
+    /// (E1 op ... op E2)
+    Binary,
+}
+
+impl FoldKind {
+    fn to_str(self) -> &'static str {
+        match self {
+            Self::Left => "left",
+            Self::Right => "right",
+            Self::Binary => "binary",
+        }
+    }
+}
+
+impl Dump for FoldKind {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_str!(name, self.to_str(), Cyan, prefix, last, stdout);
+    }
+}
+
+/// A fold expression: the missing side of a unary fold is the ellipsis
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fold {
+    pub op: Operator,
+    pub kind: FoldKind,
+    pub lhs: Option<ExprNode>,
+    pub rhs: Option<ExprNode>,
+}
+
+impl Dump for Fold {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        let prefix = dump_start!(
+            name,
+            format!("fold({})", self.op.to_str()),
+            prefix,
+            last,
+            stdout
+        );
+        dump_fields!(self, prefix, stdout, kind, lhs, rhs);
+    }
+}
+
+fn fold_op(tok: &Token) -> Option<Operator> {
+    Some(match tok {
+        Token::Plus => Operator::Add,
+        Token::Minus => Operator::Sub,
+        Token::Star => Operator::Mul,
+        Token::Divide => Operator::Div,
+        Token::Modulo => Operator::Mod,
+        Token::Xor => Operator::BitXor,
+        Token::And => Operator::BitAnd,
+        Token::Or => Operator::BitOr,
+        Token::Equal => Operator::Assign,
+        Token::PlusEqual => Operator::AddAssign,
+        Token::MinusEqual => Operator::SubAssign,
+        Token::StarEqual => Operator::MulAssign,
+        Token::DivideEqual => Operator::DivAssign,
+        Token::ModuloEqual => Operator::ModAssign,
+        Token::XorEqual => Operator::XorAssign,
+        Token::AndEqual => Operator::AndAssign,
+        Token::OrEqual => Operator::OrAssign,
+        Token::LeftShiftEqual => Operator::LShiftAssign,
+        Token::RightShiftEqual => Operator::RShiftAssign,
+        Token::EqualEqual => Operator::Eq,
+        Token::NotEqual => Operator::Neq,
+        Token::Lower => Operator::Lt,
+        Token::Greater => Operator::Gt,
+        Token::LowerEqual => Operator::Leq,
+        Token::GreaterEqual => Operator::Geq,
+        Token::AndAnd => Operator::And,
+        Token::OrOr => Operator::Or,
+        Token::Comma => Operator::Comma,
+        Token::DotStar => Operator::DotIndirection,
+        Token::ArrowStar => Operator::ArrowIndirection,
+        Token::LeftShift => Operator::LShift,
+        Token::RightShift => Operator::RShift,
+        _ => {
+            return None;
+        }
+    })
+}
+
+impl<'a, L: TLexer> ExpressionParser<'a, L> {
+    /// Parse the end of the fold when an ellipsis is found in a parenthesized expression
+    pub(super) fn parse_fold(&mut self, context: &mut Context) -> Result<(), ParserError> {
+        let (op, kind, lhs, rhs) = if self.operators.last() == Some(&Operator::Parenthesis) {
+            // (... op E)
+            let tok = self.lexer.next_useful();
+            let op = if let Some(op) = fold_op(&tok) {
+                op
+            } else {
+                return Err(ParserError::InvalidTokenInExpr {
+                    sp: self.lexer.span(),
+                    tok,
+                });
+            };
+
+            let rhs = self.parse_fold_operand(context)?;
+            (op, FoldKind::Left, None, Some(rhs))
+        } else {
+            // (E op ... or (E op ... op E)
+            let op = self.operators.pop().unwrap();
+            while let Some(top) = self.operators.last() {
+                if *top == Operator::Parenthesis {
+                    break;
+                }
+                self.operators.pop().unwrap().operate(&mut self.operands);
+            }
+            let lhs = self.operands.pop();
+
+            let tok = self.lexer.next_useful();
+            if tok == Token::RightParen {
+                (op, FoldKind::Right, lhs, None)
+            } else if fold_op(&tok) == Some(op) {
+                let rhs = self.parse_fold_operand(context)?;
+                (op, FoldKind::Binary, lhs, Some(rhs))
+            } else {
+                return Err(ParserError::InvalidTokenInExpr {
+                    sp: self.lexer.span(),
+                    tok,
+                });
+            }
+        };
+
+        // The closing parenthesis has been consumed
+        self.operators.pop();
+        self.level -= 1;
+        self.operands
+            .push(ExprNode::Fold(Box::new(Fold { op, kind, lhs, rhs })));
+        self.last = LastKind::Operand;
+
+        Ok(())
+    }
+
+    fn parse_fold_operand(&mut self, context: &mut Context) -> Result<ExprNode, ParserError> {
+        let mut ep = ExpressionParser::new(self.lexer, Token::RightParen);
+        let (tok, expr) = ep.parse(None, context)?;
+
+        let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        match (tok, expr) {
+            (Token::RightParen, Some(expr)) => Ok(expr),
+            (tok, _) => Err(ParserError::InvalidTokenInExpr {
+                sp: self.lexer.span(),
+                tok,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lexer::preprocessor::context::DefaultContext;
+    use crate::lexer::Lexer;
+    use crate::parser::expressions::*;
+    use crate::parser::literals::{Bool, IntLiteral, Integer};
+    use crate::parser::names::Qualified;
+    use pretty_assertions::assert_eq;
+
+    fn parse(s: &[u8]) -> ExprNode {
+        let mut lexer = Lexer::<DefaultContext>::new(s);
+        let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+        let mut context = Context::default();
+        parser.parse(None, &mut context).unwrap().1.unwrap()
+    }
+
+    #[test]
+    fn test_fold_unary_left() {
+        assert_eq!(
+            parse(b"(... + args)"),
+            node!(Fold {
+                op: Operator::Add,
+                kind: FoldKind::Left,
+                lhs: None,
+                rhs: Some(ExprNode::Variable(Box::new(mk_var!("args")))),
+            })
+        );
+        assert_eq!(
+            parse(b"(... && (args > 0))"),
+            node!(Fold {
+                op: Operator::And,
+                kind: FoldKind::Left,
+                lhs: None,
+                rhs: Some(node!(BinaryOp {
+                    op: Operator::Gt,
+                    arg1: ExprNode::Variable(Box::new(mk_var!("args"))),
+                    arg2: ExprNode::Integer(Box::new(Integer {
+                        value: IntLiteral::Int(0),
+                    })),
+                })),
+            })
+        );
+    }
+
+    #[test]
+    fn test_fold_unary_right() {
+        assert_eq!(
+            parse(b"(args + ...) * 2"),
+            node!(BinaryOp {
+                op: Operator::Mul,
+                arg1: node!(Fold {
+                    op: Operator::Add,
+                    kind: FoldKind::Right,
+                    lhs: Some(ExprNode::Variable(Box::new(mk_var!("args")))),
+                    rhs: None,
+                }),
+                arg2: ExprNode::Integer(Box::new(Integer {
+                    value: IntLiteral::Int(2),
+                })),
+            })
+        );
+        assert_eq!(
+            parse(b"(args && ...)"),
+            node!(Fold {
+                op: Operator::And,
+                kind: FoldKind::Right,
+                lhs: Some(ExprNode::Variable(Box::new(mk_var!("args")))),
+                rhs: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_fold_binary() {
+        assert_eq!(
+            parse(b"(init + ... + args)"),
+            node!(Fold {
+                op: Operator::Add,
+                kind: FoldKind::Binary,
+                lhs: Some(ExprNode::Variable(Box::new(mk_var!("init")))),
+                rhs: Some(ExprNode::Variable(Box::new(mk_var!("args")))),
+            })
+        );
+        assert_eq!(
+            parse(b"(args && ... && true)"),
+            node!(Fold {
+                op: Operator::And,
+                kind: FoldKind::Binary,
+                lhs: Some(ExprNode::Variable(Box::new(mk_var!("args")))),
+                rhs: Some(ExprNode::Bool(Box::new(Bool { value: true }))),
+            })
+        );
+    }
+}
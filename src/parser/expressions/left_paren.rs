@@ -11,8 +11,8 @@ use super::params::ParametersParser;
 use crate::lexer::{TLexer, Token};
 use crate::parser::context::{Context, SearchResult, TypeToFix};
 use crate::parser::declarations::{
-    DeclHint, NoPtrDeclaratorParser, PointerDeclaratorParser, PtrKind, Specifier, TypeDeclarator,
-    TypeDeclaratorParser,
+    check_array_qualifiers, DeclHint, NoPtrDeclaratorParser, PointerDeclaratorParser, PtrKind,
+    Specifier, TypeDeclarator, TypeDeclaratorParser,
 };
 use crate::parser::errors::ParserError;
 use crate::parser::name::QualifiedParser;
@@ -116,6 +116,7 @@ impl<'a, L: TLexer> ExpressionParser<'a, L> {
         };
         let (tok, decl, _, _) = npdp.parse(None, typ, Specifier::empty(), false, false, context)?;
         let mut typ = decl.unwrap().typ;
+        check_array_qualifiers(&typ, self.lexer.span())?;
         typ.pointers = Some(pointers);
 
         let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
@@ -262,6 +263,7 @@ impl<'a, L: TLexer> ExpressionParser<'a, L> {
             }
             tok => {
                 self.operators.push(Operator::Parenthesis);
+                self.level += 1;
                 self.last = LastKind::Operator;
 
                 Ok(Some(tok))
@@ -281,6 +283,7 @@ mod tests {
     use crate::lexer::Lexer;
     use crate::parser::declarations::*;
     use crate::parser::expressions::*;
+    use crate::parser::literals::{IntLiteral, Integer};
     use crate::parser::names::Qualified;
     use crate::parser::types::Primitive;
     use pretty_assertions::assert_eq;
@@ -367,7 +370,9 @@ mod tests {
                 attributes: None,
             },
             init: None,
+            align: None,
             bitfield_size: None,
+            explicit_spec: None,
         });
         context.add_type_decl(Rc::clone(&t));
 
@@ -424,7 +429,9 @@ mod tests {
                                 attributes: None
                             },
                             init: None,
+                            align: None,
                             bitfield_size: None,
+                            explicit_spec: None,
                         }),
                     }],
                     cv: CVQualifier::empty(),
@@ -436,7 +443,8 @@ mod tests {
                     status: FunStatus::None,
                     requires: None,
                     ctor_init: None,
-                    body: RefCell::new(None)
+                    body: RefCell::new(None),
+                    handlers: RefCell::new(None)
                 })),
                 cv: CVQualifier::empty(),
                 pointers: Some(vec![Pointer {
@@ -469,7 +477,9 @@ mod tests {
                 attributes: None,
             },
             init: None,
+            align: None,
             bitfield_size: None,
+            explicit_spec: None,
         });
         context.add_type_decl(Rc::clone(&t));
 
@@ -501,7 +511,9 @@ mod tests {
                                 attributes: None
                             },
                             init: None,
+                            align: None,
                             bitfield_size: None,
+                            explicit_spec: None,
                         }),
                     }],
                     cv: CVQualifier::empty(),
@@ -513,7 +525,8 @@ mod tests {
                     status: FunStatus::None,
                     requires: None,
                     ctor_init: None,
-                    body: RefCell::new(None)
+                    body: RefCell::new(None),
+                    handlers: RefCell::new(None)
                 })),
                 cv: CVQualifier::empty(),
                 pointers: Some(vec![Pointer {
@@ -618,4 +631,74 @@ mod tests {
 
         assert_eq!(node, expected);
     }
+
+    #[test]
+    fn test_compound_literal_struct() {
+        let mut lexer = Lexer::<DefaultContext>::new(b"(struct S){.x = 1}");
+        let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+        let mut context = Context::default();
+        let node = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let cl = match node {
+            ExprNode::CompoundLiteral(cl) => cl,
+            _ => panic!("Not a compound literal"),
+        };
+        match &cl.typ.base {
+            BaseType::Class(c) => {
+                assert_eq!(c.name, Some(mk_id!("S")));
+                assert!(c.body.is_none());
+            }
+            _ => panic!("Not a struct"),
+        }
+        assert_eq!(
+            cl.list,
+            vec![node!(BinaryOp {
+                op: Operator::Assign,
+                arg1: node!(Designator {
+                    field: "x".to_string(),
+                }),
+                arg2: ExprNode::Integer(Box::new(Integer {
+                    value: IntLiteral::Int(1),
+                })),
+            })]
+        );
+    }
+
+    #[test]
+    fn test_compound_literal_array() {
+        let mut lexer = Lexer::<DefaultContext>::new(b"(int[]){1, 2}");
+        let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+        let mut context = Context::default();
+        let node = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let expected = node!(CompoundLiteral {
+            typ: Type {
+                base: BaseType::Array(Box::new(Array {
+                    base: Some(Type {
+                        base: BaseType::Primitive(Primitive::Int),
+                        cv: CVQualifier::empty(),
+                        pointers: None,
+                    }),
+                    dimensions: vec![Dimension {
+                        size: None,
+                        attributes: None,
+                        is_static: false,
+                        cv: CVQualifier::empty(),
+                    }],
+                })),
+                cv: CVQualifier::empty(),
+                pointers: None,
+            },
+            list: vec![
+                ExprNode::Integer(Box::new(Integer {
+                    value: IntLiteral::Int(1),
+                })),
+                ExprNode::Integer(Box::new(Integer {
+                    value: IntLiteral::Int(2),
+                })),
+            ],
+        });
+
+        assert_eq!(node, expected);
+    }
 }
@@ -21,3 +21,15 @@ pub use self::left_paren::*;
 
 pub mod casts;
 pub use self::casts::*;
+
+pub mod new;
+pub use self::new::*;
+
+pub mod builtins;
+pub use self::builtins::*;
+
+pub mod fold;
+pub use self::fold::*;
+
+pub mod requires;
+pub use self::requires::*;
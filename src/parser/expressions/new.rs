@@ -0,0 +1,334 @@
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::rc::Rc;
+use termcolor::StandardStreamLock;
+
+use crate::lexer::{TLexer, Token};
+use crate::parser::declarations::{
+    ArrayParser, DeclSpecifierParser, DeclSpecifiers, PointerDeclaratorParser, Specifier,
+    TypeDeclaratorParser,
+};
+use crate::parser::dump::Dump;
+use crate::parser::errors::ParserError;
+use crate::parser::expressions::{ExprNode, Parameters, ParametersParser, VarDecl};
+use crate::parser::initializer::{Initializer, InitializerParser};
+use crate::parser::types::{BaseType, CVQualifier, Modifier, Type};
+use crate::parser::Context;
+
+/// new (placement) T[n][3] (init): the dimensions are in the array type
+#[derive(Clone, Debug, PartialEq)]
+pub struct New {
+    pub placement: Option<Parameters>,
+    pub typ: Type,
+    pub init: Option<Initializer>,
+}
+
+impl Dump for New {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(self, name, "new", prefix, last, stdout, placement, typ, init);
+    }
+}
+
+/// Check if an array size is known to be a non-constant expression:
+/// an unknown identifier could be an enumerator or a template parameter
+fn is_non_constant(expr: &ExprNode) -> bool {
+    match expr {
+        ExprNode::Variable(var) => match &var.decl {
+            VarDecl::Direct(decl) => {
+                !decl.specifier.contains(Specifier::CONSTEXPR) && !decl.typ.is_const()
+            }
+            VarDecl::Indirect(_) => false,
+        },
+        ExprNode::UnaryOp(op) => is_non_constant(&op.arg),
+        ExprNode::BinaryOp(op) => is_non_constant(&op.arg1) || is_non_constant(&op.arg2),
+        ExprNode::Conditional(op) => {
+            is_non_constant(&op.condition)
+                || is_non_constant(&op.left)
+                || is_non_constant(&op.right)
+        }
+        _ => false,
+    }
+}
+
+fn is_type_start(tok: &Token) -> bool {
+    matches!(
+        tok,
+        Token::Identifier(_)
+            | Token::ColonColon
+            | Token::Typename
+            | Token::Decltype
+            | Token::Auto
+            | Token::Struct
+            | Token::Class
+            | Token::Union
+            | Token::Enum
+    ) || Modifier::is_primitive_part(tok)
+        || CVQualifier::is_cv(tok)
+}
+
+pub(crate) struct NewParser<'a, L: TLexer> {
+    lexer: &'a mut L,
+}
+
+impl<'a, L: TLexer> NewParser<'a, L> {
+    pub(crate) fn new(lexer: &'a mut L) -> Self {
+        Self { lexer }
+    }
+
+    pub(crate) fn parse(
+        mut self,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<New>), ParserError> {
+        let tok = self.lexer.next_useful();
+
+        let (tok, placement, typ) = if tok == Token::LeftParen {
+            let (_, mut saved) = self.lexer.save_until(Token::RightParen, 1);
+            let tok = self.lexer.next_useful();
+
+            if is_type_start(&tok) {
+                // new (placement) T
+                let pp = ParametersParser::new(&mut saved, Token::RightParen);
+                let (_, placement) = pp.parse(None, None, context)?;
+                let (tok, typ) = self.parse_type(tok, context)?;
+
+                (tok, placement, typ)
+            } else {
+                // new (T)
+                let tdp = TypeDeclaratorParser::new(&mut saved);
+                let (_, typ) = tdp.parse(None, None, false, context)?;
+                let typ = if let Some(typ) = typ {
+                    Rc::try_unwrap(typ).map_or_else(|t| t.typ.clone(), |t| t.typ)
+                } else {
+                    // new (): no type
+                    return Err(ParserError::InvalidTokenInExpr {
+                        sp: self.lexer.span(),
+                        tok,
+                    });
+                };
+
+                (Some(tok), None, typ)
+            }
+        } else {
+            let (tok, typ) = self.parse_type(tok, context)?;
+            (tok, None, typ)
+        };
+
+        let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        let (tok, init) = if tok == Token::LeftParen || tok == Token::LeftBrace {
+            let ip = InitializerParser::new(self.lexer);
+            ip.parse(Some(tok), context)?
+        } else {
+            (Some(tok), None)
+        };
+
+        Ok((
+            tok,
+            Some(New {
+                placement,
+                typ,
+                init,
+            }),
+        ))
+    }
+
+    /// Parse a new-type-id: it stops before a parenthesized initializer
+    fn parse_type(
+        &mut self,
+        tok: Token,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Type), ParserError> {
+        let dsp = DeclSpecifierParser::new(self.lexer);
+        let (tok, DeclSpecifiers { typ, .. }) = dsp.parse(Some(tok), None, context)?;
+
+        let mut typ = if let Some(typ) = typ {
+            typ
+        } else {
+            return Err(ParserError::InvalidTokenInExpr {
+                sp: self.lexer.span(),
+                tok: tok.unwrap_or(Token::None),
+            });
+        };
+
+        let pdp = PointerDeclaratorParser::new(self.lexer);
+        let (tok, pointers) = pdp.parse(tok, None, context)?;
+        if pointers.is_some() {
+            typ.pointers = pointers;
+        }
+
+        let ap = ArrayParser::new(self.lexer);
+        let (tok, array) = ap.parse(tok, context)?;
+
+        let typ = if let Some(mut array) = array {
+            if array.has_qualifiers() {
+                return Err(ParserError::InvalidArrayQualifiers {
+                    sp: self.lexer.span(),
+                });
+            }
+
+            // Only the first dimension can be non-constant
+            let non_constant = array
+                .dimensions
+                .iter()
+                .skip(1)
+                .any(|dim| !matches!(&dim.size, Some(size) if !is_non_constant(size)));
+            if non_constant {
+                return Err(ParserError::InvalidArraySizeInNew {
+                    sp: self.lexer.span(),
+                });
+            }
+
+            array.base = Some(typ);
+            Type {
+                base: BaseType::Array(Box::new(array)),
+                cv: CVQualifier::empty(),
+                pointers: None,
+            }
+        } else {
+            typ
+        };
+
+        Ok((tok, typ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lexer::{preprocessor::context::DefaultContext, Lexer};
+    use crate::parser::declarations::{Array, Dimension};
+    use crate::parser::expressions::{ExpressionParser, Variable};
+    use crate::parser::literals::{IntLiteral, Integer};
+    use crate::parser::names::Qualified;
+    use crate::parser::statements::StatementParser;
+    use crate::parser::types::Primitive;
+    use crate::parser::TypeToFix;
+    use pretty_assertions::assert_eq;
+
+    fn parse(s: &[u8]) -> Result<Option<ExprNode>, ParserError> {
+        let mut lexer = Lexer::<DefaultContext>::new(s);
+        let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+        let mut context = Context::default();
+        parser.parse(None, &mut context).map(|(_, node)| node)
+    }
+
+    fn dim(size: ExprNode) -> Dimension {
+        Dimension {
+            size: Some(size),
+            attributes: None,
+            is_static: false,
+            cv: CVQualifier::empty(),
+        }
+    }
+
+    fn int(base: BaseType) -> Type {
+        Type {
+            base,
+            cv: CVQualifier::empty(),
+            pointers: None,
+        }
+    }
+
+    #[test]
+    fn test_new_array() {
+        let node = parse(b"new int[n][3]").unwrap().unwrap();
+
+        assert_eq!(
+            node,
+            ExprNode::New(Box::new(New {
+                placement: None,
+                typ: int(BaseType::Array(Box::new(Array {
+                    base: Some(int(BaseType::Primitive(Primitive::Int))),
+                    dimensions: vec![
+                        dim(ExprNode::Variable(Box::new(Variable {
+                            name: mk_id!("n"),
+                            decl: VarDecl::Indirect(TypeToFix::default()),
+                        }))),
+                        dim(ExprNode::Integer(Box::new(Integer {
+                            value: IntLiteral::Int(3),
+                        }))),
+                    ],
+                }))),
+                init: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_new_init() {
+        let node = parse(b"new (buf) A(1, 2)").unwrap().unwrap();
+        let new = match node {
+            ExprNode::New(new) => new,
+            _ => panic!("not a new expression"),
+        };
+
+        assert_eq!(new.placement.as_ref().unwrap().len(), 1);
+        assert!(matches!(new.typ.base, BaseType::UD(_)));
+        assert!(matches!(new.init, Some(Initializer::Paren(ref p)) if p.len() == 2));
+
+        let node = parse(b"new (int*){}").unwrap().unwrap();
+        let new = match node {
+            ExprNode::New(new) => new,
+            _ => panic!("not a new expression"),
+        };
+
+        assert!(new.placement.is_none());
+        assert!(new.typ.pointers.is_some());
+        assert!(matches!(new.init, Some(Initializer::Brace(_))));
+    }
+
+    #[test]
+    fn test_new_non_constant_dim() {
+        let parse_stmt = |s: &[u8]| {
+            let mut lexer = Lexer::<DefaultContext>::new(s);
+            let parser = StatementParser::new(&mut lexer);
+            let mut context = Context::default();
+            parser.parse(None, &mut context)
+        };
+
+        assert!(parse_stmt(b"{ int n = 2; const int m = 3; new int[n][m][N]; }").is_ok());
+        assert!(matches!(
+            parse_stmt(b"{ int n = 2; new int[3][n]; }"),
+            Err(ParserError::InvalidArraySizeInNew { .. })
+        ));
+        assert!(matches!(
+            parse(b"new int[3][]"),
+            Err(ParserError::InvalidArraySizeInNew { .. })
+        ));
+    }
+
+    #[test]
+    fn test_new_no_type() {
+        assert!(matches!(
+            parse(b"new ()"),
+            Err(ParserError::InvalidTokenInExpr { .. })
+        ));
+
+        let mut lexer = Lexer::<DefaultContext>::new(b"void f() { new (); }");
+        let parser = StatementParser::new(&mut lexer);
+        let mut context = Context::default();
+        assert!(parser.parse(None, &mut context).is_err());
+    }
+
+    #[test]
+    fn test_new_global() {
+        assert_eq!(
+            parse(b"::new int").unwrap(),
+            Some(ExprNode::New(Box::new(New {
+                placement: None,
+                typ: int(BaseType::Primitive(Primitive::Int)),
+                init: None,
+            })))
+        );
+
+        let node = parse(b"p = ::new T(1)").unwrap().unwrap();
+        let new = match node {
+            ExprNode::BinaryOp(op) => op.arg2,
+            _ => panic!("not an assignment"),
+        };
+        assert!(matches!(new, ExprNode::New(_)));
+    }
+}
@@ -26,6 +26,7 @@ pub enum Operator {
     AddressOf,
     AddressOfLabel,
     Sizeof,
+    Alignof,
     New,
     NewArray,
     Delete,
@@ -77,8 +78,8 @@ impl Operator {
         use Operator::*;
 
         match self {
-            Plus | Minus | Not | BitNeg | Sizeof | PreInc | PreDec | Indirection | AddressOf
-            | Throw | CoAwait | CoYield => {
+            Plus | Minus | Not | BitNeg | Sizeof | Alignof | PreInc | PreDec | Indirection
+            | AddressOf | Throw | CoAwait | CoYield => {
                 let arg = stack.pop().unwrap();
                 stack.push(ExprNode::UnaryOp(Box::new(UnaryOp { op: self, arg })));
             }
@@ -124,6 +125,7 @@ impl Operator {
             AddressOf => "address-of",
             AddressOfLabel => "&&",
             Sizeof => "sizeof",
+            Alignof => "alignof",
             New => "new",
             NewArray => "new []",
             Delete => "delete",
@@ -154,7 +156,7 @@ impl Operator {
             Or => "||",
             Conditional => "?:",
             Throw => "throw",
-            CoYield => "coyield",
+            CoYield => "co_yield",
             Assign => "=",
             AddAssign => "+=",
             SubAssign => "-=",
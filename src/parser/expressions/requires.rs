@@ -0,0 +1,458 @@
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use termcolor::StandardStreamLock;
+
+use super::expr::{ExprNode, ExpressionParser, TemplateId, VarDecl, Variable};
+use super::operator::{BinaryOp, Operator};
+use super::params::Parameters;
+use crate::lexer::{TLexer, Token};
+use crate::parser::context::{Context, TypeToFix};
+use crate::parser::declarations::{
+    Parameter, ParameterListParser, TypeDeclarator, TypeDeclaratorParser,
+};
+use crate::parser::dump::Dump;
+use crate::parser::errors::ParserError;
+use crate::parser::literals::Bool;
+use crate::parser::names::{Qualified, QualifiedParser};
+use crate::parser::types::{CVQualifier, Modifier};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompoundRequirement {
+    pub expr: ExprNode,
+    pub noexcept: bool,
+    pub constraint: Option<ExprNode>,
+}
+
+impl Dump for CompoundRequirement {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(self, name, "compound", prefix, last, stdout, expr, noexcept, constraint);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Requirement {
+    /// a + b;
+    Simple(ExprNode),
+    /// typename T::type;
+    Type(Qualified),
+    /// { a + b } noexcept -> std::same_as<int>;
+    Compound(CompoundRequirement),
+    /// requires C<T>;
+    Nested(ExprNode),
+}
+
+impl Dump for Requirement {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        match self {
+            Self::Simple(x) => x.dump(name, prefix, last, stdout),
+            Self::Type(x) => x.dump(name, prefix, last, stdout),
+            Self::Compound(x) => x.dump(name, prefix, last, stdout),
+            Self::Nested(x) => x.dump(name, prefix, last, stdout),
+        }
+    }
+}
+
+pub type Requirements = Vec<Requirement>;
+
+impl Dump for Requirements {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_vec!(name, self, "req", prefix, last, stdout);
+    }
+}
+
+/// requires (T a) { a + 1; }
+#[derive(Clone, Debug, PartialEq)]
+pub struct RequiresExpr {
+    pub params: Option<Vec<Parameter>>,
+    pub requirements: Requirements,
+}
+
+impl Dump for RequiresExpr {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(
+            self,
+            name,
+            "requires",
+            prefix,
+            last,
+            stdout,
+            params,
+            requirements
+        );
+    }
+}
+
+pub(crate) struct RequiresExprParser<'a, L: TLexer> {
+    lexer: &'a mut L,
+}
+
+impl<'a, L: TLexer> RequiresExprParser<'a, L> {
+    pub(crate) fn new(lexer: &'a mut L) -> Self {
+        Self { lexer }
+    }
+
+    /// Parse the expression after the requires keyword
+    pub(crate) fn parse(
+        mut self,
+        tok: Option<Token>,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<RequiresExpr>), ParserError> {
+        let plp = ParameterListParser::new(self.lexer);
+        let (tok, params) = plp.parse(tok, false, context)?;
+
+        let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        if tok != Token::LeftBrace {
+            return Err(ParserError::InvalidTokenInExpr {
+                sp: self.lexer.span(),
+                tok,
+            });
+        }
+
+        let mut requirements = Vec::new();
+        loop {
+            let tok = self.lexer.next_useful();
+            let req = match tok {
+                Token::RightBrace => {
+                    return Ok((
+                        None,
+                        Some(RequiresExpr {
+                            params,
+                            requirements,
+                        }),
+                    ));
+                }
+                Token::Typename => {
+                    let qp = QualifiedParser::new(self.lexer);
+                    let (tok, name) = qp.parse(None, None, context)?;
+                    self.check_semicolon(tok)?;
+                    Requirement::Type(name.unwrap())
+                }
+                Token::Requires => {
+                    let cp = ConstraintParser::new(self.lexer);
+                    let (tok, constraint) = cp.parse(None, context)?;
+                    self.check_semicolon(tok)?;
+                    Requirement::Nested(constraint.unwrap())
+                }
+                Token::LeftBrace => {
+                    let mut ep = ExpressionParser::new(self.lexer, Token::RightBrace);
+                    let (tok, expr) = ep.parse(None, context)?;
+                    let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+                    let expr = match (tok, expr) {
+                        (Token::RightBrace, Some(expr)) => expr,
+                        (tok, _) => {
+                            return Err(ParserError::InvalidTokenInExpr {
+                                sp: self.lexer.span(),
+                                tok,
+                            });
+                        }
+                    };
+
+                    let mut tok = self.lexer.next_useful();
+                    let noexcept = tok == Token::Noexcept;
+                    if noexcept {
+                        tok = self.lexer.next_useful();
+                    }
+
+                    let (tok, constraint) = if tok == Token::Arrow {
+                        let tok = self.lexer.next_useful();
+                        let (tok, constraint) = parse_concept_id(self.lexer, tok, context)?;
+                        (tok, Some(constraint))
+                    } else {
+                        (Some(tok), None)
+                    };
+                    self.check_semicolon(tok)?;
+
+                    Requirement::Compound(CompoundRequirement {
+                        expr,
+                        noexcept,
+                        constraint,
+                    })
+                }
+                _ => {
+                    let mut ep = ExpressionParser::new(self.lexer, Token::SemiColon);
+                    let (tok, expr) = ep.parse(Some(tok), context)?;
+                    self.check_semicolon(tok)?;
+                    Requirement::Simple(expr.unwrap())
+                }
+            };
+            requirements.push(req);
+        }
+    }
+
+    fn check_semicolon(&mut self, tok: Option<Token>) -> Result<(), ParserError> {
+        let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        if tok == Token::SemiColon {
+            Ok(())
+        } else {
+            Err(ParserError::InvalidTokenInExpr {
+                sp: self.lexer.span(),
+                tok,
+            })
+        }
+    }
+}
+
+/// Parse a name followed by optional template arguments: C<T, 1>
+fn parse_concept_id<L: TLexer>(
+    lexer: &mut L,
+    tok: Token,
+    context: &mut Context,
+) -> Result<(Option<Token>, ExprNode), ParserError> {
+    let qp = QualifiedParser::new(lexer);
+    let (tok, name) = qp.parse(Some(tok), None, context)?;
+    let name = if let Some(name) = name {
+        name
+    } else {
+        let tok = tok.unwrap_or_else(|| lexer.next_useful());
+        return Err(ParserError::InvalidTokenInExpr {
+            sp: lexer.span(),
+            tok,
+        });
+    };
+
+    let tok = tok.unwrap_or_else(|| lexer.next_useful());
+    if tok != Token::Lower {
+        return Ok((
+            Some(tok),
+            ExprNode::Variable(Box::new(Variable {
+                name,
+                decl: VarDecl::Indirect(TypeToFix::default()),
+            })),
+        ));
+    }
+
+    let args = parse_template_args(lexer, context)?;
+    Ok((
+        None,
+        ExprNode::TemplateId(Box::new(TemplateId { name, args })),
+    ))
+}
+
+/// Parse the arguments after the '<' up to the closing '>': they're either types or expressions
+pub(crate) fn parse_template_args<L: TLexer>(
+    lexer: &mut L,
+    context: &mut Context,
+) -> Result<Parameters, ParserError> {
+    let mut args = Vec::new();
+    let mut tok = lexer.next_useful();
+    if tok == Token::Greater {
+        return Ok(args);
+    }
+
+    loop {
+        let is_type = match &tok {
+            Token::Identifier(id) => context.is_type_name(id),
+            Token::Typename => true,
+            tok => {
+                Modifier::is_primitive_part(tok)
+                    || CVQualifier::is_cv(tok)
+                    || TypeDeclarator::is_type_part(tok)
+            }
+        };
+
+        let tk = if is_type {
+            let tdp = TypeDeclaratorParser::new(lexer);
+            let (tk, decl) = tdp.parse(Some(tok), None, false, context)?;
+            if let Some(decl) = decl {
+                args.push(ExprNode::Type(Box::new(decl.typ.clone())));
+            }
+            tk
+        } else {
+            let mut ep = ExpressionParser::new(lexer, Token::Greater);
+            let (tk, arg) = ep.parse(Some(tok), context)?;
+            if let Some(arg) = arg {
+                args.push(arg);
+            }
+            tk
+        };
+
+        match tk.unwrap_or_else(|| lexer.next_useful()) {
+            Token::Comma => {
+                tok = lexer.next_useful();
+            }
+            Token::Greater => {
+                return Ok(args);
+            }
+            tk => {
+                return Err(ParserError::InvalidTokenInExpr {
+                    sp: lexer.span(),
+                    tok: tk,
+                });
+            }
+        }
+    }
+}
+
+/// Parse the constraint in a requires-clause: a conjunction or disjunction of primary expressions
+pub(crate) struct ConstraintParser<'a, L: TLexer> {
+    lexer: &'a mut L,
+}
+
+impl<'a, L: TLexer> ConstraintParser<'a, L> {
+    pub(crate) fn new(lexer: &'a mut L) -> Self {
+        Self { lexer }
+    }
+
+    pub(crate) fn parse(
+        mut self,
+        tok: Option<Token>,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<ExprNode>), ParserError> {
+        let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        let (tok, expr) = self.parse_or(tok, context)?;
+        Ok((Some(tok), Some(expr)))
+    }
+
+    fn parse_or(
+        &mut self,
+        tok: Token,
+        context: &mut Context,
+    ) -> Result<(Token, ExprNode), ParserError> {
+        let (mut tok, mut lhs) = self.parse_and(tok, context)?;
+        while tok == Token::OrOr {
+            let tk = self.lexer.next_useful();
+            let (tk, rhs) = self.parse_and(tk, context)?;
+            lhs = ExprNode::BinaryOp(Box::new(BinaryOp {
+                op: Operator::Or,
+                arg1: lhs,
+                arg2: rhs,
+            }));
+            tok = tk;
+        }
+        Ok((tok, lhs))
+    }
+
+    fn parse_and(
+        &mut self,
+        tok: Token,
+        context: &mut Context,
+    ) -> Result<(Token, ExprNode), ParserError> {
+        let (mut tok, mut lhs) = self.parse_primary(tok, context)?;
+        while tok == Token::AndAnd {
+            let tk = self.lexer.next_useful();
+            let (tk, rhs) = self.parse_primary(tk, context)?;
+            lhs = ExprNode::BinaryOp(Box::new(BinaryOp {
+                op: Operator::And,
+                arg1: lhs,
+                arg2: rhs,
+            }));
+            tok = tk;
+        }
+        Ok((tok, lhs))
+    }
+
+    fn parse_primary(
+        &mut self,
+        tok: Token,
+        context: &mut Context,
+    ) -> Result<(Token, ExprNode), ParserError> {
+        let (tok, expr) = match tok {
+            Token::LeftParen => {
+                let mut ep = ExpressionParser::new(self.lexer, Token::RightParen);
+                let (tok, expr) = ep.parse(None, context)?;
+                let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+                match (tok, expr) {
+                    (Token::RightParen, Some(expr)) => (None, expr),
+                    (tok, _) => {
+                        return Err(ParserError::InvalidTokenInExpr {
+                            sp: self.lexer.span(),
+                            tok,
+                        });
+                    }
+                }
+            }
+            Token::Requires => {
+                let rep = RequiresExprParser::new(self.lexer);
+                let (tok, req) = rep.parse(None, context)?;
+                (tok, ExprNode::RequiresExpr(Box::new(req.unwrap())))
+            }
+            Token::True => (None, ExprNode::Bool(Box::new(Bool { value: true }))),
+            Token::False => (None, ExprNode::Bool(Box::new(Bool { value: false }))),
+            Token::Identifier(_) | Token::ColonColon => parse_concept_id(self.lexer, tok, context)?,
+            _ => {
+                return Err(ParserError::InvalidTokenInExpr {
+                    sp: self.lexer.span(),
+                    tok,
+                });
+            }
+        };
+
+        let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        Ok((tok, expr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lexer::preprocessor::context::DefaultContext;
+    use crate::lexer::Lexer;
+    use crate::parser::expressions::CallExpr;
+    use crate::parser::literals::{IntLiteral, Integer};
+    use crate::parser::names::Qualified;
+    use crate::parser::types::{BaseType, Primitive, Type};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_requires_expr_compound() {
+        let mut lexer = Lexer::<DefaultContext>::new(
+            b"requires { typename T::type; a + 1; { a.f() } noexcept -> same_as<int>; requires C<T> && (N > 0); }",
+        );
+        let mut parser = ExpressionParser::new(&mut lexer, Token::Eof);
+        let mut context = Context::default();
+        let node = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let expected = node!(RequiresExpr {
+            params: None,
+            requirements: vec![
+                Requirement::Type(mk_id!("T", "type")),
+                Requirement::Simple(node!(BinaryOp {
+                    op: Operator::Add,
+                    arg1: ExprNode::Variable(Box::new(mk_var!("a"))),
+                    arg2: ExprNode::Integer(Box::new(Integer {
+                        value: IntLiteral::Int(1),
+                    })),
+                })),
+                Requirement::Compound(CompoundRequirement {
+                    expr: node!(CallExpr {
+                        callee: node!(BinaryOp {
+                            op: Operator::Dot,
+                            arg1: ExprNode::Variable(Box::new(mk_var!("a"))),
+                            arg2: ExprNode::Variable(Box::new(mk_var!("f"))),
+                        }),
+                        params: vec![],
+                    }),
+                    noexcept: true,
+                    constraint: Some(node!(TemplateId {
+                        name: mk_id!("same_as"),
+                        args: vec![ExprNode::Type(Box::new(Type {
+                            base: BaseType::Primitive(Primitive::Int),
+                            cv: CVQualifier::empty(),
+                            pointers: None,
+                        }))],
+                    })),
+                }),
+                Requirement::Nested(node!(BinaryOp {
+                    op: Operator::And,
+                    arg1: node!(TemplateId {
+                        name: mk_id!("C"),
+                        args: vec![ExprNode::Variable(Box::new(mk_var!("T")))],
+                    }),
+                    arg2: node!(BinaryOp {
+                        op: Operator::Gt,
+                        arg1: ExprNode::Variable(Box::new(mk_var!("N"))),
+                        arg2: ExprNode::Integer(Box::new(Integer {
+                            value: IntLiteral::Int(0),
+                        })),
+                    }),
+                })),
+            ],
+        });
+
+        assert_eq!(node, expected);
+    }
+}
@@ -18,6 +18,8 @@ pub enum IntLiteral {
     ULong(u64),
     LongLong(u64),
     ULongLong(u64),
+    Size(u64),
+    USize(u64),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -29,7 +31,8 @@ impl Into<u64> for &IntLiteral {
     fn into(self) -> u64 {
         use IntLiteral::*;
         match *self {
-            Int(n) | UInt(n) | Long(n) | ULong(n) | LongLong(n) | ULongLong(n) => n,
+            Int(n) | UInt(n) | Long(n) | ULong(n) | LongLong(n) | ULongLong(n) | Size(n)
+            | USize(n) => n,
         }
     }
 }
@@ -105,6 +105,10 @@ impl Qualified {
         }
     }
 
+    pub fn is_dtor(&self) -> bool {
+        matches!(self.names.last().unwrap(), Name::Destructor(_))
+    }
+
     pub fn get_first_name(mut self) -> String {
         if let Name::Identifier(id) = self.names.pop().unwrap() {
             id.val
@@ -175,6 +179,10 @@ impl<'a, L: TLexer> QualifiedParser<'a, L> {
                 Token::Identifier(_) if !wait_id => {
                     return Ok((Some(tok), Some(Qualified { names })));
                 }
+                Token::Operator if !wait_id => {
+                    // T operator[](...): T is the return type
+                    return Ok((Some(tok), Some(Qualified { names })));
+                }
                 Token::Operator => {
                     let op = OperatorParser::new(self.lexer);
                     let (tok, operator) = op.parse(Some(tok), context)?;
@@ -6,7 +6,7 @@
 use crate::lexer::{TLexer, Token};
 use crate::parser::declarations::{
     pointer::{PointerDeclaratorParser, Pointers},
-    types::DeclSpecifierParser,
+    types::{DeclSpecifierParser, DeclSpecifiers},
 };
 use crate::parser::errors::ParserError;
 use crate::parser::expressions;
@@ -239,8 +239,16 @@ impl<'a, L: TLexer> ConversionTypeParser<'a, L> {
         tok: Option<Token>,
         context: &mut Context,
     ) -> Result<(Option<Token>, Option<Type>), ParserError> {
+        // operator typename T::type(): the dependent type is just a user-defined one
+        let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
+        let tok = if tok == Token::Typename {
+            None
+        } else {
+            Some(tok)
+        };
+
         let dsp = DeclSpecifierParser::new(self.lexer);
-        let (tok, (_, typ, _, _)) = dsp.parse(tok, None, context)?;
+        let (tok, DeclSpecifiers { typ, .. }) = dsp.parse(tok, None, context)?;
 
         let mut typ = if let Some(typ) = typ {
             typ
@@ -5,7 +5,7 @@
 
 use termcolor::StandardStreamLock;
 
-use super::{Statement, StatementParser};
+use super::{Statement, StatementParser, StructuralEq};
 use crate::lexer::lexer::{TLexer, Token};
 use crate::parser::attributes::Attributes;
 use crate::parser::dump::Dump;
@@ -35,6 +35,12 @@ impl Dump for Compound {
     }
 }
 
+impl StructuralEq for Compound {
+    fn structural_eq(&self, other: &Self) -> bool {
+        self.stmts.structural_eq(&other.stmts)
+    }
+}
+
 pub struct CompoundStmtParser<'a, L: TLexer> {
     lexer: &'a mut L,
 }
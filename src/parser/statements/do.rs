@@ -5,7 +5,7 @@
 
 use termcolor::StandardStreamLock;
 
-use super::{Statement, StatementParser};
+use super::{Statement, StatementParser, StructuralEq};
 use crate::lexer::lexer::{TLexer, Token};
 use crate::parser::attributes::Attributes;
 use crate::parser::dump::Dump;
@@ -26,6 +26,12 @@ impl Dump for Do {
     }
 }
 
+impl StructuralEq for Do {
+    fn structural_eq(&self, other: &Self) -> bool {
+        self.condition.structural_eq(&other.condition) && self.body.structural_eq(&other.body)
+    }
+}
+
 pub struct DoStmtParser<'a, L: TLexer> {
     lexer: &'a mut L,
 }
@@ -6,10 +6,10 @@
 use std::rc::Rc;
 use termcolor::StandardStreamLock;
 
-use super::{Statement, StatementParser};
+use super::{Statement, StatementParser, StructuralEq};
 use crate::lexer::lexer::{TLexer, Token};
 use crate::parser::attributes::Attributes;
-use crate::parser::declarations::{DeclOrExpr, DeclOrExprParser, TypeDeclarator};
+use crate::parser::declarations::{DeclOrExpr, DeclOrExprParser};
 use crate::parser::dump::Dump;
 use crate::parser::errors::ParserError;
 use crate::parser::expressions::{ExprNode, ExpressionParser};
@@ -32,11 +32,21 @@ impl Dump for For {
     }
 }
 
+impl StructuralEq for For {
+    fn structural_eq(&self, other: &Self) -> bool {
+        self.init.structural_eq(&other.init)
+            && self.condition.structural_eq(&other.condition)
+            && self.iteration.structural_eq(&other.iteration)
+            && self.body.structural_eq(&other.body)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct ForRange {
     pub attributes: Option<Attributes>,
     pub init: Option<DeclOrExpr>,
-    pub decl: Rc<TypeDeclarator>,
+    /// A declaration or a structured binding
+    pub decl: DeclOrExpr,
     pub expr: ExprNode,
     pub body: Statement,
 }
@@ -59,6 +69,15 @@ impl Dump for ForRange {
     }
 }
 
+impl StructuralEq for ForRange {
+    fn structural_eq(&self, other: &Self) -> bool {
+        self.init.structural_eq(&other.init)
+            && self.decl.structural_eq(&other.decl)
+            && self.expr.structural_eq(&other.expr)
+            && self.body.structural_eq(&other.body)
+    }
+}
+
 pub(super) enum ForRes {
     Normal(For),
     Range(ForRange),
@@ -73,6 +92,19 @@ impl<'a, L: TLexer> ForStmtParser<'a, L> {
         Self { lexer }
     }
 
+    fn add_decl(decl: Option<&DeclOrExpr>, context: &mut Context) {
+        match decl {
+            Some(DeclOrExpr::Decl(typ)) => context.add_type_decl(Rc::clone(typ)),
+            Some(DeclOrExpr::Decls(typs)) => {
+                for typ in typs {
+                    context.add_type_decl(Rc::clone(typ));
+                }
+            }
+            Some(DeclOrExpr::Binding(binding)) => context.add_binding(binding),
+            _ => {}
+        }
+    }
+
     pub(super) fn parse(
         self,
         attributes: Option<Attributes>,
@@ -89,19 +121,16 @@ impl<'a, L: TLexer> ForStmtParser<'a, L> {
 
         context.set_current(None, ScopeKind::ForBlock);
 
-        let dep = DeclOrExprParser::new(self.lexer);
+        let dep = DeclOrExprParser::new_list(self.lexer);
         let (tok, init) = dep.parse(None, context)?;
 
-        if let Some(DeclOrExpr::Decl(typ)) = init.as_ref() {
-            context.add_type_decl(Rc::clone(typ));
-        }
+        Self::add_decl(init.as_ref(), context);
 
         let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
         if tok == Token::Colon {
             // for (decl : ...)
-            let decl = if let DeclOrExpr::Decl(typ) = init.unwrap() {
-                context.add_type_decl(Rc::clone(&typ));
-                typ
+            let decl = if let Some(DeclOrExpr::Decl(_)) | Some(DeclOrExpr::Binding(_)) = init {
+                init.unwrap()
             } else {
                 context.pop();
                 return Err(ParserError::InvalidExprInFor {
@@ -148,15 +177,13 @@ impl<'a, L: TLexer> ForStmtParser<'a, L> {
         let dep = DeclOrExprParser::new(self.lexer);
         let (tok, condition) = dep.parse(None, context)?;
 
-        if let Some(DeclOrExpr::Decl(typ)) = condition.as_ref() {
-            context.add_type_decl(Rc::clone(typ));
-        }
+        Self::add_decl(condition.as_ref(), context);
 
         let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
         if tok == Token::Colon {
             // for (init; decl : ...)
-            let decl = if let DeclOrExpr::Decl(typ) = condition.unwrap() {
-                typ
+            let decl = if let Some(DeclOrExpr::Decl(_)) | Some(DeclOrExpr::Binding(_)) = condition {
+                condition.unwrap()
             } else {
                 context.pop();
                 return Err(ParserError::InvalidExprInFor {
@@ -5,6 +5,7 @@
 
 use termcolor::StandardStreamLock;
 
+use super::StructuralEq;
 use crate::lexer::lexer::{TLexer, Token};
 use crate::parser::attributes::Attributes;
 use crate::parser::dump::Dump;
@@ -39,6 +40,12 @@ impl Dump for Goto {
     }
 }
 
+impl StructuralEq for Goto {
+    fn structural_eq(&self, other: &Self) -> bool {
+        self.label == other.label
+    }
+}
+
 pub struct GotoStmtParser<'a, L: TLexer> {
     lexer: &'a mut L,
 }
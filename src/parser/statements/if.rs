@@ -5,7 +5,7 @@
 
 use termcolor::StandardStreamLock;
 
-use super::{Statement, StatementParser};
+use super::{Statement, StatementParser, StructuralEq};
 use crate::lexer::lexer::{TLexer, Token};
 use crate::parser::attributes::Attributes;
 use crate::parser::dump::Dump;
@@ -17,7 +17,8 @@ use crate::parser::Context;
 pub struct If {
     pub attributes: Option<Attributes>,
     pub constexpr: bool,
-    pub condition: ExprNode,
+    pub consteval: bool,
+    pub condition: Option<ExprNode>,
     pub then: Statement,
     pub r#else: Option<Statement>,
 }
@@ -25,11 +26,22 @@ pub struct If {
 impl Dump for If {
     fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
         dump_obj!(
-            self, name, "if", prefix, last, stdout, attributes, constexpr, condition, then, r#else
+            self, name, "if", prefix, last, stdout, attributes, constexpr, consteval, condition,
+            then, r#else
         );
     }
 }
 
+impl StructuralEq for If {
+    fn structural_eq(&self, other: &Self) -> bool {
+        self.constexpr == other.constexpr
+            && self.consteval == other.consteval
+            && self.condition.structural_eq(&other.condition)
+            && self.then.structural_eq(&other.then)
+            && self.r#else.structural_eq(&other.r#else)
+    }
+}
+
 pub struct IfStmtParser<'a, L: TLexer> {
     lexer: &'a mut L,
 }
@@ -52,6 +64,11 @@ impl<'a, L: TLexer> IfStmtParser<'a, L> {
             false
         };
 
+        if !constexpr && tok == Token::Consteval {
+            // if consteval { ... } else { ... }: no condition
+            return self.parse_body(attributes, false, true, None, context);
+        }
+
         if tok != Token::LeftParen {
             return Err(ParserError::InvalidTokenInIf {
                 sp: self.lexer.span(),
@@ -70,6 +87,17 @@ impl<'a, L: TLexer> IfStmtParser<'a, L> {
             });
         }
 
+        self.parse_body(attributes, constexpr, false, condition, context)
+    }
+
+    fn parse_body(
+        self,
+        attributes: Option<Attributes>,
+        constexpr: bool,
+        consteval: bool,
+        condition: Option<ExprNode>,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<If>), ParserError> {
         let sp = StatementParser::new(self.lexer);
         let (tok, then) = sp.parse(None, context)?;
 
@@ -87,7 +115,8 @@ impl<'a, L: TLexer> IfStmtParser<'a, L> {
             Some(If {
                 attributes,
                 constexpr,
-                condition: condition.unwrap(),
+                consteval,
+                condition,
                 then: then.unwrap(),
                 r#else,
             }),
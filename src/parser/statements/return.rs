@@ -5,6 +5,7 @@
 
 use termcolor::StandardStreamLock;
 
+use super::StructuralEq;
 use crate::lexer::lexer::{TLexer, Token};
 use crate::parser::attributes::Attributes;
 use crate::parser::dump::Dump;
@@ -24,6 +25,48 @@ impl Dump for Return {
     }
 }
 
+impl StructuralEq for Return {
+    fn structural_eq(&self, other: &Self) -> bool {
+        self.val.structural_eq(&other.val)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoReturn {
+    pub(crate) attributes: Option<Attributes>,
+    pub(crate) val: Option<ExprNode>,
+}
+
+impl Dump for CoReturn {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(
+            self,
+            name,
+            "co_return",
+            prefix,
+            last,
+            stdout,
+            attributes,
+            val
+        );
+    }
+}
+
+impl StructuralEq for CoReturn {
+    fn structural_eq(&self, other: &Self) -> bool {
+        self.val.structural_eq(&other.val)
+    }
+}
+
+impl From<Return> for CoReturn {
+    fn from(ret: Return) -> Self {
+        Self {
+            attributes: ret.attributes,
+            val: ret.val,
+        }
+    }
+}
+
 pub struct ReturnStmtParser<'a, L: TLexer> {
     lexer: &'a mut L,
 }
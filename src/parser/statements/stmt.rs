@@ -7,17 +7,21 @@ use std::rc::Rc;
 use termcolor::StandardStreamLock;
 
 use super::{
-    Case, CaseStmtParser, Compound, CompoundStmtParser, Default, DefaultStmtParser, Do,
+    Case, CaseStmtParser, CoReturn, Compound, CompoundStmtParser, Default, DefaultStmtParser, Do,
     DoStmtParser, For, ForRange, ForRes, ForStmtParser, Goto, GotoStmtParser, If, IfStmtParser,
     Return, ReturnStmtParser, Switch, SwitchStmtParser, Try, TryStmtParser, While, WhileStmtParser,
 };
 use crate::lexer::{TLexer, Token};
-use crate::parser::attributes::{Attributes, AttributesParser};
+use crate::parser::attributes::{check_decl_attributes, Attributes, AttributesParser};
 use crate::parser::context::Context;
-use crate::parser::declarations::types::{DeclOrExpr, DeclOrExprParser, TypeDeclarator};
+use crate::parser::declarations::types::{
+    DeclOrExpr, DeclOrExprParser, TypeDeclarator, TypeDeclarators,
+};
+use crate::parser::declarations::{Asm, AsmParser, StructuredBinding};
 use crate::parser::dump::Dump;
 use crate::parser::errors::ParserError;
-use crate::parser::expressions::{ExprNode, ExpressionParser};
+use crate::parser::expressions::{ExprNode, ExpressionParser, VarDecl};
+use crate::parser::initializer::Initializer;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Break {
@@ -41,14 +45,45 @@ impl Dump for Continue {
     }
 }
 
+/// A labeled statement: label: stmt
 #[derive(Clone, Debug, PartialEq)]
 pub struct Label {
-    pub(crate) name: String,
+    pub attributes: Option<Attributes>,
+    pub name: String,
+    pub stmt: Statement,
 }
 
 impl Dump for Label {
     fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
-        dump_obj!(self, name, "label", prefix, last, stdout, name);
+        dump_obj!(self, name, "label", prefix, last, stdout, attributes, name, stmt);
+    }
+}
+
+impl StructuralEq for Label {
+    fn structural_eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.stmt.structural_eq(&other.stmt)
+    }
+}
+
+/// An expression statement with leading attributes: [[likely]] ++x;
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attributed {
+    pub attributes: Attributes,
+    pub stmt: Statement,
+}
+
+impl Dump for Attributed {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(
+            self,
+            name,
+            "attributed",
+            prefix,
+            last,
+            stdout,
+            attributes,
+            stmt
+        );
     }
 }
 
@@ -56,6 +91,7 @@ impl Dump for Label {
 pub enum Statement {
     Compound(Box<Compound>),
     Return(Box<Return>),
+    CoReturn(Box<CoReturn>),
     If(Box<If>),
     Switch(Box<Switch>),
     Case(Box<Case>),
@@ -69,8 +105,13 @@ pub enum Statement {
     Try(Box<Try>),
     For(Box<For>),
     ForRange(Box<ForRange>),
+    Asm(Box<Asm>),
     Type(Rc<TypeDeclarator>),
+    Types(TypeDeclarators),
+    StructuredBinding(Box<StructuredBinding>),
     Expression(Box<ExprNode>),
+    Attributes(Box<Attributes>),
+    Attributed(Box<Attributed>),
     Empty,
 }
 
@@ -85,6 +126,7 @@ impl Dump for Statement {
         match self {
             Self::Compound(x) => dump!(x),
             Self::Return(x) => dump!(x),
+            Self::CoReturn(x) => dump!(x),
             Self::If(x) => dump!(x),
             Self::Switch(x) => dump!(x),
             Self::Case(x) => dump!(x),
@@ -98,13 +140,177 @@ impl Dump for Statement {
             Self::Try(x) => dump!(x),
             Self::For(x) => dump!(x),
             Self::ForRange(x) => dump!(x),
+            Self::Asm(x) => dump!(x),
             Self::Type(x) => dump!(x),
+            Self::Types(x) => dump!(x),
+            Self::StructuredBinding(x) => dump!(x),
             Self::Expression(x) => dump!(x),
+            Self::Attributes(x) => dump!(x),
+            Self::Attributed(x) => dump!(x),
             Self::Empty => dump_str!(name, "empty", Cyan, prefix, last, stdout),
         }
     }
 }
 
+/// Compare the semantically significant parts only: the attributes are ignored
+/// and an attributed null statement is a null statement.
+/// The statements, the declarators and the operations are compared recursively whereas
+/// the types and the other expressions are compared as they are.
+pub trait StructuralEq {
+    fn structural_eq(&self, other: &Self) -> bool;
+}
+
+impl<T: StructuralEq> StructuralEq for Option<T> {
+    fn structural_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Some(x), Some(y)) => x.structural_eq(y),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T: StructuralEq> StructuralEq for Box<T> {
+    fn structural_eq(&self, other: &Self) -> bool {
+        (**self).structural_eq(other)
+    }
+}
+
+impl<T: StructuralEq> StructuralEq for Rc<T> {
+    fn structural_eq(&self, other: &Self) -> bool {
+        (**self).structural_eq(other)
+    }
+}
+
+impl<T: StructuralEq> StructuralEq for Vec<T> {
+    fn structural_eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(x, y)| x.structural_eq(y))
+    }
+}
+
+impl StructuralEq for ExprNode {
+    fn structural_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::UnaryOp(x), Self::UnaryOp(y)) => x.op == y.op && x.arg.structural_eq(&y.arg),
+            (Self::BinaryOp(x), Self::BinaryOp(y)) => {
+                x.op == y.op && x.arg1.structural_eq(&y.arg1) && x.arg2.structural_eq(&y.arg2)
+            }
+            (Self::Conditional(x), Self::Conditional(y)) => {
+                x.condition.structural_eq(&y.condition)
+                    && x.left.structural_eq(&y.left)
+                    && x.right.structural_eq(&y.right)
+            }
+            (Self::CallExpr(x), Self::CallExpr(y)) => {
+                x.callee.structural_eq(&y.callee) && x.params.structural_eq(&y.params)
+            }
+            (Self::MultiSubscript(x), Self::MultiSubscript(y)) => {
+                x.array.structural_eq(&y.array) && x.indices.structural_eq(&y.indices)
+            }
+            (Self::InitExpr(x), Self::InitExpr(y)) => {
+                x.base.structural_eq(&y.base) && x.list.structural_eq(&y.list)
+            }
+            (Self::ListInit(x), Self::ListInit(y)) => x.structural_eq(y),
+            (Self::Variable(x), Self::Variable(y)) => {
+                x.name == y.name
+                    && match (&x.decl, &y.decl) {
+                        (VarDecl::Direct(x), VarDecl::Direct(y)) => x.structural_eq(y),
+                        (x, y) => x == y,
+                    }
+            }
+            (x, y) => x == y,
+        }
+    }
+}
+
+impl StructuralEq for Initializer {
+    fn structural_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Equal(x), Self::Equal(y)) => x.structural_eq(y),
+            (Self::Paren(x), Self::Paren(y)) | (Self::Brace(x), Self::Brace(y)) => {
+                x.structural_eq(y)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl StructuralEq for TypeDeclarator {
+    fn structural_eq(&self, other: &Self) -> bool {
+        self.typ == other.typ
+            && self.specifier == other.specifier
+            && self.identifier.identifier == other.identifier.identifier
+            && self.init.structural_eq(&other.init)
+            && self.align == other.align
+            && self.bitfield_size.structural_eq(&other.bitfield_size)
+            && self.explicit_spec == other.explicit_spec
+    }
+}
+
+impl StructuralEq for StructuredBinding {
+    fn structural_eq(&self, other: &Self) -> bool {
+        self.names == other.names && self.decl.structural_eq(&other.decl)
+    }
+}
+
+impl StructuralEq for DeclOrExpr {
+    fn structural_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Decl(x), Self::Decl(y)) => x.structural_eq(y),
+            (Self::Decls(x), Self::Decls(y)) => x.structural_eq(y),
+            (Self::Binding(x), Self::Binding(y)) => x.structural_eq(y),
+            (Self::Expr(x), Self::Expr(y)) => x.structural_eq(y),
+            _ => false,
+        }
+    }
+}
+
+impl StructuralEq for Asm {
+    fn structural_eq(&self, other: &Self) -> bool {
+        self.qualifiers == other.qualifiers
+            && self.code == other.code
+            && self.outputs == other.outputs
+            && self.inputs == other.inputs
+            && self.clobbers == other.clobbers
+            && self.labels == other.labels
+            && self.block == other.block
+    }
+}
+
+impl StructuralEq for Statement {
+    fn structural_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Compound(x), Self::Compound(y)) => x.structural_eq(y),
+            (Self::Return(x), Self::Return(y)) => x.structural_eq(y),
+            (Self::CoReturn(x), Self::CoReturn(y)) => x.structural_eq(y),
+            (Self::If(x), Self::If(y)) => x.structural_eq(y),
+            (Self::Switch(x), Self::Switch(y)) => x.structural_eq(y),
+            (Self::Case(x), Self::Case(y)) => x.structural_eq(y),
+            (Self::Default(_), Self::Default(_)) => true,
+            (Self::Do(x), Self::Do(y)) => x.structural_eq(y),
+            (Self::While(x), Self::While(y)) => x.structural_eq(y),
+            (Self::Continue(_), Self::Continue(_)) => true,
+            (Self::Break(_), Self::Break(_)) => true,
+            (Self::Goto(x), Self::Goto(y)) => x.structural_eq(y),
+            (Self::Label(x), Self::Label(y)) => x.structural_eq(y),
+            (Self::Try(x), Self::Try(y)) => x.structural_eq(y),
+            (Self::For(x), Self::For(y)) => x.structural_eq(y),
+            (Self::ForRange(x), Self::ForRange(y)) => x.structural_eq(y),
+            (Self::Asm(x), Self::Asm(y)) => x.structural_eq(y),
+            (Self::Type(x), Self::Type(y)) => x.structural_eq(y),
+            (Self::Types(x), Self::Types(y)) => x.structural_eq(y),
+            (Self::StructuredBinding(x), Self::StructuredBinding(y)) => x.structural_eq(y),
+            (Self::Expression(x), Self::Expression(y)) => x.structural_eq(y),
+            (Self::Attributes(_), Self::Attributes(_))
+            | (Self::Attributes(_), Self::Empty)
+            | (Self::Empty, Self::Attributes(_))
+            | (Self::Empty, Self::Empty) => true,
+            (Self::Attributed(x), y) => x.stmt.structural_eq(y),
+            (x, Self::Attributed(y)) => x.structural_eq(&y.stmt),
+            _ => false,
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! check_semicolon {
     ( $self:expr, $tok:expr ) => {
@@ -165,6 +371,15 @@ impl<'a, L: TLexer> StatementParser<'a, L> {
                 check_semicolon!(self, tok);
                 Ok((None, Some(Statement::Return(Box::new(ret.unwrap())))))
             }
+            Token::CoReturn => {
+                let rp = ReturnStmtParser::new(self.lexer);
+                let (tok, ret) = rp.parse(attributes, context)?;
+                check_semicolon!(self, tok);
+                Ok((
+                    None,
+                    Some(Statement::CoReturn(Box::new(ret.unwrap().into()))),
+                ))
+            }
             Token::LeftBrace => {
                 let cp = CompoundStmtParser::new(self.lexer);
                 let (_, compound) = cp.parse(attributes, context)?;
@@ -178,7 +393,7 @@ impl<'a, L: TLexer> StatementParser<'a, L> {
             Token::Switch => {
                 let sp = SwitchStmtParser::new(self.lexer);
                 let (tok, switch) = sp.parse(attributes, context)?;
-                Ok((tok, Some(Statement::Switch(Box::new(switch.unwrap())))))
+                Ok((tok, switch.map(|s| Statement::Switch(Box::new(s)))))
             }
             Token::While => {
                 let wp = WhileStmtParser::new(self.lexer);
@@ -232,29 +447,129 @@ impl<'a, L: TLexer> StatementParser<'a, L> {
                 let (tok, default) = dp.parse(attributes, context)?;
                 Ok((tok, Some(Statement::Default(Box::new(default.unwrap())))))
             }
-            Token::SemiColon => Ok((None, Some(Statement::Empty))),
+            Token::Asm | Token::GnuAsm | Token::MSAsm => {
+                let ap = AsmParser::new(self.lexer);
+                let (tok, asm) = ap.parse(Some(tok), context)?;
+                let mut asm = asm.unwrap();
+                if asm.block.is_none() {
+                    check_semicolon!(self, tok);
+                }
+                asm.attributes = attributes;
+                Ok((None, Some(Statement::Asm(Box::new(asm)))))
+            }
+            Token::SemiColon => {
+                // attributed null statement: [[fallthrough]];
+                if let Some(attributes) = attributes {
+                    Ok((None, Some(Statement::Attributes(Box::new(attributes)))))
+                } else {
+                    Ok((None, Some(Statement::Empty)))
+                }
+            }
+            Token::Identifier(id) => {
+                let tok = self.lexer.next_useful();
+                if tok == Token::Colon {
+                    // case and default are keywords and :: is a single token
+                    return self.parse_label(attributes, id, context);
+                }
+
+                let doep = DeclOrExprParser::new_list(self.lexer);
+                let (_, doe) = doep.parse_id(Some(tok), id, context)?;
+
+                self.decl_or_expr(attributes, doe.unwrap(), context)
+            }
             _ => {
-                let doep = DeclOrExprParser::new(self.lexer);
+                let doep = DeclOrExprParser::new_list(self.lexer);
                 let (_, doe) = doep.parse(Some(tok.clone()), context)?;
 
                 if let Some(doe) = doe {
-                    return Ok(match doe {
-                        DeclOrExpr::Decl(d) => {
-                            context.add_type_decl(Rc::clone(&d));
-                            (None, Some(Statement::Type(d)))
-                        }
-                        DeclOrExpr::Expr(e) => (None, Some(Statement::Expression(Box::new(e)))),
-                    });
+                    return self.decl_or_expr(attributes, doe, context);
                 }
 
                 let mut ep = ExpressionParser::new(self.lexer, Token::SemiColon);
                 let (tok, expr) = ep.parse(Some(tok), context)?;
 
                 check_semicolon!(self, tok);
-                Ok((None, Some(Statement::Expression(Box::new(expr.unwrap())))))
+                let stmt = Statement::Expression(Box::new(expr.unwrap()));
+                Ok((None, Some(Self::attach(attributes, stmt))))
             }
         }
     }
+
+    fn attach(attributes: Option<Attributes>, stmt: Statement) -> Statement {
+        if let Some(attributes) = attributes {
+            Statement::Attributed(Box::new(Attributed { attributes, stmt }))
+        } else {
+            stmt
+        }
+    }
+
+    fn decl_or_expr(
+        self,
+        attributes: Option<Attributes>,
+        doe: DeclOrExpr,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<Statement>), ParserError> {
+        Ok(match doe {
+            DeclOrExpr::Decl(mut d) => {
+                check_decl_attributes(attributes.as_ref(), self.lexer.span())?;
+                if let Some(attributes) = attributes.as_ref() {
+                    Rc::make_mut(&mut d).add_leading_attributes(attributes);
+                }
+                context.add_type_decl(Rc::clone(&d));
+                (None, Some(Statement::Type(d)))
+            }
+            DeclOrExpr::Decls(mut decls) => {
+                check_decl_attributes(attributes.as_ref(), self.lexer.span())?;
+                for d in decls.iter_mut() {
+                    if let Some(attributes) = attributes.as_ref() {
+                        Rc::make_mut(d).add_leading_attributes(attributes);
+                    }
+                    context.add_type_decl(Rc::clone(d));
+                }
+                (None, Some(Statement::Types(decls)))
+            }
+            DeclOrExpr::Binding(mut b) => {
+                check_decl_attributes(attributes.as_ref(), self.lexer.span())?;
+                if let Some(attributes) = attributes.as_ref() {
+                    Rc::make_mut(&mut b.decl).add_leading_attributes(attributes);
+                }
+                context.add_binding(&b);
+                (None, Some(Statement::StructuredBinding(b)))
+            }
+            DeclOrExpr::Expr(e) => (
+                None,
+                Some(Self::attach(attributes, Statement::Expression(Box::new(e)))),
+            ),
+        })
+    }
+
+    fn parse_label(
+        self,
+        attributes: Option<Attributes>,
+        name: String,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<Statement>), ParserError> {
+        let sp = StatementParser::new(self.lexer);
+        let (tok, stmt) = sp.parse(None, context)?;
+
+        let stmt = if let Some(stmt) = stmt {
+            stmt
+        } else {
+            return Err(ParserError::InvalidTokenInStmt {
+                sp: self.lexer.span(),
+                tok: tok.unwrap_or(Token::Eof),
+            });
+        };
+
+        Ok((
+            tok,
+            Some(Statement::Label(Box::new(Label {
+                attributes,
+                name,
+                stmt,
+            }))),
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -264,12 +579,14 @@ mod tests {
 
     use super::*;
     use crate::lexer::{preprocessor::context::DefaultContext, Lexer};
+    use crate::parser::attributes::Attribute;
     use crate::parser::context::TypeToFix;
     use crate::parser::declarations::*;
     use crate::parser::expressions::*;
     use crate::parser::initializer::*;
     use crate::parser::literals::{self, *};
     use crate::parser::names::Qualified;
+    use crate::parser::statements::goto;
     use crate::parser::types::*;
     use pretty_assertions::assert_eq;
 
@@ -334,7 +651,9 @@ mod tests {
                     value: IntLiteral::Int(1),
                 },
             )))),
+            align: None,
             bitfield_size: None,
+            explicit_spec: None,
         });
 
         assert_eq!(
@@ -369,7 +688,9 @@ mod tests {
                                 value: IntLiteral::Int(1)
                             }
                         )))),
+                        align: None,
                         bitfield_size: None,
+                        explicit_spec: None,
                     })),
                     Statement::Type(Rc::new(TypeDeclarator {
                         typ: Type {
@@ -388,7 +709,9 @@ mod tests {
                             attributes: None
                         },
                         init: Some(Initializer::Equal(ExprNode::Nullptr(Box::new(Nullptr {})))),
+                        align: None,
                         bitfield_size: None,
+                        explicit_spec: None,
                     })),
                     Statement::Empty,
                 ]
@@ -441,11 +764,12 @@ mod tests {
         let expected = Statement::If(Box::new(If {
             attributes: None,
             constexpr: true,
-            condition: node!(BinaryOp {
+            consteval: false,
+            condition: Some(node!(BinaryOp {
                 op: Operator::Neq,
                 arg1: ExprNode::Variable(Box::new(mk_var!("a"))),
                 arg2: ExprNode::Variable(Box::new(mk_var!("b"))),
-            }),
+            })),
             then: Statement::Compound(Box::new(Compound {
                 attributes: None,
                 stmts: vec![Statement::Return(Box::new(Return {
@@ -470,6 +794,127 @@ mod tests {
         assert_eq!(stmt, expected);
     }
 
+    #[test]
+    fn test_statement_if_builtin_expect() {
+        let mut lexer = Lexer::<DefaultContext>::new(b"if (__builtin_expect(x, 0)) return;");
+        let parser = StatementParser::new(&mut lexer);
+        let mut context = Context::default();
+        let stmt = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let expected = Statement::If(Box::new(If {
+            attributes: None,
+            constexpr: false,
+            consteval: false,
+            condition: Some(node!(BuiltinExpect {
+                cond: ExprNode::Variable(Box::new(mk_var!("x"))),
+                expected: ExprNode::Integer(Box::new(literals::Integer {
+                    value: IntLiteral::Int(0),
+                })),
+            })),
+            then: Statement::Return(Box::new(Return {
+                attributes: None,
+                val: None,
+            })),
+            r#else: None,
+        }));
+
+        assert_eq!(stmt, expected);
+    }
+
+    #[test]
+    fn test_statement_if_builtin_constant_p() {
+        let mut lexer =
+            Lexer::<DefaultContext>::new(b"if (__builtin_constant_p(x)) return; else return;");
+        let parser = StatementParser::new(&mut lexer);
+        let mut context = Context::default();
+        let stmt = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let expected = Statement::If(Box::new(If {
+            attributes: None,
+            constexpr: false,
+            consteval: false,
+            condition: Some(node!(BuiltinConstantP {
+                arg: ExprNode::Variable(Box::new(mk_var!("x"))),
+            })),
+            then: Statement::Return(Box::new(Return {
+                attributes: None,
+                val: None,
+            })),
+            r#else: Some(Statement::Return(Box::new(Return {
+                attributes: None,
+                val: None,
+            }))),
+        }));
+
+        assert_eq!(stmt, expected);
+    }
+
+    #[test]
+    fn test_statement_if_consteval() {
+        let mut lexer = Lexer::<DefaultContext>::new(
+            b"
+             if consteval {
+                   return a;
+             }
+             ",
+        );
+        let parser = StatementParser::new(&mut lexer);
+        let mut context = Context::default();
+        let stmt = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let expected = Statement::If(Box::new(If {
+            attributes: None,
+            constexpr: false,
+            consteval: true,
+            condition: None,
+            then: Statement::Compound(Box::new(Compound {
+                attributes: None,
+                stmts: vec![Statement::Return(Box::new(Return {
+                    attributes: None,
+                    val: Some(ExprNode::Variable(Box::new(mk_var!("a")))),
+                }))],
+            })),
+            r#else: None,
+        }));
+
+        assert_eq!(stmt, expected);
+    }
+
+    #[test]
+    fn test_statement_if_consteval_else() {
+        let mut lexer = Lexer::<DefaultContext>::new(
+            b"
+             if consteval {
+             } else {
+                   return b;
+             }
+             ",
+        );
+        let parser = StatementParser::new(&mut lexer);
+        let mut context = Context::default();
+        let stmt = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let expected = Statement::If(Box::new(If {
+            attributes: None,
+            constexpr: false,
+            consteval: true,
+            condition: None,
+            then: Statement::Compound(Box::new(Compound {
+                attributes: None,
+                stmts: vec![],
+            })),
+            r#else: Some(Statement::Compound(Box::new(Compound {
+                attributes: None,
+                stmts: vec![Statement::Return(Box::new(Return {
+                    attributes: None,
+                    val: Some(ExprNode::Variable(Box::new(mk_var!("b")))),
+                }))],
+            }))),
+        }));
+
+        assert_eq!(stmt, expected);
+    }
+
     #[test]
     fn test_statement_try_named() {
         let mut lexer = Lexer::<DefaultContext>::new(
@@ -511,7 +956,9 @@ mod tests {
                     attributes: None,
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })),
             handler: Box::new(Statement::Compound(Box::new(Compound {
                 attributes: None,
@@ -563,7 +1010,9 @@ mod tests {
                     attributes: None,
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
+                explicit_spec: None,
             })),
             handler: Box::new(Statement::Compound(Box::new(Compound {
                 attributes: None,
@@ -656,7 +1105,9 @@ mod tests {
                     value: IntLiteral::Int(0),
                 },
             )))),
+            align: None,
             bitfield_size: None,
+            explicit_spec: None,
         });
 
         let expected = Statement::For(Box::new(For {
@@ -700,7 +1151,7 @@ mod tests {
         let expected = Statement::ForRange(Box::new(ForRange {
             attributes: None,
             init: None,
-            decl: Rc::new(TypeDeclarator {
+            decl: DeclOrExpr::Decl(Rc::new(TypeDeclarator {
                 typ: Type {
                     base: BaseType::Auto,
                     cv: CVQualifier::empty(),
@@ -717,8 +1168,10 @@ mod tests {
                     attributes: None,
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
-            }),
+                explicit_spec: None,
+            })),
             expr: node!(CallExpr {
                 callee: node!(BinaryOp {
                     op: Operator::Dot,
@@ -757,7 +1210,9 @@ mod tests {
                 attributes: None,
             },
             init: None,
+            align: None,
             bitfield_size: None,
+            explicit_spec: None,
         });
         let thing = Rc::new(TypeDeclarator {
             typ: Type {
@@ -777,7 +1232,9 @@ mod tests {
                 callee: ExprNode::Variable(Box::new(mk_var!("foo"))),
                 params: vec![]
             }))),
+            align: None,
             bitfield_size: None,
+            explicit_spec: None,
         });
 
         context.add_type_decl(Rc::clone(&t));
@@ -787,7 +1244,7 @@ mod tests {
         let expected = Statement::ForRange(Box::new(ForRange {
             attributes: None,
             init: Some(DeclOrExpr::Decl(Rc::clone(&thing))),
-            decl: Rc::new(TypeDeclarator {
+            decl: DeclOrExpr::Decl(Rc::new(TypeDeclarator {
                 typ: Type {
                     base: BaseType::Auto,
                     cv: CVQualifier::empty(),
@@ -804,8 +1261,10 @@ mod tests {
                     attributes: None,
                 },
                 init: None,
+                align: None,
                 bitfield_size: None,
-            }),
+                explicit_spec: None,
+            })),
             expr: node!(CallExpr {
                 callee: node!(BinaryOp {
                     op: Operator::Dot,
@@ -856,7 +1315,9 @@ mod tests {
                 attributes: None,
             },
             init: None,
+            align: None,
             bitfield_size: None,
+            explicit_spec: None,
         });
         context.add_type_decl(Rc::clone(&x));
 
@@ -929,4 +1390,476 @@ mod tests {
 
         assert_eq!(stmt, expected);
     }
+
+    #[test]
+    fn test_statement_coroutine() {
+        let mut lexer = Lexer::<DefaultContext>::new(
+            b"
+             task f() {
+                 co_await x;
+                 co_yield 1;
+                 co_return;
+                 co_return x;
+             }
+             ",
+        );
+        let parser = DeclarationListParser::new(&mut lexer);
+        let mut context = Context::default();
+        let decls = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let decl = match &decls[0] {
+            Declaration::Type(decl) => decl,
+            _ => panic!("not a type declaration"),
+        };
+        let fun = match &decl.typ.base {
+            BaseType::Function(fun) => fun,
+            _ => panic!("not a function"),
+        };
+
+        let expected = Compound {
+            attributes: None,
+            stmts: vec![
+                Statement::Expression(Box::new(node!(UnaryOp {
+                    op: Operator::CoAwait,
+                    arg: ExprNode::Variable(Box::new(mk_var!("x"))),
+                }))),
+                Statement::Expression(Box::new(node!(UnaryOp {
+                    op: Operator::CoYield,
+                    arg: ExprNode::Integer(Box::new(literals::Integer {
+                        value: IntLiteral::Int(1),
+                    })),
+                }))),
+                Statement::CoReturn(Box::new(CoReturn {
+                    attributes: None,
+                    val: None,
+                })),
+                Statement::CoReturn(Box::new(CoReturn {
+                    attributes: None,
+                    val: Some(ExprNode::Variable(Box::new(mk_var!("x")))),
+                })),
+            ],
+        };
+
+        assert_eq!(decls.len(), 1);
+        assert_eq!(*fun.body.borrow(), Some(expected));
+    }
+
+    #[test]
+    fn test_statement_fallthrough() {
+        let mut lexer = Lexer::<DefaultContext>::new(
+            b"
+             switch (0) {
+                 case 1:
+                     [[fallthrough]];
+                 case 2:
+                     break;
+             }
+             ",
+        );
+        let parser = StatementParser::new(&mut lexer);
+        let mut context = Context::default();
+        let stmt = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let expected = Statement::Switch(Box::new(Switch {
+            attributes: None,
+            condition: DeclOrExpr::Expr(ExprNode::Integer(Box::new(literals::Integer {
+                value: IntLiteral::Int(0),
+            }))),
+            cases: Statement::Compound(Box::new(Compound {
+                attributes: None,
+                stmts: vec![
+                    Statement::Case(Box::new(Case {
+                        attributes: None,
+                        value: ExprNode::Integer(Box::new(literals::Integer {
+                            value: IntLiteral::Int(1),
+                        })),
+                    })),
+                    Statement::Attributes(Box::new(vec![Attribute {
+                        namespace: None,
+                        name: "fallthrough".to_string(),
+                        arg: None,
+                        has_using: false,
+                    }])),
+                    Statement::Case(Box::new(Case {
+                        attributes: None,
+                        value: ExprNode::Integer(Box::new(literals::Integer {
+                            value: IntLiteral::Int(2),
+                        })),
+                    })),
+                    Statement::Break(Box::new(Break { attributes: None })),
+                ],
+            })),
+        }));
+
+        assert_eq!(stmt, expected);
+    }
+
+    #[test]
+    fn test_statement_attributes_in_switch() {
+        let mut lexer = Lexer::<DefaultContext>::new(
+            b"
+             switch (x) {
+                 [[likely]] case 1:
+                     [[maybe_unused]] ++x;
+                     [[fallthrough]];
+                 case 2:
+                     [[unlikely]] return;
+             }
+             ",
+        );
+        let parser = StatementParser::new(&mut lexer);
+        let mut context = Context::default();
+        let stmt = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let stmts = match stmt {
+            Statement::Switch(s) => match s.cases {
+                Statement::Compound(c) => c.stmts,
+                _ => panic!("not a compound"),
+            },
+            _ => panic!("not a switch"),
+        };
+        let name = |attrs: &Attributes| attrs[0].name.clone();
+
+        assert_eq!(stmts.len(), 5);
+        match &stmts[0] {
+            Statement::Case(c) => assert_eq!(name(c.attributes.as_ref().unwrap()), "likely"),
+            _ => panic!("not a case"),
+        }
+        match &stmts[1] {
+            Statement::Attributed(a) => {
+                assert_eq!(name(&a.attributes), "maybe_unused");
+                assert!(matches!(a.stmt, Statement::Expression(_)));
+            }
+            _ => panic!("not an attributed statement"),
+        }
+        match &stmts[2] {
+            Statement::Attributes(a) => assert_eq!(name(a), "fallthrough"),
+            _ => panic!("not an attributed null statement"),
+        }
+        match &stmts[4] {
+            Statement::Return(r) => assert_eq!(name(r.attributes.as_ref().unwrap()), "unlikely"),
+            _ => panic!("not a return"),
+        }
+    }
+
+    #[test]
+    fn test_statement_structural_eq() {
+        let parse = |s: &[u8]| {
+            let mut lexer = Lexer::<DefaultContext>::new(s);
+            let parser = StatementParser::new(&mut lexer);
+            let mut context = Context::default();
+            parser.parse(None, &mut context).unwrap().1.unwrap()
+        };
+
+        let s1 = parse(
+            b"switch (0) { [[likely]] case 1: break; case 2: [[fallthrough]]; default: break; }",
+        );
+        let s2 = parse(b"switch (0) { case 1: break; [[unlikely]] case 2: ; default: break; }");
+        let s3 = parse(b"switch (0) { case 1: break; case 3: ; default: break; }");
+
+        assert!(s1 != s2);
+        assert!(s1.structural_eq(&s2));
+        assert!(!s1.structural_eq(&s3));
+
+        // the attributes of the nested declarations are ignored too
+        let s1 = parse(
+            b"{ [[maybe_unused]] int x = 1, y; for (int i [[maybe_unused]] = 0; i < x; ++i) { [[deprecated]] int z; } }",
+        );
+        let s2 = parse(b"{ int x = 1, y; for (int i = 0; i < x; ++i) { int z; } }");
+        let s3 = parse(b"{ int x = 2, y; for (int i = 0; i < x; ++i) { int z; } }");
+
+        assert!(s1 != s2);
+        assert!(s1.structural_eq(&s2));
+        assert!(!s1.structural_eq(&s3));
+    }
+
+    #[test]
+    fn test_statement_switch_recovery() {
+        let parse = |s: &[u8], context: &mut Context| {
+            let mut lexer = Lexer::<DefaultContext>::new(s);
+            let parser = StatementParser::new(&mut lexer);
+            parser.parse(None, context)
+        };
+
+        let mut context = Context::default();
+        assert!(parse(b"switch x {}", &mut context).is_err());
+        assert!(context.errors().is_empty());
+
+        context.set_recovery(true);
+        let (_, stmt) = parse(b"switch x { default: break; }", &mut context).unwrap();
+        assert_eq!(context.errors().len(), 1);
+        match stmt.unwrap() {
+            Statement::Switch(switch) => {
+                assert_eq!(
+                    switch.condition,
+                    DeclOrExpr::Expr(ExprNode::Invalid(Box::new(Invalid {})))
+                );
+                assert!(matches!(switch.cases, Statement::Compound(_)));
+            }
+            _ => panic!("not a switch"),
+        }
+
+        let mut context = Context::default();
+        context.set_recovery(true);
+        let (_, stmt) = parse(b"switch (0; {}", &mut context).unwrap();
+        assert_eq!(context.errors().len(), 1);
+        match stmt.unwrap() {
+            Statement::Switch(switch) => {
+                assert_eq!(
+                    switch.condition,
+                    DeclOrExpr::Expr(ExprNode::Integer(Box::new(literals::Integer {
+                        value: IntLiteral::Int(0),
+                    })))
+                );
+                assert_eq!(switch.cases, Statement::Empty);
+            }
+            _ => panic!("not a switch"),
+        }
+
+        let mut context = Context::default();
+        context.set_recovery(true);
+        let (tok, stmt) = parse(b"switch (x {}", &mut context).unwrap();
+        assert_eq!(context.errors().len(), 1);
+        assert_eq!(tok, Some(Token::Eof));
+        assert!(stmt.is_none());
+
+        let mut context = Context::default();
+        context.set_recovery(true);
+        let (tok, stmt) = parse(b"switch x", &mut context).unwrap();
+        assert_eq!(context.errors().len(), 1);
+        assert_eq!(tok, Some(Token::Eof));
+        assert!(stmt.is_none());
+    }
+
+    #[test]
+    fn test_statement_likely() {
+        let parse = |s: &[u8]| {
+            let mut lexer = Lexer::<DefaultContext>::new(s);
+            let parser = StatementParser::new(&mut lexer);
+            let mut context = Context::default();
+            parser.parse(None, &mut context)
+        };
+
+        let likely = Some(vec![Attribute {
+            namespace: None,
+            name: "likely".to_string(),
+            arg: None,
+            has_using: false,
+        }]);
+
+        let stmt = parse(b"if (a) [[likely]] return 1; else [[unlikely]] { return 0; }")
+            .unwrap()
+            .1
+            .unwrap();
+        match stmt {
+            Statement::If(ifs) => {
+                match ifs.then {
+                    Statement::Return(ret) => assert_eq!(ret.attributes, likely),
+                    _ => panic!("not a return"),
+                }
+                match ifs.r#else.unwrap() {
+                    Statement::Compound(c) => {
+                        assert_eq!(c.attributes.unwrap()[0].name, "unlikely")
+                    }
+                    _ => panic!("not a compound"),
+                }
+            }
+            _ => panic!("not an if"),
+        }
+
+        assert!(parse(b"[[likely]] a = 1;").is_ok());
+        assert!(matches!(
+            parse(b"[[likely]] int x = 0;"),
+            Err(ParserError::InvalidAttrInDecl { ref name, .. }) if name == "likely"
+        ));
+        assert!(matches!(
+            parse(b"[[maybe_unused, unlikely]] int x = 0;"),
+            Err(ParserError::InvalidAttrInDecl { ref name, .. }) if name == "unlikely"
+        ));
+        assert!(parse(b"[[maybe_unused]] int x = 0;").is_ok());
+    }
+
+    #[test]
+    fn test_statement_goto_label() {
+        let mut lexer = Lexer::<DefaultContext>::new(
+            b"
+             {
+                 goto end;
+                 switch (a) {
+                     case 1: again: a = A::b; break;
+                     default: goto again;
+                 }
+                 end: return;
+             }
+             ",
+        );
+        let parser = StatementParser::new(&mut lexer);
+        let mut context = Context::default();
+        let stmt = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let stmts = match stmt {
+            Statement::Compound(c) => c.stmts,
+            _ => panic!("not a compound"),
+        };
+
+        assert_eq!(
+            stmts[0],
+            Statement::Goto(Box::new(Goto {
+                attributes: None,
+                label: goto::Label::Id("end".to_string()),
+            }))
+        );
+
+        let cases = match &stmts[1] {
+            Statement::Switch(s) => match &s.cases {
+                Statement::Compound(c) => c.stmts.clone(),
+                _ => panic!("not a compound"),
+            },
+            _ => panic!("not a switch"),
+        };
+        assert!(matches!(cases[0], Statement::Case(_)));
+        match &cases[1] {
+            Statement::Label(l) => {
+                assert_eq!(l.name, "again");
+                assert!(matches!(l.stmt, Statement::Expression(_)));
+            }
+            _ => panic!("not a label"),
+        }
+        assert!(matches!(cases[2], Statement::Break(_)));
+        assert!(matches!(cases[3], Statement::Default(_)));
+        assert!(matches!(cases[4], Statement::Goto(_)));
+
+        assert_eq!(
+            stmts[2],
+            Statement::Label(Box::new(Label {
+                attributes: None,
+                name: "end".to_string(),
+                stmt: Statement::Return(Box::new(Return {
+                    attributes: None,
+                    val: None,
+                })),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_statement_bitneg() {
+        let mut lexer = Lexer::<DefaultContext>::new(b"{ ~x; ~x + 1; }");
+        let parser = StatementParser::new(&mut lexer);
+        let mut context = Context::default();
+        let stmt = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let bitneg = node!(UnaryOp {
+            op: Operator::BitNeg,
+            arg: ExprNode::Variable(Box::new(mk_var!("x"))),
+        });
+        let expected = Statement::Compound(Box::new(Compound {
+            attributes: None,
+            stmts: vec![
+                Statement::Expression(Box::new(bitneg.clone())),
+                Statement::Expression(Box::new(node!(BinaryOp {
+                    op: Operator::Add,
+                    arg1: bitneg,
+                    arg2: ExprNode::Integer(Box::new(literals::Integer {
+                        value: IntLiteral::Int(1)
+                    })),
+                }))),
+            ],
+        }));
+
+        assert_eq!(stmt, expected);
+
+        let mut lexer = Lexer::<DefaultContext>::new(b"{ int x = 0; ~x; }");
+        let parser = StatementParser::new(&mut lexer);
+        let mut context = Context::default();
+        let stmt = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let stmts = match stmt {
+            Statement::Compound(c) => c.stmts,
+            _ => panic!("Not a compound statement"),
+        };
+        assert!(matches!(
+            &stmts[1],
+            Statement::Expression(e) if matches!(&**e, ExprNode::UnaryOp(u) if u.op == Operator::BitNeg)
+        ));
+    }
+
+    #[test]
+    fn test_statement_declarator_list() {
+        let mut lexer =
+            Lexer::<DefaultContext>::new(b"{ int x [[deprecated]], y = 2; int a, b; a = b; }");
+        let parser = StatementParser::new(&mut lexer);
+        let mut context = Context::default();
+        let stmt = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let stmts = match stmt {
+            Statement::Compound(c) => c.stmts,
+            _ => panic!("Not a compound statement"),
+        };
+        assert_eq!(stmts.len(), 3);
+
+        let decls = match &stmts[0] {
+            Statement::Types(decls) => decls,
+            _ => panic!("Not a declaration list: {:?}", stmts[0]),
+        };
+        assert_eq!(decls.len(), 2);
+        assert_eq!(decls[0].identifier.identifier, Some(mk_id!("x")));
+        assert_eq!(
+            decls[0].identifier.attributes,
+            Some(vec![Attribute {
+                namespace: None,
+                name: "deprecated".to_string(),
+                arg: None,
+                has_using: false,
+            }])
+        );
+        assert_eq!(decls[0].init, None);
+        assert_eq!(decls[1].identifier.identifier, Some(mk_id!("y")));
+        assert_eq!(decls[1].identifier.attributes, None);
+        assert_eq!(
+            decls[1].init,
+            Some(Initializer::Equal(ExprNode::Integer(Box::new(
+                literals::Integer {
+                    value: IntLiteral::Int(2)
+                }
+            ))))
+        );
+
+        let decls = match &stmts[1] {
+            Statement::Types(decls) => decls,
+            _ => panic!("Not a declaration list: {:?}", stmts[1]),
+        };
+        let names: Vec<_> = decls
+            .iter()
+            .map(|d| d.identifier.identifier.clone())
+            .collect();
+        assert_eq!(names, vec![Some(mk_id!("a")), Some(mk_id!("b"))]);
+        for d in decls {
+            assert_eq!(d.typ.base, BaseType::Primitive(Primitive::Int));
+        }
+
+        assert!(matches!(&stmts[2], Statement::Expression(_)));
+    }
+
+    #[test]
+    fn test_statement_for_declarator_list() {
+        let mut lexer = Lexer::<DefaultContext>::new(b"for (int i = 0, j = 1; i < j; ++i) {}");
+        let parser = StatementParser::new(&mut lexer);
+        let mut context = Context::default();
+        let stmt = parser.parse(None, &mut context).unwrap().1.unwrap();
+
+        let init = match stmt {
+            Statement::For(f) => f.init,
+            _ => panic!("Not a for statement"),
+        };
+        let decls = match init {
+            Some(DeclOrExpr::Decls(decls)) => decls,
+            _ => panic!("Not a declaration list: {:?}", init),
+        };
+        let names: Vec<_> = decls
+            .iter()
+            .map(|d| d.identifier.identifier.clone())
+            .collect();
+        assert_eq!(names, vec![Some(mk_id!("i")), Some(mk_id!("j"))]);
+    }
 }
@@ -6,13 +6,13 @@
 use std::rc::Rc;
 use termcolor::StandardStreamLock;
 
-use super::{Statement, StatementParser};
+use super::{Statement, StatementParser, StructuralEq};
 use crate::lexer::lexer::{TLexer, Token};
 use crate::parser::attributes::Attributes;
 use crate::parser::declarations::{DeclOrExpr, DeclOrExprParser};
 use crate::parser::dump::Dump;
 use crate::parser::errors::ParserError;
-use crate::parser::expressions::{ExprNode, ExpressionParser};
+use crate::parser::expressions::{ExprNode, ExpressionParser, Invalid};
 use crate::parser::{Context, ScopeKind};
 
 #[derive(Clone, Debug, PartialEq)]
@@ -28,6 +28,12 @@ impl Dump for Switch {
     }
 }
 
+impl StructuralEq for Switch {
+    fn structural_eq(&self, other: &Self) -> bool {
+        self.condition.structural_eq(&other.condition) && self.cases.structural_eq(&other.cases)
+    }
+}
+
 pub struct SwitchStmtParser<'a, L: TLexer> {
     lexer: &'a mut L,
 }
@@ -44,10 +50,27 @@ impl<'a, L: TLexer> SwitchStmtParser<'a, L> {
     ) -> Result<(Option<Token>, Option<Switch>), ParserError> {
         let tok = self.lexer.next_useful();
         if tok != Token::LeftParen {
-            return Err(ParserError::InvalidTokenInSwitch {
+            let err = ParserError::InvalidTokenInSwitch {
                 sp: self.lexer.span(),
-                tok,
-            });
+                tok: tok.clone(),
+            };
+            if !context.is_recovering() {
+                return Err(err);
+            }
+            context.add_error(err);
+
+            // resync on the body
+            let mut tok = tok;
+            while tok != Token::LeftBrace {
+                if tok == Token::Eof {
+                    return Ok((Some(tok), None));
+                }
+                tok = self.lexer.next_useful();
+            }
+
+            context.set_current(None, ScopeKind::SwitchBlock);
+            let condition = DeclOrExpr::Expr(ExprNode::Invalid(Box::new(Invalid {})));
+            return self.parse_cases(Some(tok), attributes, condition, context);
         }
 
         context.set_current(None, ScopeKind::SwitchBlock);
@@ -59,26 +82,63 @@ impl<'a, L: TLexer> SwitchStmtParser<'a, L> {
             context.add_type_decl(Rc::clone(typ));
         }
 
-        if let Some(tok) = tok {
-            if tok != Token::RightParen {
-                context.pop();
-                return Err(ParserError::InvalidTokenInSwitch {
+        let tok = match tok {
+            Some(Token::RightParen) | None => None,
+            Some(tok) => {
+                let err = ParserError::InvalidTokenInSwitch {
                     sp: self.lexer.span(),
-                    tok,
-                });
+                    tok: tok.clone(),
+                };
+                if !context.is_recovering() {
+                    context.pop();
+                    return Err(err);
+                }
+                context.add_error(err);
+                if tok == Token::Eof {
+                    context.pop();
+                    return Ok((Some(tok), None));
+                }
+                Some(tok)
             }
-        }
+        };
 
+        let condition =
+            condition.unwrap_or_else(|| DeclOrExpr::Expr(ExprNode::Invalid(Box::new(Invalid {}))));
+        self.parse_cases(tok, attributes, condition, context)
+    }
+
+    fn parse_cases(
+        self,
+        tok: Option<Token>,
+        attributes: Option<Attributes>,
+        condition: DeclOrExpr,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<Switch>), ParserError> {
         let sp = StatementParser::new(self.lexer);
-        let (tok, cases) = sp.parse(None, context)?;
+        let (tok, cases) = sp.parse(tok, context)?;
         context.pop();
 
+        let cases = if let Some(cases) = cases {
+            cases
+        } else if context.is_recovering() {
+            context.add_error(ParserError::InvalidTokenInSwitch {
+                sp: self.lexer.span(),
+                tok: tok.clone().unwrap_or(Token::Eof),
+            });
+            return Ok((tok, None));
+        } else {
+            return Err(ParserError::InvalidTokenInSwitch {
+                sp: self.lexer.span(),
+                tok: tok.unwrap_or(Token::Eof),
+            });
+        };
+
         Ok((
             tok,
             Some(Switch {
                 attributes,
-                condition: condition.unwrap(),
-                cases: cases.unwrap(),
+                condition,
+                cases,
             }),
         ))
     }
@@ -96,6 +156,12 @@ impl Dump for Case {
     }
 }
 
+impl StructuralEq for Case {
+    fn structural_eq(&self, other: &Self) -> bool {
+        self.value.structural_eq(&other.value)
+    }
+}
+
 pub struct CaseStmtParser<'a, L: TLexer> {
     lexer: &'a mut L,
 }
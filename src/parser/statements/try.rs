@@ -6,7 +6,7 @@
 use std::rc::Rc;
 use termcolor::StandardStreamLock;
 
-use super::{Statement, StatementParser};
+use super::{Statement, StatementParser, StructuralEq};
 use crate::lexer::lexer::{TLexer, Token};
 use crate::parser::attributes::Attributes;
 use crate::parser::declarations::{TypeDeclarator, TypeDeclaratorParser};
@@ -28,38 +28,52 @@ impl Dump for Try {
     }
 }
 
-pub struct TryStmtParser<'a, L: TLexer> {
+impl StructuralEq for Try {
+    fn structural_eq(&self, other: &Self) -> bool {
+        self.clause.structural_eq(&other.clause)
+            && self.body.structural_eq(&other.body)
+            && self.handler.structural_eq(&other.handler)
+    }
+}
+
+/// A catch clause: no declaration means catch(...)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Handler {
+    pub clause: Option<Rc<TypeDeclarator>>,
+    pub body: Box<Statement>,
+}
+
+impl Dump for Handler {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_obj!(self, name, "catch", prefix, last, stdout, clause, body);
+    }
+}
+
+pub type Handlers = Vec<Handler>;
+
+impl Dump for Handlers {
+    fn dump(&self, name: &str, prefix: &str, last: bool, stdout: &mut StandardStreamLock) {
+        dump_vec!(name, self, "cat", prefix, last, stdout);
+    }
+}
+
+pub(crate) struct HandlerParser<'a, L: TLexer> {
     lexer: &'a mut L,
 }
 
-impl<'a, L: TLexer> TryStmtParser<'a, L> {
-    pub(super) fn new(lexer: &'a mut L) -> Self {
+impl<'a, L: TLexer> HandlerParser<'a, L> {
+    pub(crate) fn new(lexer: &'a mut L) -> Self {
         Self { lexer }
     }
 
-    pub(super) fn parse(
+    pub(crate) fn parse(
         self,
-        attributes: Option<Attributes>,
+        tok: Option<Token>,
         context: &mut Context,
-    ) -> Result<(Option<Token>, Option<Try>), ParserError> {
-        let sp = StatementParser::new(self.lexer);
-        let (tok, body) = sp.parse(None, context)?;
-
-        let body = if let Some(body) = body {
-            body
-        } else {
-            return Err(ParserError::InvalidTokenInTry {
-                sp: self.lexer.span(),
-                tok: tok.unwrap(),
-            });
-        };
-
+    ) -> Result<(Option<Token>, Option<Handler>), ParserError> {
         let tok = tok.unwrap_or_else(|| self.lexer.next_useful());
         if tok != Token::Catch {
-            return Err(ParserError::InvalidTokenInTry {
-                sp: self.lexer.span(),
-                tok,
-            });
+            return Ok((Some(tok), None));
         }
 
         let tok = self.lexer.next_useful();
@@ -105,12 +119,60 @@ impl<'a, L: TLexer> TryStmtParser<'a, L> {
         };
 
         let sp = StatementParser::new(self.lexer);
-        let (tok, handler) = sp.parse(None, context)?;
+        let (tok, body) = sp.parse(None, context)?;
 
         if clause.is_some() {
             context.pop();
         }
 
+        let body = if let Some(body) = body {
+            body
+        } else {
+            return Err(ParserError::InvalidTokenInTry {
+                sp: self.lexer.span(),
+                tok: tok.unwrap(),
+            });
+        };
+
+        Ok((
+            tok,
+            Some(Handler {
+                clause,
+                body: Box::new(body),
+            }),
+        ))
+    }
+}
+
+pub struct TryStmtParser<'a, L: TLexer> {
+    lexer: &'a mut L,
+}
+
+impl<'a, L: TLexer> TryStmtParser<'a, L> {
+    pub(super) fn new(lexer: &'a mut L) -> Self {
+        Self { lexer }
+    }
+
+    pub(super) fn parse(
+        self,
+        attributes: Option<Attributes>,
+        context: &mut Context,
+    ) -> Result<(Option<Token>, Option<Try>), ParserError> {
+        let sp = StatementParser::new(self.lexer);
+        let (tok, body) = sp.parse(None, context)?;
+
+        let body = if let Some(body) = body {
+            body
+        } else {
+            return Err(ParserError::InvalidTokenInTry {
+                sp: self.lexer.span(),
+                tok: tok.unwrap(),
+            });
+        };
+
+        let hp = HandlerParser::new(self.lexer);
+        let (tok, handler) = hp.parse(tok, context)?;
+
         let handler = if let Some(handler) = handler {
             handler
         } else {
@@ -125,8 +187,8 @@ impl<'a, L: TLexer> TryStmtParser<'a, L> {
             Some(Try {
                 attributes,
                 body: Box::new(body),
-                clause,
-                handler: Box::new(handler),
+                clause: handler.clause,
+                handler: handler.body,
             }),
         ))
     }
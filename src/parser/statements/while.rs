@@ -6,7 +6,7 @@
 use std::rc::Rc;
 use termcolor::StandardStreamLock;
 
-use super::{Statement, StatementParser};
+use super::{Statement, StatementParser, StructuralEq};
 use crate::lexer::lexer::{TLexer, Token};
 use crate::parser::attributes::Attributes;
 use crate::parser::declarations::{DeclOrExpr, DeclOrExprParser};
@@ -27,6 +27,12 @@ impl Dump for While {
     }
 }
 
+impl StructuralEq for While {
+    fn structural_eq(&self, other: &Self) -> bool {
+        self.condition.structural_eq(&other.condition) && self.body.structural_eq(&other.body)
+    }
+}
+
 pub struct WhileStmtParser<'a, L: TLexer> {
     lexer: &'a mut L,
 }
@@ -36,7 +36,7 @@ impl CVQualifier {
                 *self |= Self::CONST;
                 true
             }
-            Token::Volatile => {
+            Token::Volatile | Token::GnuVolatile => {
                 *self |= Self::VOLATILE;
                 true
             }
@@ -50,7 +50,7 @@ impl CVQualifier {
 
     pub(crate) fn is_cv(tok: &Token) -> bool {
         match tok {
-            Token::Const | Token::Volatile | Token::Restrict => true,
+            Token::Const | Token::Volatile | Token::GnuVolatile | Token::Restrict => true,
             _ => false,
         }
     }
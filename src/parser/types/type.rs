@@ -13,6 +13,7 @@ use crate::parser::context::TypeToFix;
 use crate::parser::declarations::types::TypeDeclarator;
 use crate::parser::declarations::{Array, Class, Enum, Function, Pointers};
 use crate::parser::dump::Dump;
+use crate::parser::expressions::ExprNode;
 use crate::parser::names::Qualified;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -61,6 +62,8 @@ pub enum BaseType {
     Class(Box<Class>),
     Function(Box<Function>),
     Array(Box<Array>),
+    Decltype(ExprNode),
+    DecltypeAuto,
 }
 
 impl ToString for BaseType {
@@ -76,6 +79,8 @@ impl ToString for BaseType {
             Class(_) => "class".to_string(),
             Function(_) => "function".to_string(),
             Array(_) => "array".to_string(),
+            Decltype(_) => "decltype".to_string(),
+            DecltypeAuto => "decltype(auto)".to_string(),
         }
     }
 }
@@ -97,6 +102,11 @@ impl Dump for BaseType {
             Self::Class(x) => dump!(x),
             Self::Function(x) => dump!(x),
             Self::Array(x) => dump!(x),
+            Self::Decltype(x) => {
+                let prefix = dump_start!(name, "decltype", prefix, last, stdout);
+                x.dump("expr", &prefix, true, stdout);
+            }
+            Self::DecltypeAuto => dump_str!(name, "decltype(auto)", Cyan, prefix, last, stdout),
         }
     }
 }